@@ -0,0 +1,80 @@
+//! Reference bootstrap server of the boolean layer.
+//!
+//! Threads enabling remote bootstrapping with `RemoteGates::enable_remote_bootstrap` send the
+//! bootstraps of their packed gates to this server, which computes them on the CPU, or on the FPGA
+//! with `--fpga` when it is built with the `fpga` feature.
+
+use clap::Parser;
+use std::thread;
+use std::time::Duration;
+use tfhe::boolean::server_key::{
+    BootstrapServer, REMOTE_BOOTSTRAP_DEFAULT_MAX_KEYS, REMOTE_BOOTSTRAP_DEFAULT_MAX_SESSIONS,
+    REMOTE_BOOTSTRAP_DEFAULT_READ_TIMEOUT,
+};
+
+#[derive(Parser)]
+#[command(about = "Serve the packed bootstraps of remote boolean server keys")]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    listen: String,
+    /// Maximum number of server keys stored, uploads of other keys being refused until clients
+    /// evict keys
+    #[arg(long, default_value_t = REMOTE_BOOTSTRAP_DEFAULT_MAX_KEYS)]
+    max_keys: usize,
+    /// Maximum number of connections served at the same time, further ones waiting to be accepted
+    #[arg(long, default_value_t = REMOTE_BOOTSTRAP_DEFAULT_MAX_SESSIONS)]
+    max_sessions: usize,
+    /// Seconds after which a silent connection is closed, 0 to never close it
+    #[arg(long, default_value_t = REMOTE_BOOTSTRAP_DEFAULT_READ_TIMEOUT.as_secs())]
+    read_timeout: u64,
+    /// Bootstrap on the FPGA named by FPGA_IMAGE and FPGA_INDEX, which only accepts keys with
+    /// DEMO_PARAMETERS
+    #[cfg(feature = "fpga")]
+    #[arg(long)]
+    fpga: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if args.max_sessions == 0 {
+        eprintln!("--max-sessions must be at least 1");
+        std::process::exit(1);
+    }
+
+    let read_timeout = (args.read_timeout > 0).then(|| Duration::from_secs(args.read_timeout));
+    let server = BootstrapServer::bind(args.listen.as_str())
+        .unwrap_or_else(|err| {
+            eprintln!("Cannot listen on {}: {err}", args.listen);
+            std::process::exit(1);
+        })
+        .with_max_keys(args.max_keys)
+        .with_max_sessions(args.max_sessions)
+        .with_read_timeout(read_timeout);
+    #[cfg(feature = "fpga")]
+    let server = if args.fpga {
+        server.with_fpga()
+    } else {
+        server
+    };
+    println!(
+        "Serving packed bootstraps on {}",
+        server.local_addr().unwrap()
+    );
+
+    // Every connection is served on its own thread, a failing one only ends itself. Accepting
+    // waits while --max-sessions connections are served
+    loop {
+        match server.serve_connection() {
+            Ok(session) => {
+                thread::spawn(move || {
+                    if let Err(err) = session.join().unwrap() {
+                        eprintln!("Connection closed: {err}");
+                    }
+                });
+            }
+            Err(err) => eprintln!("Cannot accept a connection: {err}"),
+        }
+    }
+}
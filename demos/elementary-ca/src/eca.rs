@@ -0,0 +1,7 @@
+pub mod formula;
+pub mod ring;
+pub mod rule;
+
+pub use formula::*;
+pub use ring::*;
+pub use rule::*;
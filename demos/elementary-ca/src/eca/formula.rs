@@ -0,0 +1,252 @@
+use std::fmt::{Display, Formatter};
+
+use tfhe::boolean::prelude::*;
+
+use super::rule::Rule;
+
+#[cfg(test)]
+mod tests;
+
+/// a cell of the neighbourhood of a cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable {
+  Left,
+  Centre,
+  Right,
+}
+
+impl Variable {
+  // truth table of the variable, whose bit `4 l + 2 c + r` is the value of the variable when
+  // the neighbourhood is `(l, c, r)`, as in the Wolfram codes
+  fn truth_table(&self) -> u8 {
+    match self {
+      Variable::Left => 0b1111_0000,
+      Variable::Centre => 0b1100_1100,
+      Variable::Right => 0b1010_1010,
+    }
+  }
+}
+
+/// a binary gate, computed with one bootstrap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+  And,
+  Or,
+  Xor,
+}
+
+impl Op {
+  const ALL: [Op; 3] = [Op::And, Op::Or, Op::Xor];
+
+  fn apply<T>(&self, a: T, b: T) -> T
+  where
+    T: std::ops::BitAnd<Output = T> + std::ops::BitOr<Output = T> + std::ops::BitXor<Output = T>,
+  {
+    match self {
+      Op::And => a & b,
+      Op::Or => a | b,
+      Op::Xor => a ^ b,
+    }
+  }
+}
+
+/// a boolean formula of the neighbourhood of a cell
+///
+/// Negations are free on encrypted bits, only the gates cost a bootstrap.
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_elementary_ca::eca::*;
+///
+/// let formula = Formula::synthesize(Rule(30));
+/// assert_eq!(formula.gate_count(), 2);
+/// assert_eq!(formula.to_string(), "l ^ (c | r)");
+/// assert!(formula.evaluate(false, true, false));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Formula {
+  Constant(bool),
+  Variable(Variable),
+  Not(Box<Formula>),
+  Gate(Op, Box<Formula>, Box<Formula>),
+}
+
+// how the cheapest formula of a truth table is obtained
+#[derive(Clone, Copy)]
+enum Origin {
+  Constant,
+  Variable(Variable),
+  Gate(Op, u8, u8),
+}
+
+impl Formula {
+  /// the formula of a rule with the fewest gates
+  ///
+  /// The formulas of all the truth tables of 3 variables are built by increasing number of
+  /// gates: the formulas with `n` gates combine two formulas with `n - 1` gates in total. Among
+  /// the formulas of a truth table with the fewest gates, the one with the fewest negations is
+  /// kept: negations cost no bootstrap, but make the formulas harder to read.
+  pub fn synthesize(rule: Rule) -> Formula {
+    // numbers of negations of the formulas found, and how they are obtained
+    let mut negations = [None::<usize>; 256];
+    let mut origin = [(Origin::Constant, false); 256];
+    // truth tables by number of gates of their formulas
+    let mut levels: Vec<Vec<u8>> = vec![];
+
+    let mut candidates = vec![(0, 0, Origin::Constant)];
+    for variable in [Variable::Left, Variable::Centre, Variable::Right] {
+      candidates.push((variable.truth_table(), 0, Origin::Variable(variable)));
+    }
+    while negations[rule.0 as usize].is_none() {
+      // keep the candidates of the truth tables not reached with fewer gates
+      let mut level = vec![];
+      for (table, n, o) in candidates {
+        for (table, n, negated) in [(table, n, false), (!table, n + 1, true)] {
+          match negations[table as usize] {
+            Some(current) if current <= n || !level.contains(&table) => {}
+            _ => {
+              if negations[table as usize].is_none() {
+                level.push(table);
+              }
+              negations[table as usize] = Some(n);
+              origin[table as usize] = (o, negated);
+            }
+          }
+        }
+      }
+      levels.push(level);
+
+      // combine the formulas into formulas with one more gate
+      let n_gates = levels.len();
+      candidates = vec![];
+      for i in 0..n_gates / 2 + n_gates % 2 {
+        for &a in &levels[i] {
+          for &b in &levels[n_gates - 1 - i] {
+            let n = negations[a as usize].unwrap() + negations[b as usize].unwrap();
+            for op in Op::ALL {
+              // the operand with the larger truth table first, so that `l` comes before `r`
+              let (a, b) = (a.max(b), a.min(b));
+              candidates.push((op.apply(a, b), n, Origin::Gate(op, a, b)));
+            }
+          }
+        }
+      }
+    }
+
+    // the operands of a gate are reached with fewer gates, which ends the recursion
+    fn build(table: u8, origin: &[(Origin, bool); 256]) -> Formula {
+      let (o, negated) = origin[table as usize];
+      let formula = match o {
+        Origin::Constant => return Formula::Constant(negated),
+        Origin::Variable(variable) => Formula::Variable(variable),
+        Origin::Gate(op, a, b) => {
+          Formula::Gate(op, Box::new(build(a, origin)), Box::new(build(b, origin)))
+        }
+      };
+      if negated {
+        Formula::Not(Box::new(formula))
+      } else {
+        formula
+      }
+    }
+    build(rule.0, &origin)
+  }
+
+  /// number of gates, which is the number of bootstraps to update one cell
+  pub fn gate_count(&self) -> usize {
+    match self {
+      Formula::Constant(_) | Formula::Variable(_) => 0,
+      Formula::Not(a) => a.gate_count(),
+      Formula::Gate(_, a, b) => a.gate_count() + b.gate_count() + 1,
+    }
+  }
+
+  /// evaluate the formula in the clear
+  pub fn evaluate(&self, left: bool, centre: bool, right: bool) -> bool {
+    match self {
+      Formula::Constant(value) => *value,
+      Formula::Variable(Variable::Left) => left,
+      Formula::Variable(Variable::Centre) => centre,
+      Formula::Variable(Variable::Right) => right,
+      Formula::Not(a) => !a.evaluate(left, centre, right),
+      Formula::Gate(op, a, b) => op.apply(
+        a.evaluate(left, centre, right),
+        b.evaluate(left, centre, right),
+      ),
+    }
+  }
+
+  /// evaluate the formula on a batch of encrypted cells, each gate with one packed bootstrap
+  ///
+  /// # Arguments
+  ///
+  /// * `server_key`: the server key
+  /// * `left`, `centre`, `right`: the encrypted states of the left neighbours, of the cells and
+  ///   of the right neighbours
+  /// * `zeros`: encryptions of `false`, at least one per cell, used by the constant rules
+  pub fn evaluate_packed(
+    &self,
+    server_key: &ServerKey,
+    left: &Vec<Ciphertext>,
+    centre: &Vec<Ciphertext>,
+    right: &Vec<Ciphertext>,
+    zeros: &[Ciphertext],
+  ) -> Vec<Ciphertext> {
+    let evaluate =
+      |formula: &Formula| formula.evaluate_packed(server_key, left, centre, right, zeros);
+    match self {
+      Formula::Constant(false) => zeros[..centre.len()].to_vec(),
+      Formula::Constant(true) => server_key.not_packed(&zeros[..centre.len()]),
+      Formula::Variable(Variable::Left) => left.clone(),
+      Formula::Variable(Variable::Centre) => centre.clone(),
+      Formula::Variable(Variable::Right) => right.clone(),
+      Formula::Not(a) => server_key.not_packed(&evaluate(a)),
+      Formula::Gate(op, a, b) => {
+        let (a, b) = (evaluate(a), evaluate(b));
+        match op {
+          Op::And => server_key.and_packed(&a, &b),
+          Op::Or => server_key.or_packed(&a, &b),
+          Op::Xor => server_key.xor_packed(&a, &b),
+        }
+      }
+    }
+  }
+
+  // write the formula, with parentheses around the gates of the operands
+  fn write(&self, f: &mut Formatter<'_>, operand: bool) -> std::fmt::Result {
+    match self {
+      Formula::Constant(value) => write!(f, "{}", *value as u8),
+      Formula::Variable(Variable::Left) => write!(f, "l"),
+      Formula::Variable(Variable::Centre) => write!(f, "c"),
+      Formula::Variable(Variable::Right) => write!(f, "r"),
+      Formula::Not(a) => {
+        write!(f, "!")?;
+        a.write(f, true)
+      }
+      Formula::Gate(op, a, b) => {
+        let symbol = match op {
+          Op::And => "&",
+          Op::Or => "|",
+          Op::Xor => "^",
+        };
+        if operand {
+          write!(f, "(")?;
+        }
+        a.write(f, true)?;
+        write!(f, " {symbol} ")?;
+        b.write(f, true)?;
+        if operand {
+          write!(f, ")")?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+impl Display for Formula {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    self.write(f, false)
+  }
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn test_synthesize_all_rules() {
+  for code in 0..=255u8 {
+    let rule = Rule(code);
+    let formula = Formula::synthesize(rule);
+    for row in 0..8 {
+      let (l, c, r) = (row & 4 != 0, row & 2 != 0, row & 1 != 0);
+      assert_eq!(
+        formula.evaluate(l, c, r),
+        rule.next_state(l, c, r),
+        "rule {code}: {formula} on {l} {c} {r}"
+      );
+    }
+  }
+}
+
+#[test]
+fn test_gate_counts() {
+  // constants, single cells and their negations need no bootstrap
+  for code in [0, 255, 204, 51, 170, 85, 240, 15] {
+    assert_eq!(
+      Formula::synthesize(Rule(code)).gate_count(),
+      0,
+      "rule {code}"
+    );
+  }
+  assert_eq!(Formula::synthesize(Rule(0)), Formula::Constant(false));
+  assert_eq!(Formula::synthesize(Rule(255)), Formula::Constant(true));
+  assert_eq!(Formula::synthesize(Rule(51)).to_string(), "!c");
+
+  // rule 90 is l ^ r, rule 150 is l ^ c ^ r
+  assert_eq!(Formula::synthesize(Rule(90)).gate_count(), 1);
+  assert_eq!(Formula::synthesize(Rule(150)).gate_count(), 2);
+  assert_eq!(Formula::synthesize(Rule(30)).gate_count(), 2);
+  assert_eq!(Formula::synthesize(Rule(110)).gate_count(), 3);
+
+  // no rule needs more than a few gates
+  let max = (0..=255u8)
+    .map(|code| Formula::synthesize(Rule(code)).gate_count())
+    .max()
+    .unwrap();
+  assert!(max <= 6, "{max}");
+}
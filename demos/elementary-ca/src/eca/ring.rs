@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use tfhe::boolean::prelude::*;
+
+use super::formula::Formula;
+use super::rule::Rule;
+
+#[cfg(test)]
+mod tests;
+
+/// an encrypted ring of cells, evolved by an elementary rule
+///
+/// The last cell is the left neighbour of the first one.
+///
+/// # Fields
+///
+/// * `states`: encrypted states of the cells
+/// * `rule`: the rule of the automaton
+/// * `formula`: the formula synthesized from the rule, evaluated on every cell
+/// * `generation`: number of generations computed since the initial configuration
+/// * `tick_time`: duration of the last update
+#[derive(Clone)]
+pub struct Ring {
+  pub states: Vec<Ciphertext>,
+  pub rule: Rule,
+  pub formula: Formula,
+  pub generation: usize,
+  pub tick_time: Duration,
+}
+
+impl Ring {
+  /// create a new ring
+  ///
+  /// # Arguments
+  ///
+  /// * `rule`: the rule of the automaton
+  /// * `states`: encrypted states of the cells in the initial configuration
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use homomorphic_elementary_ca::eca::*;
+  /// use tfhe::boolean::prelude::*;
+  ///
+  /// let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  /// let server_key = ServerKey::new(&client_key);
+  /// let zeros: Vec<Ciphertext> =
+  ///     (0..FPGA_BOOTSTRAP_PACKING).map(|_| client_key.encrypt(false)).collect();
+  ///
+  /// // a single live cell
+  /// let initial_state = [false, false, true, false, false];
+  /// let states = initial_state.iter().map(|&s| client_key.encrypt(s)).collect();
+  /// let mut ring = Ring::new(Rule(30), states);
+  ///
+  /// ring.update(&server_key, &zeros);
+  /// let states: Vec<bool> = ring.states.iter().map(|s| client_key.decrypt(s)).collect();
+  /// assert_eq!(states, Rule(30).step(&initial_state));
+  /// ```
+  pub fn new(rule: Rule, states: Vec<Ciphertext>) -> Ring {
+    assert!(!states.is_empty(), "a ring holds at least one cell");
+    Ring {
+      states,
+      rule,
+      formula: Formula::synthesize(rule),
+      generation: 0,
+      tick_time: Duration::default(),
+    }
+  }
+
+  /// compute the next generation
+  ///
+  /// The cells are updated in batches of `FPGA_BOOTSTRAP_PACKING`, each gate of the formula
+  /// costing one packed bootstrap per batch.
+  ///
+  /// # Arguments
+  ///
+  /// * `server_key`: the server key
+  /// * `zeros`: `FPGA_BOOTSTRAP_PACKING` encryptions of `false`
+  pub fn update(&mut self, server_key: &ServerKey, zeros: &[Ciphertext]) {
+    let now = Instant::now();
+    let n = self.states.len();
+    let mut new_states = Vec::with_capacity(n);
+    for start in (0..n).step_by(FPGA_BOOTSTRAP_PACKING) {
+      let batch = start..(start + FPGA_BOOTSTRAP_PACKING).min(n);
+      let cells = |offset: usize| -> Vec<Ciphertext> {
+        batch
+          .clone()
+          .map(|k| self.states[(k + offset) % n].clone())
+          .collect()
+      };
+      new_states.extend(self.formula.evaluate_packed(
+        server_key,
+        &cells(n - 1),
+        &cells(0),
+        &cells(1),
+        zeros,
+      ));
+    }
+    self.states = new_states;
+    self.generation += 1;
+    self.tick_time = now.elapsed();
+  }
+}
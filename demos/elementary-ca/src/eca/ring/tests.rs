@@ -0,0 +1,80 @@
+use super::*;
+
+fn keys() -> (ClientKey, ServerKey, Vec<Ciphertext>) {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+  let zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+  (client_key, server_key, zeros)
+}
+
+fn decrypt(client_key: &ClientKey, ring: &Ring) -> Vec<bool> {
+  ring.states.iter().map(|s| client_key.decrypt(s)).collect()
+}
+
+#[test]
+fn test_all_rules() {
+  let (client_key, server_key, zeros) = keys();
+
+  // in rings of one to three cells, a cell is its own neighbour or its neighbours are the same
+  let initial_states: [&[bool]; 4] = [
+    &[true],
+    &[true, false],
+    &[false, true, true],
+    &[false, true, true, false, true],
+  ];
+
+  for initial_state in initial_states {
+    let width = initial_state.len();
+    for code in 0..=255u8 {
+      let rule = Rule(code);
+      let states = initial_state
+        .iter()
+        .map(|&s| client_key.encrypt(s))
+        .collect();
+      let mut ring = Ring::new(rule, states);
+      let mut expected = initial_state.to_vec();
+      for generation in 1..=2 {
+        ring.update(&server_key, &zeros);
+        expected = rule.step(&expected);
+        assert_eq!(
+          decrypt(&client_key, &ring),
+          expected,
+          "rule {code}, width {width}, generation {generation}"
+        );
+      }
+      assert_eq!(ring.generation, 2);
+    }
+  }
+}
+
+#[test]
+fn test_batches() {
+  let (client_key, server_key, zeros) = keys();
+
+  // a ring wider than two batches, with the neighbours of the first and last cells in other
+  // batches
+  let width = 2 * FPGA_BOOTSTRAP_PACKING + 4;
+  let initial_state: Vec<bool> = (0..width).map(|k| k % 3 == 0 || k % 7 == 1).collect();
+
+  for rule in [Rule(30), Rule(110)] {
+    let states = initial_state
+      .iter()
+      .map(|&s| client_key.encrypt(s))
+      .collect();
+    let mut ring = Ring::new(rule, states);
+    let mut expected = initial_state.clone();
+    for _ in 0..4 {
+      server_key.reset_packed_bootstrap_stats();
+      ring.update(&server_key, &zeros);
+      expected = rule.step(&expected);
+      assert_eq!(decrypt(&client_key, &ring), expected, "rule {rule}");
+
+      // one packed bootstrap per gate and per cell, in three batches
+      let stats = server_key.packed_bootstrap_stats();
+      assert_eq!(stats.bootstraps as usize, ring.formula.gate_count() * width);
+      assert_eq!(stats.batches as usize, ring.formula.gate_count() * 3);
+    }
+  }
+}
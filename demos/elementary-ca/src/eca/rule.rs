@@ -0,0 +1,85 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+/// an elementary cellular automaton rule, given by its Wolfram code
+///
+/// The next state of a cell depends on its state and on the states of its left and right
+/// neighbours: it is bit `4 l + 2 c + r` of the code, where `l`, `c` and `r` are the states of
+/// the left neighbour, of the cell, and of the right neighbour.
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_elementary_ca::eca::*;
+///
+/// let rule: Rule = "30".parse().unwrap();
+/// assert!(rule.next_state(true, false, false));
+/// assert!(!rule.next_state(true, true, true));
+///
+/// // a single live cell on a ring of 5 cells
+/// assert_eq!(
+///   rule.step(&[false, false, true, false, false]),
+///   [false, true, true, true, false]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rule(pub u8);
+
+/// error returned when parsing a rule which is not a number from 0 to 255
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleError(pub String);
+
+impl Display for RuleError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "invalid rule \"{}\", expected a Wolfram code from 0 to 255",
+      self.0
+    )
+  }
+}
+
+impl std::error::Error for RuleError {}
+
+impl Rule {
+  /// the next state of a cell
+  ///
+  /// # Arguments
+  ///
+  /// * `left`, `centre`, `right`: the states of the left neighbour, of the cell and of the right
+  ///   neighbour
+  pub fn next_state(&self, left: bool, centre: bool, right: bool) -> bool {
+    let row = (left as u8) << 2 | (centre as u8) << 1 | right as u8;
+    (self.0 >> row) & 1 == 1
+  }
+
+  /// evolve a plaintext ring of cells by one generation
+  ///
+  /// This is the reference the encrypted rings are checked against.
+  pub fn step(&self, states: &[bool]) -> Vec<bool> {
+    let n = states.len();
+    (0..n)
+      .map(|k| self.next_state(states[(k + n - 1) % n], states[k], states[(k + 1) % n]))
+      .collect()
+  }
+}
+
+impl FromStr for Rule {
+  type Err = RuleError;
+
+  fn from_str(s: &str) -> Result<Rule, RuleError> {
+    s.trim()
+      .parse::<u8>()
+      .map(Rule)
+      .map_err(|_| RuleError(s.to_string()))
+  }
+}
+
+impl Display for Rule {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
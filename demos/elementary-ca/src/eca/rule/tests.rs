@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn test_next_state() {
+  // rule 110: 01101110
+  let rule = Rule(110);
+  let expected = [false, true, true, true, false, true, true, false];
+  for (row, &state) in expected.iter().enumerate() {
+    let (l, c, r) = (row & 4 != 0, row & 2 != 0, row & 1 != 0);
+    assert_eq!(rule.next_state(l, c, r), state, "{l} {c} {r}");
+  }
+
+  // rule 204 is the identity, rule 51 the negation
+  for row in 0..8 {
+    let (l, c, r) = (row & 4 != 0, row & 2 != 0, row & 1 != 0);
+    assert_eq!(Rule(204).next_state(l, c, r), c);
+    assert_eq!(Rule(51).next_state(l, c, r), !c);
+  }
+}
+
+#[test]
+fn test_step_ring() {
+  // rule 90 is the xor of the neighbours, which wraps around the ring
+  let states = [true, false, false, false, false, false];
+  assert_eq!(
+    Rule(90).step(&states),
+    [false, true, false, false, false, true]
+  );
+
+  // rule 170 shifts the cells to the left
+  let states = [true, true, false, true, false];
+  assert_eq!(Rule(170).step(&states), [true, false, true, false, true]);
+
+  // a ring of one cell is its own neighbour
+  assert_eq!(Rule(128).step(&[true]), [true]);
+  assert_eq!(Rule(127).step(&[true]), [false]);
+}
+
+#[test]
+fn test_parse_rules() {
+  assert_eq!("30".parse(), Ok(Rule(30)));
+  assert_eq!(" 255".parse(), Ok(Rule(255)));
+  assert_eq!(Rule(110).to_string().parse(), Ok(Rule(110)));
+  for invalid in ["256", "-1", "rule 30", ""] {
+    assert_eq!(invalid.parse::<Rule>(), Err(RuleError(invalid.to_string())));
+  }
+}
@@ -0,0 +1,104 @@
+use std::error::Error;
+
+use clap::Parser;
+use homomorphic_elementary_ca::eca::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use tfhe::boolean::prelude::*;
+#[cfg(feature = "fpga")]
+use tfhe::boolean::server_key::FpgaGates;
+
+/// Homomorphic elementary cellular automata: evolves an encrypted ring of cells with any of the
+/// 256 Wolfram rules, printing every generation and checking it against a plaintext simulation
+#[derive(Parser)]
+struct Args {
+  /// Wolfram code of the rule, from 0 to 255
+  #[arg(long, default_value_t = Rule(30))]
+  rule: Rule,
+
+  /// number of cells of the ring
+  #[arg(long, default_value_t = 64)]
+  width: usize,
+
+  /// number of generations to compute
+  #[arg(long, default_value_t = 32)]
+  generations: usize,
+
+  /// seed of a random initial configuration; defaults to a single live cell in the middle
+  #[arg(long)]
+  seed: Option<u64>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+  let args = Args::parse();
+  if args.width == 0 {
+    return Err("the ring needs at least one cell".into());
+  }
+
+  // initial configuration
+  let initial_state: Vec<bool> = match args.seed {
+    Some(seed) => {
+      let mut rng = ChaCha8Rng::seed_from_u64(seed);
+      (0..args.width).map(|_| rng.gen()).collect()
+    }
+    None => (0..args.width).map(|k| k == args.width / 2).collect(),
+  };
+
+  // generate the keys
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+
+  // encrypt false once per cell of a batch
+  let zeros: Vec<Ciphertext> = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+
+  // encrypt the initial configuration
+  let states: Vec<Ciphertext> = initial_state
+    .iter()
+    .map(|&x| client_key.encrypt(x))
+    .collect();
+  let mut ring = Ring::new(args.rule, states);
+  println!(
+    "rule {}: {}, bootstraps per cell: {}",
+    ring.rule,
+    ring.formula,
+    ring.formula.gate_count()
+  );
+
+  #[cfg(feature = "fpga")]
+  server_key.enable_fpga()?;
+
+  let render =
+    |states: &[bool]| -> String { states.iter().map(|&s| if s { '#' } else { '.' }).collect() };
+  println!("{}", render(&initial_state));
+
+  let mut expected = initial_state;
+  for _ in 0..args.generations {
+    server_key.reset_packed_bootstrap_stats();
+    ring.update(&server_key, &zeros);
+    let stats = server_key.packed_bootstrap_stats();
+
+    let states: Vec<bool> = ring.states.iter().map(|s| client_key.decrypt(s)).collect();
+    expected = ring.rule.step(&expected);
+    println!(
+      "{}  {:>8.2?}  {} bootstraps",
+      render(&states),
+      ring.tick_time,
+      stats.bootstraps
+    );
+    if states != expected {
+      return Err(
+        format!(
+          "generation {} differs from the plaintext simulation:\n{}",
+          ring.generation,
+          render(&expected)
+        )
+        .into(),
+      );
+    }
+  }
+
+  Ok(())
+}
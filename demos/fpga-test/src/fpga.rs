@@ -1,73 +1,101 @@
-#![allow(non_upper_case_globals)]
-#![allow(non_camel_case_types)]
-#![allow(non_snake_case)]
-// Supress not FFI-safe warning
-#![allow(improper_ctypes)]
+use std::process::ExitCode;
 
-use tfhe::boolean::engine::BooleanEngine;
-use tfhe::boolean::prelude::*;
+use clap::Parser;
 
-use rand::{Rng, SeedableRng};
-
-use std::iter::zip;
 #[cfg(feature = "fpga")]
 use tfhe::boolean::server_key::FpgaGates;
 
-fn main() {
-  const TEST_COUNT: u32 = 10;
-
-  let mut boolean_engine = BooleanEngine::new();
-
-  let client_key = boolean_engine.create_client_key(DEMO_PARAMETERS);
-
-  // generate the server key, only the SW needs this
-  let server_key = boolean_engine.create_server_key(&client_key);
-
-  #[cfg(feature = "fpga")]
-  server_key.enable_fpga();
-
-  let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
-
-  for test in 0..TEST_COUNT {
-    let mut lefts = Vec::<bool>::new();
-    let mut rights = Vec::<bool>::new();
-    let mut outputs = Vec::<bool>::new();
-
-    let mut lefts_ct = Vec::<Ciphertext>::new();
-    let mut rights_ct = Vec::<Ciphertext>::new();
-
-    for _ in 0..FPGA_BOOTSTRAP_PACKING {
-      let l = rng.gen_range(0..=1) != 0;
-      let r = rng.gen_range(0..=1) != 0;
-
-      let l_ct = boolean_engine.encrypt(l, &client_key);
-      let r_ct = boolean_engine.encrypt(r, &client_key);
-
-      lefts.push(l);
-      rights.push(r);
-      outputs.push(l && r);
+mod report;
+mod suite;
+
+use report::CaseReport;
+use suite::{Case, PbsOrder, Suite};
+
+/// Conformance suite of the packed gates: checks every gate, partial batches, trivial operands
+/// and long chains of gates in both PBS orders against a plaintext reference, and reports the
+/// error rates, the throughput and the latencies of the batches
+///
+/// Built with the fpga feature, the batches are bootstrapped by the card or the hardware
+/// emulator opened from the FPGA_IMAGE and FPGA_INDEX variables; otherwise they run on the CPU.
+/// The exit code is non-zero when a case fails.
+#[derive(Parser)]
+struct Args {
+  /// seed of the operands and gates
+  #[arg(long, default_value_t = 0)]
+  seed: u64,
+
+  /// number of batches of the cases with random batches
+  #[arg(long, default_value_t = 100)]
+  batches: usize,
+
+  /// number of chained gates of the chain case
+  #[arg(long, default_value_t = 1000)]
+  chain_length: usize,
+
+  /// case to run, can be repeated; defaults to all the cases
+  #[arg(long = "case", value_enum)]
+  cases: Vec<Case>,
+
+  /// PBS order to run the cases in, can be repeated; defaults to both orders
+  #[arg(long = "pbs-order", value_enum)]
+  pbs_orders: Vec<PbsOrder>,
+
+  /// largest fraction of wrong output bits for a case to pass
+  #[arg(long, default_value_t = 0.)]
+  max_error_rate: f64,
+
+  /// print one line of JSON per case instead of a table
+  #[arg(long)]
+  json: bool,
+}
 
-      lefts_ct.push(l_ct);
-      rights_ct.push(r_ct);
+fn main() -> ExitCode {
+  let args = Args::parse();
+  let cases = if args.cases.is_empty() {
+    Case::ALL.to_vec()
+  } else {
+    args.cases.clone()
+  };
+  let pbs_orders = if args.pbs_orders.is_empty() {
+    PbsOrder::ALL.to_vec()
+  } else {
+    args.pbs_orders.clone()
+  };
+
+  if !args.json {
+    println!("{}", CaseReport::header());
+  }
+  let mut failed = 0;
+  for pbs_order in pbs_orders {
+    let mut suite = Suite::new(pbs_order, args.seed, args.batches, args.chain_length);
+
+    #[cfg(feature = "fpga")]
+    if let Err(err) = suite.server_key.enable_fpga() {
+      eprintln!("cannot enable the FPGA: {err}");
+      return ExitCode::FAILURE;
     }
 
-    let outputs_ct = server_key.and_packed(&lefts_ct, &rights_ct);
-
-    let mut success = true;
-    for (output, ct_output) in zip(outputs, outputs_ct) {
-      let expected: bool = output;
-      let calculated: bool = client_key.decrypt(&ct_output);
-
-      if expected != calculated {
-        success = false;
+    for &case in &cases {
+      let report = suite.run(case);
+      if args.json {
+        println!("{}", serde_json::to_string(&report).unwrap());
+      } else {
+        println!("{report}");
+      }
+      if !report.passed(args.max_error_rate) {
+        failed += 1;
       }
     }
 
-    if success {
-      println!("TEST {} PASSED", test);
-    } else {
-      println!("TEST {} FAILED", test);
-    }
+    #[cfg(feature = "fpga")]
+    suite.server_key.disable_fpga();
   }
 
+  if failed > 0 {
+    eprintln!("FAILED: {failed} case(s)");
+    ExitCode::FAILURE
+  } else {
+    eprintln!("PASSED");
+    ExitCode::SUCCESS
+  }
 }
@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// distribution of the latencies of the batches of a case, in microseconds
+///
+/// The percentiles are nearest-rank percentiles of the recorded latencies.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LatencySummary {
+  pub min_us: f64,
+  pub p50_us: f64,
+  pub p90_us: f64,
+  pub p99_us: f64,
+  pub max_us: f64,
+  pub mean_us: f64,
+}
+
+impl LatencySummary {
+  /// summarize the latencies of a case, `None` when no batch was timed
+  pub fn new(latencies: &[Duration]) -> Option<LatencySummary> {
+    if latencies.is_empty() {
+      return None;
+    }
+    let mut sorted: Vec<f64> = latencies
+      .iter()
+      .map(|latency| latency.as_secs_f64() * 1e6)
+      .collect();
+    sorted.sort_by(f64::total_cmp);
+    let percentile = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+
+    Some(LatencySummary {
+      min_us: sorted[0],
+      p50_us: percentile(0.5),
+      p90_us: percentile(0.9),
+      p99_us: percentile(0.99),
+      max_us: sorted[sorted.len() - 1],
+      mean_us: sorted.iter().sum::<f64>() / sorted.len() as f64,
+    })
+  }
+}
+
+/// result of one case of the conformance suite, for one PBS order
+///
+/// # Fields
+///
+/// * `case`, `pbs_order`: what was tested
+/// * `device`: `fpga` when the batches were bootstrapped by the accelerator, `cpu` otherwise, `-`
+///   when the case computed no bootstrap
+/// * `batches`: number of packed calls
+/// * `checked`: number of output bits decrypted and compared with the plaintext reference
+/// * `errors`: number of wrong output bits
+/// * `bootstraps`: number of bootstraps computed, as counted by the server key
+/// * `fpga_batches`: number of batches bootstrapped by the accelerator
+/// * `bootstraps_per_second`: bootstraps over the total latency of the batches
+/// * `latency`: distribution of the latencies of the batches
+/// * `panic`: message of the panic which aborted the case, if any
+#[derive(Clone, Debug, Serialize)]
+pub struct CaseReport {
+  pub case: String,
+  pub pbs_order: String,
+  pub device: &'static str,
+  pub batches: usize,
+  pub checked: u64,
+  pub errors: u64,
+  pub error_rate: f64,
+  pub bootstraps: u64,
+  pub fpga_batches: u64,
+  pub bootstraps_per_second: f64,
+  pub latency: Option<LatencySummary>,
+  pub panic: Option<String>,
+}
+
+impl CaseReport {
+  /// whether the case passed: it ran to completion with at most `max_error_rate` wrong bits
+  pub fn passed(&self, max_error_rate: f64) -> bool {
+    self.panic.is_none() && self.error_rate <= max_error_rate
+  }
+
+  /// header of the table written by the [`Display`] implementation
+  pub fn header() -> String {
+    format!(
+      "{:<20} {:<8} {:<6} {:>8} {:>9} {:>7} {:>10} {:>13}  {}",
+      "pbs order",
+      "case",
+      "device",
+      "batches",
+      "bits",
+      "errors",
+      "error rate",
+      "bootstraps/s",
+      "latency min/p50/p90/p99/max (µs)"
+    )
+  }
+}
+
+impl Display for CaseReport {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{:<20} {:<8} {:<6} {:>8} {:>9} {:>7} {:>10.2e} {:>13.0}  ",
+      self.pbs_order,
+      self.case,
+      self.device,
+      self.batches,
+      self.checked,
+      self.errors,
+      self.error_rate,
+      self.bootstraps_per_second
+    )?;
+    match &self.latency {
+      Some(l) => write!(
+        f,
+        "{:.0}/{:.0}/{:.0}/{:.0}/{:.0}",
+        l.min_us, l.p50_us, l.p90_us, l.p99_us, l.max_us
+      )?,
+      None => write!(f, "-")?,
+    }
+    if let Some(panic) = &self.panic {
+      write!(f, "  PANICKED: {panic}")?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,322 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use tfhe::boolean::engine::Gate;
+use tfhe::boolean::prelude::*;
+
+use crate::report::{CaseReport, LatencySummary};
+
+/// the cases of the conformance suite
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Case {
+  /// full batches of `and_packed`
+  And,
+  /// full batches of `or_packed`
+  Or,
+  /// full batches of `xor_packed`
+  Xor,
+  /// full batches of `gates_packed`, with a random gate in every slot
+  Mixed,
+  /// full batches of `not_packed`, which does not bootstrap
+  Not,
+  /// batches of every size from 1 to `FPGA_BOOTSTRAP_PACKING`
+  Partial,
+  /// batches mixing trivial and encrypted operands, starting with a batch of trivial operands
+  Trivial,
+  /// full batches of chained gates, each fed with the outputs of the previous one
+  Chain,
+}
+
+impl Case {
+  pub const ALL: [Case; 8] = [
+    Case::And,
+    Case::Or,
+    Case::Xor,
+    Case::Mixed,
+    Case::Not,
+    Case::Partial,
+    Case::Trivial,
+    Case::Chain,
+  ];
+}
+
+/// the order of the bootstrap and of the keyswitch, picked by the encryption key of the
+/// parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PbsOrder {
+  /// ciphertexts under the small key, bootstrapped then keyswitched back
+  BootstrapKeyswitch,
+  /// ciphertexts under the large key, keyswitched then bootstrapped
+  KeyswitchBootstrap,
+}
+
+impl PbsOrder {
+  pub const ALL: [PbsOrder; 2] = [PbsOrder::BootstrapKeyswitch, PbsOrder::KeyswitchBootstrap];
+
+  /// the demo parameters, with the encryption key of the order
+  pub fn parameters(&self) -> BooleanParameters {
+    let encryption_key_choice = match self {
+      PbsOrder::BootstrapKeyswitch => EncryptionKeyChoice::Small,
+      PbsOrder::KeyswitchBootstrap => EncryptionKeyChoice::Big,
+    };
+    BooleanParameters {
+      encryption_key_choice,
+      ..DEMO_PARAMETERS
+    }
+  }
+}
+
+fn name(value: impl ValueEnum) -> String {
+  value.to_possible_value().unwrap().get_name().to_string()
+}
+
+fn evaluate(gate: &Gate, lhs: bool, rhs: bool) -> bool {
+  match gate {
+    Gate::AND => lhs && rhs,
+    Gate::OR => lhs || rhs,
+    Gate::XOR => lhs ^ rhs,
+  }
+}
+
+/// the keys and the settings of a run of the suite, for one PBS order
+///
+/// # Fields
+///
+/// * `pbs_order`: the PBS order of the keys
+/// * `batches`: number of batches of the cases with random batches
+/// * `chain_length`: number of gates of the chains
+pub struct Suite {
+  pub pbs_order: PbsOrder,
+  pub client_key: ClientKey,
+  pub server_key: ServerKey,
+  pub batches: usize,
+  pub chain_length: usize,
+  rng: ChaCha8Rng,
+}
+
+// outcomes of the batches of a case
+#[derive(Default)]
+struct Recorder {
+  checked: u64,
+  errors: u64,
+  latencies: Vec<Duration>,
+}
+
+impl Recorder {
+  // time a packed call
+  fn time<T>(&mut self, call: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = call();
+    self.latencies.push(start.elapsed());
+    result
+  }
+
+  // compare decrypted outputs with their plaintext reference
+  fn check(&mut self, client_key: &ClientKey, outputs: &[Ciphertext], expected: &[bool]) {
+    assert_eq!(outputs.len(), expected.len(), "wrong number of outputs");
+    for (output, &expected) in outputs.iter().zip(expected) {
+      self.checked += 1;
+      if client_key.decrypt(output) != expected {
+        self.errors += 1;
+      }
+    }
+  }
+}
+
+impl Suite {
+  /// generate the keys of a PBS order
+  ///
+  /// # Arguments
+  ///
+  /// * `pbs_order`: the PBS order of the keys
+  /// * `seed`: seed of the operands and gates of the cases
+  /// * `batches`: number of batches of the cases with random batches
+  /// * `chain_length`: number of gates of the chains
+  pub fn new(pbs_order: PbsOrder, seed: u64, batches: usize, chain_length: usize) -> Suite {
+    let client_key = ClientKey::new(&pbs_order.parameters());
+    let server_key = ServerKey::new(&client_key);
+    Suite {
+      pbs_order,
+      client_key,
+      server_key,
+      batches,
+      chain_length,
+      rng: ChaCha8Rng::seed_from_u64(seed),
+    }
+  }
+
+  /// run a case, catching the panics of the packed gates to report them
+  pub fn run(&mut self, case: Case) -> CaseReport {
+    let mut recorder = Recorder::default();
+    self.server_key.reset_packed_bootstrap_stats();
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| self.run_case(case, &mut recorder)));
+    let stats = self.server_key.packed_bootstrap_stats();
+
+    let elapsed: Duration = recorder.latencies.iter().sum();
+    CaseReport {
+      case: name(case),
+      pbs_order: name(self.pbs_order),
+      device: match (stats.bootstraps, stats.fpga_batches) {
+        (0, _) => "-",
+        (_, 0) => "cpu",
+        _ => "fpga",
+      },
+      batches: recorder.latencies.len(),
+      checked: recorder.checked,
+      errors: recorder.errors,
+      error_rate: if recorder.checked == 0 {
+        0.
+      } else {
+        recorder.errors as f64 / recorder.checked as f64
+      },
+      bootstraps: stats.bootstraps,
+      fpga_batches: stats.fpga_batches,
+      bootstraps_per_second: if elapsed.is_zero() {
+        0.
+      } else {
+        stats.bootstraps as f64 / elapsed.as_secs_f64()
+      },
+      latency: LatencySummary::new(&recorder.latencies),
+      panic: outcome.err().map(|payload| {
+        payload
+          .downcast_ref::<String>()
+          .cloned()
+          .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+          .unwrap_or_else(|| "unknown panic".to_string())
+      }),
+    }
+  }
+
+  fn run_case(&mut self, case: Case, recorder: &mut Recorder) {
+    match case {
+      Case::And | Case::Or | Case::Xor => {
+        let gate = match case {
+          Case::And => Gate::AND,
+          Case::Or => Gate::OR,
+          _ => Gate::XOR,
+        };
+        for _ in 0..self.batches {
+          let (lhs, ct_lhs) = self.operands(FPGA_BOOTSTRAP_PACKING, false);
+          let (rhs, ct_rhs) = self.operands(FPGA_BOOTSTRAP_PACKING, false);
+          let server_key = &self.server_key;
+          let outputs = recorder.time(|| match gate {
+            Gate::AND => server_key.and_packed(&ct_lhs, &ct_rhs),
+            Gate::OR => server_key.or_packed(&ct_lhs, &ct_rhs),
+            Gate::XOR => server_key.xor_packed(&ct_lhs, &ct_rhs),
+          });
+          let expected: Vec<bool> = (0..lhs.len())
+            .map(|i| evaluate(&gate, lhs[i], rhs[i]))
+            .collect();
+          recorder.check(&self.client_key, &outputs, &expected);
+        }
+      }
+      Case::Mixed => {
+        for _ in 0..self.batches {
+          self.random_batch(FPGA_BOOTSTRAP_PACKING, false, recorder);
+        }
+      }
+      Case::Not => {
+        for _ in 0..self.batches {
+          let (inputs, ct_inputs) = self.operands(FPGA_BOOTSTRAP_PACKING, false);
+          let outputs = recorder.time(|| self.server_key.not_packed(&ct_inputs));
+          let expected: Vec<bool> = inputs.iter().map(|input| !input).collect();
+          recorder.check(&self.client_key, &outputs, &expected);
+        }
+      }
+      Case::Partial => {
+        for len in 1..=FPGA_BOOTSTRAP_PACKING {
+          for _ in 0..(self.batches / FPGA_BOOTSTRAP_PACKING).max(1) {
+            self.random_batch(len, false, recorder);
+          }
+        }
+      }
+      Case::Trivial => {
+        // a batch of trivial operands only, which needs no bootstrap
+        let trivial = [true; FPGA_BOOTSTRAP_PACKING];
+        let ct_trivial: Vec<Ciphertext> = trivial
+          .iter()
+          .map(|&b| self.server_key.trivial_encrypt(b))
+          .collect();
+        let outputs = recorder.time(|| self.server_key.xor_packed(&ct_trivial, &ct_trivial));
+        recorder.check(&self.client_key, &outputs, &[false; FPGA_BOOTSTRAP_PACKING]);
+
+        for _ in 1..self.batches {
+          let len = self.rng.gen_range(1..=FPGA_BOOTSTRAP_PACKING);
+          self.random_batch(len, true, recorder);
+        }
+      }
+      Case::Chain => {
+        // every gate takes the outputs of the previous one, so that a noise growth across
+        // bootstraps would eventually flip bits; the reference is resynchronized with the
+        // decrypted outputs after every gate, so that every wrong bit is counted once
+        let (mut states, mut ct_states) = self.operands(FPGA_BOOTSTRAP_PACKING, false);
+        for _ in 0..self.chain_length {
+          let gates: Vec<Gate> = (0..FPGA_BOOTSTRAP_PACKING)
+            .map(|_| self.random_gate())
+            .collect();
+          let mut ct_rotated = ct_states.clone();
+          ct_rotated.rotate_left(1);
+          ct_states = recorder.time(|| {
+            self
+              .server_key
+              .gates_packed(&gates, &ct_states, &ct_rotated)
+          });
+          let expected: Vec<bool> = (0..FPGA_BOOTSTRAP_PACKING)
+            .map(|i| {
+              evaluate(
+                &gates[i],
+                states[i],
+                states[(i + 1) % FPGA_BOOTSTRAP_PACKING],
+              )
+            })
+            .collect();
+          recorder.check(&self.client_key, &ct_states, &expected);
+          states = ct_states
+            .iter()
+            .map(|ct| self.client_key.decrypt(ct))
+            .collect();
+        }
+      }
+    }
+  }
+
+  fn random_gate(&mut self) -> Gate {
+    match self.rng.gen_range(0..3) {
+      0 => Gate::AND,
+      1 => Gate::OR,
+      _ => Gate::XOR,
+    }
+  }
+
+  // random operands, each trivial with probability 1/2 when `trivial` is set
+  fn operands(&mut self, len: usize, trivial: bool) -> (Vec<bool>, Vec<Ciphertext>) {
+    let values: Vec<bool> = (0..len).map(|_| self.rng.gen()).collect();
+    let ciphertexts = values
+      .iter()
+      .map(|&value| {
+        if trivial && self.rng.gen() {
+          self.server_key.trivial_encrypt(value)
+        } else {
+          self.client_key.encrypt(value)
+        }
+      })
+      .collect();
+    (values, ciphertexts)
+  }
+
+  // a batch of random gates on random operands
+  fn random_batch(&mut self, len: usize, trivial: bool, recorder: &mut Recorder) {
+    let gates: Vec<Gate> = (0..len).map(|_| self.random_gate()).collect();
+    let (lhs, ct_lhs) = self.operands(len, trivial);
+    let (rhs, ct_rhs) = self.operands(len, trivial);
+    let outputs = recorder.time(|| self.server_key.gates_packed(&gates, &ct_lhs, &ct_rhs));
+    let expected: Vec<bool> = (0..len)
+      .map(|i| evaluate(&gates[i], lhs[i], rhs[i]))
+      .collect();
+    recorder.check(&self.client_key, &outputs, &expected);
+  }
+}
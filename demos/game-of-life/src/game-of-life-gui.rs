@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::Parser;
+use homomorphic_game_of_life::gol::*;
+
+use tfhe::boolean::prelude::*;
+#[cfg(feature = "fpga")]
+use tfhe::boolean::server_key::FpgaGates;
+
+/// Graphical frontend of the homomorphic Game of Life: evolves an encrypted board in this
+/// process, on the accelerator when built with the fpga feature, and shows it in a window
+#[derive(Parser)]
+struct Args {
+  /// config file of the window and of the board size, see the readme
+  #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/gui.config"))]
+  config: PathBuf,
+
+  /// rule of the game, in B/S notation; defaults to the rule of the pattern, or to Conway's
+  /// B3/S23
+  #[arg(long)]
+  rule: Option<Rule>,
+
+  /// initial pattern, a .rle, .cells or 0/1 grid file, placed in the middle of the board
+  #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/initial_state"))]
+  pattern: PathBuf,
+
+  /// what lies beyond the edges of the board: torus, dead or mirror
+  #[arg(long, default_value_t = Boundary::Torus)]
+  boundary: Boundary,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+  let args = Args::parse();
+
+  let config =
+    Config::read(&args.config).map_err(|err| format!("{}: {err}", args.config.display()))?;
+
+  // read the pattern and place it in the board
+  let pattern =
+    Pattern::read(&args.pattern).map_err(|err| format!("{}: {err}", args.pattern.display()))?;
+  let initial_state = pattern.place(config.dimensions)?;
+  let rule = args.rule.or(pattern.rule).unwrap_or_else(Rule::conway);
+
+  // generate the keys
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+
+  // encrypt false once per cell of a batch
+  let zeros: Zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+
+  // encrypt the initial configuration
+  let states: Vec<Ciphertext> = initial_state
+    .iter()
+    .map(|&x| client_key.encrypt(x))
+    .collect();
+  let mut board = Board::with_rule(config.dimensions.1, states, rule);
+  board.boundary = args.boundary;
+
+  // the event loop runs on this thread, which computes the bootstraps
+  #[cfg(feature = "fpga")]
+  server_key.enable_fpga()?;
+
+  let (width, height) = window_dimensions(&config);
+  let (ctx, event_loop) = ContextBuilder::new("game-of-life", "tfhe-rs")
+    .window_setup(
+      conf::WindowSetup::default()
+        .title(&format!("Game of Life, {rule}, {} boundary", args.boundary)),
+    )
+    .window_mode(conf::WindowMode::default().dimensions(width, height))
+    .build()?;
+
+  let state = MainState::new(
+    board,
+    &config,
+    server_key,
+    zeros,
+    client_key,
+    Device::Accelerator,
+  )?;
+  event::run(ctx, event_loop, state)
+}
@@ -0,0 +1,154 @@
+use std::error::Error;
+use std::io::{BufReader, BufWriter};
+use std::net::{TcpListener, TcpStream};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::Parser;
+use homomorphic_game_of_life::gol::*;
+
+#[cfg(feature = "fpga")]
+use tfhe::boolean::server_key::FpgaGates;
+
+/// Server of the homomorphic Game of Life: evolves encrypted boards with the server key it
+/// receives, without ever seeing the state of a cell
+#[derive(Parser)]
+struct Args {
+  /// address to listen on
+  #[arg(long, default_value = DEFAULT_ADDRESS)]
+  address: String,
+
+  /// directory where the checkpoints of the game are saved, and from which a client can resume
+  /// it; it must not hold checkpoints of another game when a new game starts
+  #[arg(long)]
+  checkpoints: Option<PathBuf>,
+
+  /// save a checkpoint every this many generations
+  #[arg(long, default_value = "1")]
+  checkpoint_every: NonZeroUsize,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+  let args = Args::parse();
+
+  let listener = TcpListener::bind(&args.address)?;
+  println!("Listening on {}", args.address);
+
+  // serve the clients one after the other
+  for stream in listener.incoming() {
+    let stream = stream?;
+    let peer = stream.peer_addr()?;
+    println!("{peer}: connected");
+    match serve(stream, &args) {
+      Ok(()) => println!("{peer}: disconnected"),
+      Err(err) => println!("{peer}: {err}"),
+    }
+  }
+
+  Ok(())
+}
+
+fn serve(stream: TcpStream, args: &Args) -> Result<(), Box<dyn Error>> {
+  let mut reader = BufReader::new(stream.try_clone()?);
+  let mut writer = BufWriter::new(stream);
+
+  let (server_key, mut board, zeros) = match receive(&mut reader)? {
+    ClientMessage::Start {
+      server_key,
+      n_cols,
+      states,
+      rule,
+      boundary,
+      zeros,
+    } => {
+      if let Some(directory) = &args.checkpoints {
+        if !list_checkpoints(directory)?.is_empty() {
+          return Err(
+            format!(
+              "{} already holds checkpoints, resume the game or pick another directory",
+              directory.display()
+            )
+            .into(),
+          );
+        }
+      }
+      if let Err(reason) = check_board(n_cols, states.len()).and_then(|()| check_zeros(&zeros)) {
+        send(&mut writer, &ServerMessage::Error(reason.clone()))?;
+        return Err(reason.into());
+      }
+      let mut board = Board::with_rule(n_cols, states, rule);
+      board.boundary = boundary;
+      save_checkpoint(&board, args)?;
+      (server_key, board, zeros)
+    }
+    ClientMessage::Resume { server_key, zeros } => {
+      if let Err(reason) = check_zeros(&zeros) {
+        send(&mut writer, &ServerMessage::Error(reason.clone()))?;
+        return Err(reason.into());
+      }
+      let directory = args
+        .checkpoints
+        .as_ref()
+        .ok_or("the server saves no checkpoints, see --checkpoints")?;
+      let path = list_checkpoints(directory)?
+        .pop()
+        .ok_or_else(|| format!("no checkpoint in {}", directory.display()))?;
+      let checkpoint = Checkpoint::load(&path)?;
+      println!("resuming from {}", path.display());
+      send(&mut writer, &ServerMessage::Resumed(checkpoint.clone()))?;
+      (server_key, Board::from_checkpoint(checkpoint), zeros)
+    }
+    ClientMessage::Step => return Err("the game has not been started".into()),
+  };
+
+  println!(
+    "rule {}, {}x{} board with {} boundary: {} bootstraps per inner cell",
+    board.rule,
+    board.dimensions.1,
+    board.dimensions.0,
+    board.boundary,
+    board.circuit.bootstrap_count()
+  );
+
+  #[cfg(feature = "fpga")]
+  server_key.enable_fpga()?;
+
+  loop {
+    // a closed connection ends the game
+    let message = match receive(&mut reader) {
+      Ok(message) => message,
+      Err(_) => return Ok(()),
+    };
+
+    match message {
+      ClientMessage::Start { .. } | ClientMessage::Resume { .. } => {
+        return Err("the game is already started".into())
+      }
+      ClientMessage::Step => {
+        let now = Instant::now();
+        board.update(&server_key, &zeros);
+        board.tick_time = now.elapsed();
+        if board.generation % args.checkpoint_every.get() == 0 {
+          save_checkpoint(&board, args)?;
+        }
+
+        send(
+          &mut writer,
+          &ServerMessage::Generation {
+            states: board.states.clone(),
+            tick_time: board.tick_time,
+          },
+        )?;
+      }
+    }
+  }
+}
+
+// save a checkpoint of the board, if the server saves checkpoints
+fn save_checkpoint(board: &Board, args: &Args) -> Result<(), Box<dyn Error>> {
+  if let Some(directory) = &args.checkpoints {
+    board.checkpoint().save(directory)?;
+  }
+  Ok(())
+}
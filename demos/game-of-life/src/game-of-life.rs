@@ -1,72 +1,269 @@
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode};
 use crossterm::execute;
 use crossterm::terminal::{
   disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use std::error::Error;
-use std::io;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
 
-use std::time::Instant;
 use tui::backend::{Backend, CrosstermBackend};
 use tui::layout::Rect;
-use tui::widgets::{Block, Borders};
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph};
 use tui::{Frame, Terminal};
 
+use clap::Parser;
 use homomorphic_game_of_life::gol::*;
 
+use tfhe::boolean::prelude::*;
 #[cfg(feature = "fpga")]
 use tfhe::boolean::server_key::FpgaGates;
 
-use std::env;
-use tfhe::boolean::prelude::*;
+/// Client of the homomorphic Game of Life: holds the keys, encrypts the initial board and renders
+/// the boards decrypted from the server's answers
+#[derive(Parser)]
+struct Args {
+  /// address of the server
+  #[arg(long, default_value = DEFAULT_ADDRESS)]
+  address: String,
 
-fn main() -> Result<(), Box<dyn Error>> {
-  // setup terminal
-  enable_raw_mode()?;
-  let mut stdout = io::stdout();
-  execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-  let backend = CrosstermBackend::new(stdout);
-  let mut terminal = Terminal::new(backend)?;
+  /// rule of the game, in B/S notation, e.g. B36/S23 for HighLife; defaults to the rule of the
+  /// pattern, or to Conway's B3/S23
+  #[arg(long)]
+  rule: Option<Rule>,
 
-  let mut current_dir = env::current_dir().unwrap();
-  current_dir.push("demos/game-of-life/initial_state");
-  let csv_path = current_dir.to_str().unwrap();
+  /// initial pattern, a .rle, .cells or 0/1 grid file
+  #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/initial_state"))]
+  pattern: PathBuf,
 
-  // read the dimensions and initial state
-  let (dimensions, initial_state): ((usize, usize), Vec<bool>) = read_csv(csv_path).unwrap();
+  /// width of the board, defaults to the width of the pattern
+  #[arg(long)]
+  width: Option<usize>,
 
-  // generate the client key
-  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  /// height of the board, defaults to the height of the pattern
+  #[arg(long)]
+  height: Option<usize>,
 
-  // generate the server key
-  let server_key = ServerKey::new(&client_key);
+  /// what lies beyond the edges of the board: torus (the board wraps around), dead (dead cells,
+  /// which cost no bootstrap) or mirror (copies of the cells along the edges)
+  #[arg(long, default_value_t = Boundary::Torus)]
+  boundary: Boundary,
 
-  #[cfg(feature = "fpga")]
-  server_key.enable_fpga();
+  /// file holding the client key: read when it exists, written with a new key otherwise; the
+  /// same key is needed to resume a game or replay its checkpoints
+  #[arg(long)]
+  key: Option<PathBuf>,
 
-  // encrypt false three times
-  let mut zeros = (
-    Vec::<Ciphertext>::new(),
-    Vec::<Ciphertext>::new(),
-    Vec::<Ciphertext>::new(),
-  );
-  for _ in 0..FPGA_BOOTSTRAP_PACKING {
-    zeros.0.push(client_key.encrypt(false));
-    zeros.1.push(client_key.encrypt(false));
-    zeros.2.push(client_key.encrypt(false));
+  /// resume the game from the last checkpoint saved by the server, instead of starting from a
+  /// pattern
+  #[arg(long, requires = "key", conflicts_with_all = ["headless", "compare", "replay"])]
+  resume: bool,
+
+  /// replay the checkpoints saved in a directory by the server, without connecting to it
+  #[arg(long, requires = "key", conflicts_with_all = ["headless", "compare"])]
+  replay: Option<PathBuf>,
+
+  /// evolve the board in this process without user interface, checking every generation against
+  /// a plaintext reference and printing one line of JSON per generation
+  #[arg(long, requires = "generations")]
+  headless: bool,
+
+  /// evolve the board in this process on the CPU and on the accelerator concurrently, and show
+  /// both side by side
+  #[arg(long, conflicts_with = "headless")]
+  compare: bool,
+
+  /// number of generations of a headless run
+  #[arg(long, requires = "headless")]
+  generations: Option<usize>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+  let args = Args::parse();
+
+  // generate the client key, or read it from its file
+  let client_key: ClientKey = match &args.key {
+    Some(path) if path.exists() => bincode::deserialize_from(BufReader::new(File::open(path)?))
+      .map_err(|err| format!("{}: {err}", path.display()))?,
+    Some(path) if args.resume || args.replay.is_some() => {
+      return Err(format!("{}: no client key to decrypt the game", path.display()).into())
+    }
+    path => {
+      let client_key = ClientKey::new(&DEMO_PARAMETERS);
+      if let Some(path) = path {
+        bincode::serialize_into(BufWriter::new(File::create(path)?), &client_key)?;
+      }
+      client_key
+    }
+  };
+
+  if let Some(directory) = &args.replay {
+    let checkpoints = list_checkpoints(directory)?;
+    if checkpoints.is_empty() {
+      return Err(format!("no checkpoint in {}", directory.display()).into());
+    }
+    return with_terminal(|terminal| run_replay(terminal, &checkpoints, &client_key));
   }
 
-  // encrypt the initial configuration
-  let initial_state: Vec<Ciphertext> = initial_state
-    .into_iter()
-    .map(|x| client_key.encrypt(x))
+  // generate the server key
+  let server_key = ServerKey::new(&client_key);
+
+  // encrypt false once per cell of a batch
+  let zeros: Zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
     .collect();
 
-  // build the board
-  let board = Board::new(dimensions.1, initial_state, client_key);
+  let (mode, board, title) = if args.resume {
+    let (mut reader, mut writer) = connect(&args.address)?;
+    send(&mut writer, &ClientMessage::Resume { server_key, zeros })?;
+    let checkpoint = match receive(&mut reader)? {
+      ServerMessage::Resumed(checkpoint) => checkpoint,
+      ServerMessage::Error(reason) => return Err(format!("the server refused: {reason}").into()),
+      _ => return Err("the server did not resume the game".into()),
+    };
+    let board = DecryptedBoard::decrypt(
+      checkpoint.dimensions,
+      &checkpoint.states,
+      Default::default(),
+      &client_key,
+    );
+    let title = format!(
+      "Game of Life, {}, {} boundary, resumed at generation {}",
+      checkpoint.rule, checkpoint.boundary, checkpoint.generation
+    );
+    (Mode::Remote { reader, writer }, board, title)
+  } else {
+    // read the pattern and place it in the board
+    let pattern =
+      Pattern::read(&args.pattern).map_err(|err| format!("{}: {err}", args.pattern.display()))?;
+    let dimensions = (
+      args.height.unwrap_or(pattern.dimensions.0),
+      args.width.unwrap_or(pattern.dimensions.1),
+    );
+    if dimensions.0 == 0 || dimensions.1 == 0 {
+      return Err("the board is empty".into());
+    }
+    let initial_state = pattern.place(dimensions)?;
+    let rule = args.rule.or(pattern.rule).unwrap_or_else(Rule::conway);
+
+    // encrypt the initial configuration
+    let states: Vec<Ciphertext> = initial_state
+      .iter()
+      .map(|&x| client_key.encrypt(x))
+      .collect();
+
+    if args.headless {
+      #[cfg(feature = "fpga")]
+      server_key.enable_fpga()?;
+
+      let mut board = Board::with_rule(dimensions.1, states, rule);
+      board.boundary = args.boundary;
+      let failed_generations = run_headless(
+        &mut board,
+        initial_state,
+        args.generations.unwrap(),
+        &server_key,
+        &client_key,
+        &zeros,
+        &mut io::stdout().lock(),
+      )?;
+      if failed_generations > 0 {
+        return Err(
+          format!("{failed_generations} generations differ from the plaintext reference").into(),
+        );
+      }
+      return Ok(());
+    }
+
+    // connect before touching the terminal, so that errors are readable
+    let mode = if args.compare {
+      let mut board = Board::with_rule(dimensions.1, states, rule);
+      board.boundary = args.boundary;
+      Mode::Compare {
+        board: Box::new(board),
+        server_key,
+        zeros,
+      }
+    } else {
+      let (reader, mut writer) = connect(&args.address)?;
+
+      // hand the game over to the server, which only gets the server key
+      send(
+        &mut writer,
+        &ClientMessage::Start {
+          server_key,
+          n_cols: dimensions.1,
+          states,
+          rule,
+          boundary: args.boundary,
+          zeros,
+        },
+      )?;
+      Mode::Remote { reader, writer }
+    };
+
+    let board = DecryptedBoard {
+      dimensions,
+      states: initial_state,
+      tick_time: Default::default(),
+    };
+    let title = format!("Game of Life, {rule}, {} boundary", args.boundary);
+    (mode, board, title)
+  };
 
   // create app and run it
-  let res = run_app(&mut terminal, board, &server_key, &zeros);
+  with_terminal(|terminal| match mode {
+    Mode::Remote {
+      mut reader,
+      mut writer,
+    } => run_app(
+      terminal,
+      board,
+      &title,
+      &client_key,
+      &mut reader,
+      &mut writer,
+    ),
+    Mode::Compare {
+      board: encrypted_board,
+      server_key,
+      zeros,
+    } => run_compare(
+      terminal,
+      board,
+      *encrypted_board,
+      &title,
+      server_key,
+      &client_key,
+      zeros,
+    ),
+  })
+}
+
+fn connect(address: &str) -> Result<(BufReader<TcpStream>, BufWriter<TcpStream>), Box<dyn Error>> {
+  let stream = TcpStream::connect(address)?;
+  Ok((BufReader::new(stream.try_clone()?), BufWriter::new(stream)))
+}
+
+// run a user interface in the terminal, which is restored afterwards
+fn with_terminal(
+  run: impl FnOnce(&mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+  // setup terminal
+  enable_raw_mode()?;
+  let mut stdout = io::stdout();
+  execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+  let backend = CrosstermBackend::new(stdout);
+  let mut terminal = Terminal::new(backend)?;
+
+  let res = run(&mut terminal);
 
   // restore terminal
   disable_raw_mode()?;
@@ -84,26 +281,85 @@ fn main() -> Result<(), Box<dyn Error>> {
   Ok(())
 }
 
+// whether the user pressed q or escape within `timeout`
+fn quit_requested(timeout: Duration) -> Result<bool, Box<dyn Error>> {
+  if event::poll(timeout)? {
+    if let Event::Key(key) = event::read()? {
+      return Ok(matches!(key.code, KeyCode::Char('q') | KeyCode::Esc));
+    }
+  }
+  Ok(false)
+}
+
+// where the generations are computed
+enum Mode {
+  // by the server
+  Remote {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+  },
+  // by this process, on the CPU and on the accelerator
+  Compare {
+    board: Box<Board>,
+    server_key: ServerKey,
+    zeros: Zeros,
+  },
+}
+
 fn run_app<B: Backend>(
   terminal: &mut Terminal<B>,
-  mut board: Board,
-  server_key: &ServerKey,
-  zeros: &(Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
-) -> io::Result<()> {
+  mut board: DecryptedBoard,
+  title: &str,
+  client_key: &ClientKey,
+  reader: &mut BufReader<TcpStream>,
+  writer: &mut BufWriter<TcpStream>,
+) -> Result<(), Box<dyn Error>> {
   loop {
-    terminal.draw(|f| ui(f, board.clone()))?;
-    let now = Instant::now();
-    board.update(server_key, zeros);
-    board.tick_time = now.elapsed();
+    terminal.draw(|f| ui(f, board.clone(), title))?;
+    send(writer, &ClientMessage::Step)?;
+    let (states, tick_time) = match receive(reader)? {
+      ServerMessage::Generation { states, tick_time } => (states, tick_time),
+      ServerMessage::Error(reason) => return Err(format!("the server refused: {reason}").into()),
+      ServerMessage::Resumed(_) => return Err("unexpected message from the server".into()),
+    };
+    board = DecryptedBoard::decrypt(board.dimensions, &states, tick_time, client_key);
+  }
+}
+
+// show the checkpoints of a game one after the other, then the last one until the user quits
+fn run_replay<B: Backend>(
+  terminal: &mut Terminal<B>,
+  checkpoints: &[PathBuf],
+  client_key: &ClientKey,
+) -> Result<(), Box<dyn Error>> {
+  for path in checkpoints {
+    let checkpoint = Checkpoint::load(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let board = DecryptedBoard::decrypt(
+      checkpoint.dimensions,
+      &checkpoint.states,
+      Default::default(),
+      client_key,
+    );
+    let title = format!(
+      "Replay, {}, {} boundary, generation {} | q to quit",
+      checkpoint.rule, checkpoint.boundary, checkpoint.generation
+    );
+    terminal.draw(|f| replay_ui(f, &board, &title))?;
+
+    if quit_requested(Duration::from_millis(200))? {
+      return Ok(());
+    }
   }
+
+  // keep the last generation on screen
+  while !quit_requested(Duration::from_millis(200))? {}
+  Ok(())
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, board: Board) {
+fn ui<B: Backend>(f: &mut Frame<B>, board: DecryptedBoard, title: &str) {
   // Surrounding block
   let size = f.size();
-  let block = Block::default()
-    .borders(Borders::ALL)
-    .title("Conway's Game of Life");
+  let block = Block::default().borders(Borders::ALL).title(title);
   f.render_widget(block, size);
 
   // Game block
@@ -121,3 +377,146 @@ fn ui<B: Backend>(f: &mut Frame<B>, board: Board) {
   // Game board
   f.render_widget(board, f.size());
 }
+
+fn replay_ui<B: Backend>(f: &mut Frame<B>, board: &DecryptedBoard, title: &str) {
+  // Surrounding block
+  let block = Block::default().borders(Borders::ALL).title(title);
+  f.render_widget(block, f.size());
+
+  // Game block
+  let block = Block::default().borders(Borders::ALL);
+  f.render_widget(
+    block,
+    Rect::new(
+      1,
+      1,
+      (board.dimensions.0 + 2) as u16,
+      (board.dimensions.1 + 2) as u16,
+    ),
+  );
+
+  // Game board, without update time
+  f.render_widget(board.cells(), f.size());
+}
+
+// a pane of the comparison, showing the last generation computed on a device
+struct Pane {
+  device: Device,
+  board: DecryptedBoard,
+  latencies: Latencies,
+  error: Option<String>,
+}
+
+fn run_compare<B: Backend>(
+  terminal: &mut Terminal<B>,
+  initial_board: DecryptedBoard,
+  board: Board,
+  title: &str,
+  server_key: ServerKey,
+  client_key: &ClientKey,
+  zeros: Zeros,
+) -> Result<(), Box<dyn Error>> {
+  let mut panes = [Device::Cpu, Device::Accelerator].map(|device| Pane {
+    device,
+    board: initial_board.clone(),
+    latencies: Latencies::default(),
+    error: None,
+  });
+
+  // evolve a copy of the encrypted board per device
+  let (updates, received) = mpsc::channel();
+  let stop = Arc::new(AtomicBool::new(false));
+  for pane in &panes {
+    spawn_device(
+      pane.device,
+      board.clone(),
+      server_key.clone(),
+      client_key.clone(),
+      zeros.clone(),
+      updates.clone(),
+      stop.clone(),
+    );
+  }
+
+  loop {
+    for update in received.try_iter() {
+      match update {
+        DeviceUpdate::Generation { device, board } => {
+          let pane = panes.iter_mut().find(|pane| pane.device == device).unwrap();
+          pane.latencies.record(board.tick_time);
+          pane.board = board;
+        }
+        DeviceUpdate::Failed { device, error } => {
+          let pane = panes.iter_mut().find(|pane| pane.device == device).unwrap();
+          pane.error = Some(error);
+        }
+      }
+    }
+
+    terminal.draw(|f| compare_ui(f, &panes, title))?;
+
+    // quit with q or escape, the threads stop after their current generation
+    if quit_requested(Duration::from_millis(50))? {
+      stop.store(true, Ordering::Relaxed);
+      return Ok(());
+    }
+  }
+}
+
+fn compare_ui<B: Backend>(f: &mut Frame<B>, panes: &[Pane; 2], title: &str) {
+  // Surrounding block, with the speed-up of the accelerator
+  let speed_up = match panes[1].latencies.speed_up(&panes[0].latencies) {
+    Some(speed_up) => format!("speed-up: x{speed_up:.2}"),
+    None => "speed-up: -".to_string(),
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .title(format!("{title} | {speed_up} | q to quit"));
+  f.render_widget(block, f.size());
+
+  // one pane per device, side by side
+  let dimensions = panes[0].board.dimensions;
+  let width = (dimensions.0 as u16 + 4).max(32);
+  let height = dimensions.1 as u16 + 8;
+  for (k, pane) in panes.iter().enumerate() {
+    let area = Rect::new(1 + k as u16 * (width + 1), 1, width, height);
+    f.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(pane.device.name()),
+      area,
+    );
+
+    // Game block
+    f.render_widget(
+      Block::default().borders(Borders::ALL),
+      Rect::new(
+        area.x + 1,
+        area.y + 1,
+        dimensions.0 as u16 + 2,
+        dimensions.1 as u16 + 2,
+      ),
+    );
+    f.render_widget(pane.board.cells(), area);
+
+    // latencies
+    let lines = match &pane.error {
+      Some(error) => vec![Spans::from(Span::styled(
+        error.clone(),
+        Style::default().fg(Color::Red),
+      ))],
+      None => vec![
+        Spans::from(format!("generation: {}", pane.latencies.generations)),
+        Spans::from(format!("latency: {:?}", pane.latencies.last)),
+        Spans::from(format!(
+          "mean: {:?}",
+          pane.latencies.mean().unwrap_or_default()
+        )),
+      ],
+    };
+    f.render_widget(
+      Paragraph::new(lines),
+      Rect::new(area.x + 2, area.y + dimensions.1 as u16 + 4, width - 3, 3),
+    );
+  }
+}
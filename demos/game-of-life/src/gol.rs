@@ -1,6 +1,24 @@
 pub mod board;
+pub mod boundary;
+pub mod checkpoint;
+pub mod circuit;
+pub mod compare;
+pub mod config;
 pub mod graph;
+pub mod headless;
 pub mod operators;
+pub mod pattern;
+pub mod protocol;
+pub mod rule;
 
 pub use board::*;
+pub use boundary::*;
+pub use checkpoint::*;
+pub use circuit::Circuit;
+pub use compare::*;
+pub use config::*;
 pub use graph::*;
+pub use headless::*;
+pub use pattern::*;
+pub use protocol::*;
+pub use rule::*;
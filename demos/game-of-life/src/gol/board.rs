@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use tfhe::boolean::prelude::*;
 
+use super::boundary::Boundary;
+use super::circuit::Circuit;
 use super::operators::is_alive;
+use super::protocol::Zeros;
+use super::rule::{Rule, NEIGHBOUR_OFFSETS};
 use tui::buffer::Buffer;
 use tui::layout::Rect;
 use tui::style::{Color, Style};
@@ -11,20 +16,34 @@ use tui::widgets::Widget;
 
 /// a Game of Life board structure
 ///
+/// The board only holds encrypted states, so that it can be evolved by a server which has no
+/// access to the client key.
+///
 /// # Fields
 ///
 /// * `dimensions`: the dimensions of the board
 /// * `states`: encrypted states of the cells
+/// * `rule`: the rule of the game
+/// * `boundary`: what lies beyond the edges of the board, a torus by default
+/// * `generation`: number of generations computed since the initial configuration
+/// * `circuit`: the circuit compiled from the rule, for the cells with 8 encrypted neighbours
+/// * `tick_time`: duration of the last update
 #[derive(Clone)]
 pub struct Board {
   pub dimensions: (usize, usize),
   pub states: Vec<Ciphertext>,
-  pub client_key: ClientKey,
+  pub rule: Rule,
+  pub boundary: Boundary,
+  pub generation: usize,
+  pub circuit: Circuit,
   pub tick_time: Duration,
+  // circuits for the cells with trivial neighbours, by numbers of encrypted and live trivial
+  // neighbours
+  border_circuits: BTreeMap<(usize, usize), Circuit>,
 }
 
 impl Board {
-  /// create a new board
+  /// create a new board for Conway's Game of Life
   ///
   /// # Arguments
   ///
@@ -34,13 +53,14 @@ impl Board {
   /// # Example
   ///
   /// ```
-  /// use homomorphic_game_of_life::*;
+  /// use homomorphic_game_of_life::gol::*;
+  /// use tfhe::boolean::prelude::*;
   ///
   /// // numbers of rows and columns in the board
   /// let (n_rows, n_cols): (usize, usize) = (6,6);
   ///
   /// // generate the client key
-  /// let (client_key, _) = gen_keys();
+  /// let client_key = ClientKey::new(&DEMO_PARAMETERS);
   ///
   /// // initial configuration
   /// let states = vec![true, false, false, false, false, false,
@@ -54,38 +74,66 @@ impl Board {
   /// let states: Vec<Ciphertext> = states.into_iter().map(|x| client_key.encrypt(x)).collect();
   ///
   /// // build the board
-  /// let mut board = Board::new(n_cols, states);
+  /// let board = Board::new(n_cols, states);
+  /// assert_eq!(board.dimensions, (n_rows, n_cols));
   /// ```
-  pub fn new(n_cols: usize, states: Vec<Ciphertext>, client_key: ClientKey) -> Board {
+  pub fn new(n_cols: usize, states: Vec<Ciphertext>) -> Board {
+    Board::with_rule(n_cols, states, Rule::conway())
+  }
+
+  /// create a new board for a Life-like rule
+  ///
+  /// # Arguments
+  ///
+  /// * `n_cols`: number of columns
+  /// * `states`: encrypted states of the cells in the initial configuration
+  /// * `rule`: the rule of the game, compiled once to a circuit
+  pub fn with_rule(n_cols: usize, states: Vec<Ciphertext>, rule: Rule) -> Board {
+    assert!(
+      n_cols > 0 && states.len() % n_cols == 0,
+      "{} cells do not make rows of {n_cols} cells",
+      states.len()
+    );
     let n_rows = states.len() / n_cols;
     Board {
       dimensions: (n_rows, n_cols),
       states,
-      client_key,
+      rule,
+      boundary: Boundary::default(),
+      generation: 0,
+      circuit: Circuit::compile(&rule),
       tick_time: Duration::default(),
+      border_circuits: BTreeMap::new(),
     }
   }
 
   /// update the board
   ///
+  /// The cells are packed in batches of cells with the same numbers of encrypted and of trivial
+  /// live neighbours, each updated with a circuit counting the encrypted neighbours only: the dead
+  /// cells beyond the edges of a board with a [`Boundary::Dead`] boundary cost no bootstrap.
+  ///
   /// # Arguments
   ///
   /// * `server_key`: the server key
-  /// * `zeros`: three encryption of `false`
+  /// * `zeros`: encryptions of `false`, one per cell of a batch
   ///
   /// # Example
   ///
   /// ```
-  /// use homomorphic_game_of_life::*;
+  /// use homomorphic_game_of_life::gol::*;
+  /// use tfhe::boolean::prelude::*;
   ///
   /// // numbers of rows and columns in the board
   /// let (n_rows, n_cols): (usize, usize) = (6,6);
   ///
   /// // generate the keys
-  /// let (client_key, server_key) = gen_keys();
+  /// let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  /// let server_key = ServerKey::new(&client_key);
   ///
-  /// // encrypt false three times
-  /// let zeros = (client_key.encrypt(false), client_key.encrypt(false), client_key.encrypt(false));
+  /// // encrypt false once per cell of a batch
+  /// let zeros: Vec<Ciphertext> =
+  ///     (0..FPGA_BOOTSTRAP_PACKING).map(|_| client_key.encrypt(false)).collect();
   ///
   /// // initial configuration
   /// let states = vec![true, false, false, false, false, false,
@@ -107,7 +155,7 @@ impl Board {
   /// // decrypt and show the board
   /// for i in 0..n_rows {
   ///     println!("");
-  ///     for j in 0..n_rows {
+  ///     for j in 0..n_cols {
   ///         if client_key.decrypt(&board.states[i*n_cols+j]) {
   ///             print!("█");
   ///         } else {
@@ -117,147 +165,145 @@ impl Board {
   /// }
   /// println!("");
   /// ```
-  pub fn update(
-    &mut self,
-    server_key: &ServerKey,
-    zeros: &(Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
-  ) {
-    let nx = self.dimensions.0;
-    let ny = self.dimensions.1;
-
-    let mut new_states = Vec::<Ciphertext>::new();
+  pub fn update(&mut self, server_key: &ServerKey, zeros: &Zeros) {
+    let (nx, ny) = self.dimensions;
 
-    for k1 in (0..nx * ny).step_by(FPGA_BOOTSTRAP_PACKING) {
-      let mut cell_p = Vec::<Ciphertext>::new();
-      let mut neighbours_p = [
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-        Vec::<Ciphertext>::new(),
-      ];
-      let end = std::cmp::min(FPGA_BOOTSTRAP_PACKING, nx * ny - k1);
-
-      for k2 in 0..end {
-        let i = (k1 + k2) / ny;
-        let j = (k1 + k2) % ny;
+    // sort the cells by numbers of encrypted and live trivial neighbours: the trivial neighbours,
+    // such as the dead cells beyond the edges, are known in the clear and are not counted with
+    // gates
+    let mut groups = BTreeMap::<(usize, usize), Vec<(usize, Vec<usize>)>>::new();
+    for k in 0..nx * ny {
+      let mut encrypted = Vec::new();
+      let mut offset = 0;
+      for neighbour in NEIGHBOUR_OFFSETS.iter().filter_map(|&o| {
+        self
+          .boundary
+          .neighbour(self.dimensions, (k / ny, k % ny), o)
+      }) {
+        match self.states[neighbour] {
+          Ciphertext::Encrypted(_) => encrypted.push(neighbour),
+          Ciphertext::Trivial(alive) => offset += alive as usize,
+        }
+      }
+      groups
+        .entry((encrypted.len(), offset))
+        .or_default()
+        .push((k, encrypted));
+    }
 
-        let im = if i == 0 { nx - 1 } else { i - 1 };
-        let ip = if i == nx - 1 { 0 } else { i + 1 };
-        let jm = if j == 0 { ny - 1 } else { j - 1 };
-        let jp = if j == ny - 1 { 0 } else { j + 1 };
+    let mut new_states = vec![None; nx * ny];
+    for ((n_encrypted, offset), cells) in groups {
+      let circuit = if (n_encrypted, offset) == (8, 0) {
+        &self.circuit
+      } else {
+        self
+          .border_circuits
+          .entry((n_encrypted, offset))
+          .or_insert_with(|| Circuit::compile_for(&self.rule, n_encrypted, offset))
+      };
 
-        // get the neighbours, with periodic boundary conditions
-        neighbours_p[0].push(self.states[im * ny + jm].clone());
-        neighbours_p[1].push(self.states[im * ny + j].clone());
-        neighbours_p[2].push(self.states[im * ny + jp].clone());
-        neighbours_p[3].push(self.states[i * ny + jm].clone());
-        neighbours_p[4].push(self.states[i * ny + jp].clone());
-        neighbours_p[5].push(self.states[ip * ny + jm].clone());
-        neighbours_p[6].push(self.states[ip * ny + j].clone());
-        neighbours_p[7].push(self.states[ip * ny + jp].clone());
+      // pack the cells of a group in batches
+      for batch in cells.chunks(FPGA_BOOTSTRAP_PACKING) {
+        let cell_p: Vec<Ciphertext> = batch.iter().map(|(k, _)| self.states[*k].clone()).collect();
+        let neighbours_p: Vec<Vec<Ciphertext>> = (0..n_encrypted)
+          .map(|n| {
+            batch
+              .iter()
+              .map(|(_, neighbours)| self.states[neighbours[n]].clone())
+              .collect()
+          })
+          .collect();
 
-        cell_p.push(self.states[i * ny + j].clone());
+        let mut batch_states = Vec::with_capacity(batch.len());
+        is_alive(
+          server_key,
+          circuit,
+          cell_p,
+          neighbours_p,
+          zeros,
+          &mut batch_states,
+        );
+        for ((k, _), state) in batch.iter().zip(batch_states) {
+          new_states[*k] = Some(state);
+        }
       }
-
-      is_alive(&server_key, cell_p, neighbours_p, zeros, &mut new_states);
     }
 
     // update the board
-    self.states = new_states;
+    self.states = new_states.into_iter().map(Option::unwrap).collect();
+    self.generation += 1;
   }
 }
 
-// config structure
-pub struct Config {
-  pub wait_time_micros: u64,
-  pub pixel_size: usize,
+/// a decrypted Game of Life board, as rendered by the client
+///
+/// # Fields
+///
+/// * `dimensions`: the dimensions of the board
+/// * `states`: states of the cells
+/// * `tick_time`: duration of the last update on the server
+#[derive(Clone)]
+pub struct DecryptedBoard {
   pub dimensions: (usize, usize),
-  pub col1: (f32, f32, f32),
-  pub col2: (f32, f32, f32),
+  pub states: Vec<bool>,
+  pub tick_time: Duration,
 }
 
-impl Config {
-  pub fn read(fname: &str) -> Result<Config, Box<dyn std::error::Error>> {
-    let err_message = "Missing argument in the config file";
-
-    // load the content
-    let content = std::fs::read_to_string(fname)?;
-
-    // separate the nubers
-    let mut content = content.split(' ');
-
-    // read the content
-    Ok(Config {
-      wait_time_micros: content.next().ok_or(err_message)?.parse::<u64>()?,
-      pixel_size: content.next().ok_or(err_message)?.parse::<usize>()?,
-      dimensions: (
-        content.next().ok_or(err_message)?.parse::<usize>()?,
-        content.next().ok_or(err_message)?.parse::<usize>()?,
-      ),
-      col1: (
-        content.next().ok_or(err_message)?.parse::<f32>()?,
-        content.next().ok_or(err_message)?.parse::<f32>()?,
-        content.next().ok_or(err_message)?.parse::<f32>()?,
-      ),
-      col2: (
-        content.next().ok_or(err_message)?.parse::<f32>()?,
-        content.next().ok_or(err_message)?.parse::<f32>()?,
-        content.next().ok_or(err_message)?.parse::<f32>()?,
-      ),
-    })
+impl DecryptedBoard {
+  /// decrypt the states of the cells
+  ///
+  /// # Arguments
+  ///
+  /// * `dimensions`: the dimensions of the board
+  /// * `states`: encrypted states of the cells
+  /// * `tick_time`: duration of the last update on the server
+  /// * `client_key`: the client key
+  pub fn decrypt(
+    dimensions: (usize, usize),
+    states: &[Ciphertext],
+    tick_time: Duration,
+    client_key: &ClientKey,
+  ) -> DecryptedBoard {
+    DecryptedBoard {
+      dimensions,
+      states: states.iter().map(|ct| client_key.decrypt(ct)).collect(),
+      tick_time,
+    }
   }
 }
 
-/// read a state file, space- and newline-separated
-///
-/// The file must contain only 0s and 1s and all rows need to have the same length.
-#[allow(clippy::type_complexity)]
-pub fn read_csv(fname: &str) -> Result<((usize, usize), Vec<bool>), Box<dyn std::error::Error>> {
-  // load the content
-  let content = std::fs::read_to_string(fname)?;
-
-  // separate in rows
-  let content = content.split('\n').collect::<Vec<&str>>();
-  let n_rows = content.len() - 1;
-
-  // number of columns
-  let n_cols = content[0].split(' ').count() - 1;
-
-  // load the data
-  let mut data = Vec::<bool>::new();
-  for row in content {
-    for el in row.split(' ') {
-      if let Ok(x) = el.parse::<u8>() {
-        data.push(x == 1)
-      };
-    }
+impl DecryptedBoard {
+  /// a widget drawing the cells only, without the update time
+  pub fn cells(&self) -> BoardCells<'_> {
+    BoardCells(self)
   }
-
-  Ok(((n_rows, n_cols), data))
 }
 
-impl Widget for Board {
-  fn render(self, _area: Rect, buf: &mut Buffer) {
-    for i in 0..self.dimensions.0 {
-      for j in 0..self.dimensions.1 {
-        if self
-          .client_key
-          .decrypt(&self.states[i * self.dimensions.1 + j])
-        {
+/// the cells of a decrypted board, see [`DecryptedBoard::cells`]
+pub struct BoardCells<'a>(&'a DecryptedBoard);
+
+impl Widget for BoardCells<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    let board = self.0;
+    for i in 0..board.dimensions.0 {
+      for j in 0..board.dimensions.1 {
+        if board.states[i * board.dimensions.1 + j] {
           buf
-            .get_mut(2 + i as u16, 2 + j as u16)
+            .get_mut(area.x + 2 + i as u16, area.y + 2 + j as u16)
             .set_style(Style::default().bg(Color::White));
         }
       }
     }
+  }
+}
+
+impl Widget for DecryptedBoard {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    self.cells().render(area, buf);
 
     buf.set_string(
-      1,
-      (self.dimensions.1 + 3) as u16,
+      area.x + 1,
+      area.y + (self.dimensions.1 + 3) as u16,
       format!(
         "                                   Board update: {:?}",
         self.tick_time
@@ -0,0 +1,102 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// what lies beyond the edges of a board
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_game_of_life::gol::*;
+///
+/// // the cell above the top left corner of a 4x5 board
+/// assert_eq!(Boundary::Torus.neighbour((4, 5), (0, 0), (-1, 0)), Some(15));
+/// assert_eq!(Boundary::Dead.neighbour((4, 5), (0, 0), (-1, 0)), None);
+/// assert_eq!(Boundary::Mirror.neighbour((4, 5), (0, 0), (-1, 0)), Some(0));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+  /// periodic boundary conditions: the board wraps around its edges
+  #[default]
+  Torus,
+  /// the board is surrounded by dead cells, which cost no bootstrap
+  Dead,
+  /// the cells beyond an edge are copies of the cells along it
+  Mirror,
+}
+
+/// error returned when a boundary name is unknown
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundaryError(pub String);
+
+impl Display for BoundaryError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "unknown boundary \"{}\", expected torus, dead or mirror",
+      self.0
+    )
+  }
+}
+
+impl std::error::Error for BoundaryError {}
+
+impl Boundary {
+  /// index of a neighbour of a cell, `None` for the dead cells beyond the edges
+  ///
+  /// # Arguments
+  ///
+  /// * `dimensions`: the numbers of rows and columns of the board
+  /// * `cell`: the row and column of the cell
+  /// * `offset`: the offsets of the neighbour in rows and columns, from -1 to 1
+  pub fn neighbour(
+    &self,
+    dimensions: (usize, usize),
+    cell: (usize, usize),
+    offset: (isize, isize),
+  ) -> Option<usize> {
+    let i = self.coordinate(dimensions.0, cell.0, offset.0)?;
+    let j = self.coordinate(dimensions.1, cell.1, offset.1)?;
+    Some(i * dimensions.1 + j)
+  }
+
+  fn coordinate(&self, size: usize, x: usize, offset: isize) -> Option<usize> {
+    let shifted = x as isize + offset;
+    if (0..size as isize).contains(&shifted) {
+      return Some(shifted as usize);
+    }
+    match self {
+      Boundary::Torus => Some(shifted.rem_euclid(size as isize) as usize),
+      Boundary::Dead => None,
+      Boundary::Mirror => Some(x),
+    }
+  }
+}
+
+impl FromStr for Boundary {
+  type Err = BoundaryError;
+
+  fn from_str(s: &str) -> Result<Boundary, BoundaryError> {
+    match s {
+      "torus" => Ok(Boundary::Torus),
+      "dead" => Ok(Boundary::Dead),
+      "mirror" => Ok(Boundary::Mirror),
+      _ => Err(BoundaryError(s.to_string())),
+    }
+  }
+}
+
+impl Display for Boundary {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      Boundary::Torus => "torus",
+      Boundary::Dead => "dead",
+      Boundary::Mirror => "mirror",
+    };
+    write!(f, "{name}")
+  }
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn test_parse_boundaries() {
+  for boundary in [Boundary::Torus, Boundary::Dead, Boundary::Mirror] {
+    assert_eq!(boundary.to_string().parse(), Ok(boundary));
+  }
+  assert_eq!(
+    "klein".parse::<Boundary>(),
+    Err(BoundaryError("klein".to_string()))
+  );
+}
+
+#[test]
+fn test_neighbours() {
+  // the corners of a 3x4 board
+  let dimensions = (3, 4);
+  for (cell, offset, torus, dead, mirror) in [
+    ((0, 0), (-1, -1), Some(11), None, Some(0)),
+    ((0, 0), (1, -1), Some(7), None, Some(4)),
+    ((0, 3), (-1, 1), Some(8), None, Some(3)),
+    ((2, 3), (1, 1), Some(0), None, Some(11)),
+    ((2, 0), (0, -1), Some(11), None, Some(8)),
+    ((1, 1), (1, 1), Some(10), Some(10), Some(10)),
+  ] {
+    assert_eq!(Boundary::Torus.neighbour(dimensions, cell, offset), torus);
+    assert_eq!(Boundary::Dead.neighbour(dimensions, cell, offset), dead);
+    assert_eq!(Boundary::Mirror.neighbour(dimensions, cell, offset), mirror);
+  }
+}
@@ -0,0 +1,224 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use tfhe::boolean::prelude::*;
+
+use super::board::Board;
+use super::boundary::Boundary;
+use super::protocol::MAX_MESSAGE_SIZE;
+use super::rule::Rule;
+
+#[cfg(test)]
+mod tests;
+
+/// version of the checkpoint format written by [`Checkpoint::write`]
+///
+/// It is bumped whenever the layout of [`Checkpoint`] changes, so that older checkpoints are
+/// rejected with [`CheckpointError::UnsupportedVersion`] instead of being misread.
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+// first bytes of every checkpoint file
+const CHECKPOINT_MAGIC: [u8; 8] = *b"FHEGOL\0\0";
+
+// extension of the checkpoint files written by `Checkpoint::save`
+const CHECKPOINT_EXTENSION: &str = "ckpt";
+
+/// the state of an encrypted board after some generations
+///
+/// A checkpoint only holds encrypted states, so that the server can write it and resume from it
+/// without the client key, and the key holder can decrypt it later to replay the game.
+///
+/// A checkpoint file starts with 8 magic bytes and the version of the format as a little endian
+/// `u32`, followed by the checkpoint serialized with bincode. Like the messages of the protocol,
+/// which carry checkpoints, the serialized checkpoint is at most [`MAX_MESSAGE_SIZE`] bytes.
+///
+/// # Fields
+///
+/// * `dimensions`: the dimensions of the board
+/// * `generation`: number of generations computed since the initial configuration
+/// * `rule`: the rule of the game
+/// * `boundary`: what lies beyond the edges of the board
+/// * `states`: encrypted states of the cells
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+  pub dimensions: (usize, usize),
+  pub generation: usize,
+  pub rule: Rule,
+  pub boundary: Boundary,
+  pub states: Vec<Ciphertext>,
+}
+
+/// error returned when reading, writing or resuming from a checkpoint
+#[derive(Debug)]
+pub enum CheckpointError {
+  /// the checkpoint file could not be read or written
+  Io(std::io::Error),
+  /// the file does not start with the magic bytes of a checkpoint
+  NotACheckpoint,
+  /// the checkpoint was written in a version of the format this build cannot read
+  UnsupportedVersion(u32),
+  /// the checkpoint could not be encoded or decoded
+  Encoding(bincode::Error),
+  /// the number of cells does not match the dimensions
+  Inconsistent {
+    dimensions: (usize, usize),
+    cells: usize,
+  },
+}
+
+impl Display for CheckpointError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CheckpointError::Io(err) => write!(f, "cannot access the checkpoint: {err}"),
+      CheckpointError::NotACheckpoint => write!(f, "not a checkpoint file"),
+      CheckpointError::UnsupportedVersion(version) => write!(
+        f,
+        "checkpoint format version {version} is not supported, expected {CHECKPOINT_VERSION}"
+      ),
+      CheckpointError::Encoding(err) => write!(f, "malformed checkpoint: {err}"),
+      CheckpointError::Inconsistent { dimensions, cells } => write!(
+        f,
+        "a {}x{} board cannot have {cells} cells",
+        dimensions.1, dimensions.0
+      ),
+    }
+  }
+}
+
+impl std::error::Error for CheckpointError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      CheckpointError::Io(err) => Some(err),
+      CheckpointError::Encoding(err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl From<std::io::Error> for CheckpointError {
+  fn from(err: std::io::Error) -> CheckpointError {
+    CheckpointError::Io(err)
+  }
+}
+
+impl From<bincode::Error> for CheckpointError {
+  fn from(err: bincode::Error) -> CheckpointError {
+    CheckpointError::Encoding(err)
+  }
+}
+
+// the bincode options of the checkpoints: the encoding of `bincode::serialize`, bounded by
+// `MAX_MESSAGE_SIZE` so that a malformed file cannot make the reader allocate arbitrary amounts
+// of memory
+fn options() -> impl Options {
+  bincode::DefaultOptions::new()
+    .with_fixint_encoding()
+    .allow_trailing_bytes()
+    .with_limit(MAX_MESSAGE_SIZE)
+}
+
+impl Checkpoint {
+  /// write the checkpoint, with its header
+  pub fn write(&self, out: &mut impl Write) -> Result<(), CheckpointError> {
+    out.write_all(&CHECKPOINT_MAGIC)?;
+    out.write_all(&CHECKPOINT_VERSION.to_le_bytes())?;
+    options().serialize_into(&mut *out, self)?;
+    out.flush()?;
+    Ok(())
+  }
+
+  /// read a checkpoint written by [`Checkpoint::write`]
+  pub fn read(input: &mut impl Read) -> Result<Checkpoint, CheckpointError> {
+    let mut magic = [0; CHECKPOINT_MAGIC.len()];
+    input
+      .read_exact(&mut magic)
+      .map_err(|_| CheckpointError::NotACheckpoint)?;
+    if magic != CHECKPOINT_MAGIC {
+      return Err(CheckpointError::NotACheckpoint);
+    }
+
+    let mut version = [0; 4];
+    input.read_exact(&mut version)?;
+    match u32::from_le_bytes(version) {
+      CHECKPOINT_VERSION => {}
+      version => return Err(CheckpointError::UnsupportedVersion(version)),
+    }
+
+    let checkpoint: Checkpoint = options().deserialize_from(input)?;
+    let (n_rows, n_cols) = checkpoint.dimensions;
+    if n_cols == 0 || n_rows.checked_mul(n_cols) != Some(checkpoint.states.len()) {
+      return Err(CheckpointError::Inconsistent {
+        dimensions: checkpoint.dimensions,
+        cells: checkpoint.states.len(),
+      });
+    }
+    Ok(checkpoint)
+  }
+
+  /// save the checkpoint in a directory, in a file named after its generation
+  ///
+  /// The file is written next to its final path and renamed, so that a run interrupted while
+  /// saving never leaves a truncated checkpoint behind. Returns the path of the checkpoint.
+  pub fn save(&self, directory: impl AsRef<Path>) -> Result<PathBuf, CheckpointError> {
+    let directory = directory.as_ref();
+    std::fs::create_dir_all(directory)?;
+    let path = directory.join(format!(
+      "generation-{:08}.{CHECKPOINT_EXTENSION}",
+      self.generation
+    ));
+    let partial = path.with_extension("partial");
+    self.write(&mut BufWriter::new(File::create(&partial)?))?;
+    std::fs::rename(&partial, &path)?;
+    Ok(path)
+  }
+
+  /// load a checkpoint file
+  pub fn load(path: impl AsRef<Path>) -> Result<Checkpoint, CheckpointError> {
+    Checkpoint::read(&mut BufReader::new(File::open(path)?))
+  }
+}
+
+/// the checkpoint files saved in a directory, by increasing generation
+///
+/// A directory which does not exist holds no checkpoint.
+pub fn list_checkpoints(directory: impl AsRef<Path>) -> Result<Vec<PathBuf>, CheckpointError> {
+  let directory = directory.as_ref();
+  if !directory.exists() {
+    return Ok(Vec::new());
+  }
+  let mut checkpoints = Vec::new();
+  for entry in std::fs::read_dir(directory)? {
+    let path = entry?.path();
+    if path.extension().and_then(|extension| extension.to_str()) == Some(CHECKPOINT_EXTENSION) {
+      checkpoints.push(path);
+    }
+  }
+  // the generations are zero-padded, the names sort in order
+  checkpoints.sort();
+  Ok(checkpoints)
+}
+
+impl Board {
+  /// the checkpoint of the current generation
+  pub fn checkpoint(&self) -> Checkpoint {
+    Checkpoint {
+      dimensions: self.dimensions,
+      generation: self.generation,
+      rule: self.rule,
+      boundary: self.boundary,
+      states: self.states.clone(),
+    }
+  }
+
+  /// resume a board from a checkpoint
+  pub fn from_checkpoint(checkpoint: Checkpoint) -> Board {
+    let mut board = Board::with_rule(checkpoint.dimensions.1, checkpoint.states, checkpoint.rule);
+    board.boundary = checkpoint.boundary;
+    board.generation = checkpoint.generation;
+    board
+  }
+}
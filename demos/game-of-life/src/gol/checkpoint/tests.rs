@@ -0,0 +1,152 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::*;
+use crate::gol::Zeros;
+
+fn decrypt(states: &[Ciphertext], client_key: &ClientKey) -> Vec<bool> {
+  states.iter().map(|ct| client_key.decrypt(ct)).collect()
+}
+
+// a directory of the system's temporary directory, removed first
+fn scratch_directory(name: &str) -> PathBuf {
+  let directory = std::env::temp_dir().join(format!("gol-{name}-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&directory);
+  directory
+}
+
+#[test]
+fn test_resume() {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+  let zeros: Zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+
+  let mut rng = ChaCha8Rng::seed_from_u64(0);
+  let states = (0..15).map(|_| client_key.encrypt(rng.gen())).collect();
+  let mut board = Board::with_rule(5, states, "B36/S23".parse().unwrap());
+  board.boundary = Boundary::Dead;
+  board.update(&server_key, &zeros);
+
+  let mut file = Vec::new();
+  board.checkpoint().write(&mut file).unwrap();
+  let checkpoint = Checkpoint::read(&mut file.as_slice()).unwrap();
+  assert_eq!(checkpoint.dimensions, (3, 5));
+  assert_eq!(checkpoint.generation, 1);
+  assert_eq!(checkpoint.rule, board.rule);
+  assert_eq!(checkpoint.boundary, Boundary::Dead);
+
+  // the resumed board evolves as the original one
+  let mut resumed = Board::from_checkpoint(checkpoint);
+  for _ in 0..2 {
+    board.update(&server_key, &zeros);
+    resumed.update(&server_key, &zeros);
+    assert_eq!(resumed.generation, board.generation);
+    assert_eq!(
+      decrypt(&resumed.states, &client_key),
+      decrypt(&board.states, &client_key)
+    );
+  }
+}
+
+#[test]
+fn test_invalid_checkpoints() {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let states = (0..4).map(|_| client_key.encrypt(true)).collect();
+  let board = Board::with_rule(2, states, Rule::conway());
+  let mut file = Vec::new();
+  board.checkpoint().write(&mut file).unwrap();
+
+  let read = |file: &[u8]| Checkpoint::read(&mut &file[..]);
+  assert!(read(&file).is_ok());
+  assert!(matches!(
+    read(b"FHEGOL"),
+    Err(CheckpointError::NotACheckpoint)
+  ));
+  assert!(matches!(
+    read(b"GOL\0\0\0\0\0\x01\0\0\0"),
+    Err(CheckpointError::NotACheckpoint)
+  ));
+
+  // a later version of the format
+  let mut later = file.clone();
+  later[8] = 2;
+  assert!(matches!(
+    read(&later),
+    Err(CheckpointError::UnsupportedVersion(2))
+  ));
+
+  // a truncated checkpoint
+  assert!(matches!(
+    read(&file[..file.len() - 1]),
+    Err(CheckpointError::Encoding(_))
+  ));
+
+  // a board with a missing cell
+  let mut checkpoint = board.checkpoint();
+  checkpoint.states.pop();
+  let mut file = Vec::new();
+  checkpoint.write(&mut file).unwrap();
+  assert!(matches!(
+    read(&file),
+    Err(CheckpointError::Inconsistent {
+      dimensions: (2, 2),
+      cells: 3
+    })
+  ));
+
+  // dimensions whose number of cells overflows
+  let mut checkpoint = board.checkpoint();
+  checkpoint.dimensions = (usize::MAX, 2);
+  let mut file = Vec::new();
+  checkpoint.write(&mut file).unwrap();
+  assert!(matches!(
+    read(&file),
+    Err(CheckpointError::Inconsistent {
+      dimensions: (usize::MAX, 2),
+      cells: 4
+    })
+  ));
+
+  // a number of cells far beyond the size limit, which is refused rather than allocated
+  let mut checkpoint = board.checkpoint();
+  checkpoint.states.clear();
+  let mut file = Vec::new();
+  checkpoint.write(&mut file).unwrap();
+  let n_states = file.len() - 8;
+  file[n_states..].copy_from_slice(&u64::MAX.to_le_bytes());
+  assert!(matches!(read(&file), Err(CheckpointError::Encoding(_))));
+}
+
+#[test]
+fn test_save_checkpoints() {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let directory = scratch_directory("checkpoints");
+  assert!(list_checkpoints(&directory).unwrap().is_empty());
+
+  let states = (0..6).map(|k| client_key.encrypt(k % 2 == 0)).collect();
+  let mut checkpoint = Board::with_rule(3, states, Rule::conway()).checkpoint();
+  for generation in [10, 2, 0] {
+    checkpoint.generation = generation;
+    checkpoint.save(&directory).unwrap();
+  }
+  std::fs::write(directory.join("notes.txt"), "not a checkpoint").unwrap();
+
+  // the checkpoints are listed by generation, and nothing is left half written
+  let checkpoints = list_checkpoints(&directory).unwrap();
+  let generations: Vec<usize> = checkpoints
+    .iter()
+    .map(|path| Checkpoint::load(path).unwrap().generation)
+    .collect();
+  assert_eq!(generations, [0, 2, 10]);
+  assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 4);
+
+  let loaded = Checkpoint::load(&checkpoints[2]).unwrap();
+  assert_eq!(
+    decrypt(&loaded.states, &client_key),
+    [true, false, true, false, true, false]
+  );
+
+  std::fs::remove_dir_all(&directory).unwrap();
+}
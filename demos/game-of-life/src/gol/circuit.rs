@@ -0,0 +1,599 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tfhe::boolean::prelude::*;
+
+use super::operators::{count, count_gate_count};
+use super::rule::Rule;
+
+#[cfg(test)]
+mod tests;
+
+/// index of the input holding the state of the cell; inputs `1` to `4` hold the bits of the
+/// neighbour count, least significant first
+pub const ALIVE: usize = 0;
+
+// number of inputs of a rule circuit, and of rows in its truth table
+const N_INPUTS: usize = 5;
+const N_ROWS: usize = 1 << N_INPUTS;
+
+/// a node of a rule circuit, possibly negated
+///
+/// Negations are free: they do not need a bootstrap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Literal {
+  pub node: usize,
+  pub negated: bool,
+}
+
+impl std::ops::Not for Literal {
+  type Output = Literal;
+
+  fn not(self) -> Literal {
+    Literal {
+      node: self.node,
+      negated: !self.negated,
+    }
+  }
+}
+
+/// a binary gate, each costs one bootstrap
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Op {
+  And,
+  Or,
+  Xor,
+}
+
+impl Op {
+  fn apply(self, a: bool, b: bool) -> bool {
+    match self {
+      Op::And => a && b,
+      Op::Or => a || b,
+      Op::Xor => a ^ b,
+    }
+  }
+}
+
+/// a node of a rule circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Node {
+  Input(usize),
+  Gate(Op, Literal, Literal),
+}
+
+/// output of a rule circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Output {
+  Constant(bool),
+  Literal(Literal),
+}
+
+impl std::ops::Not for Output {
+  type Output = Output;
+
+  fn not(self) -> Output {
+    match self {
+      Output::Constant(value) => Output::Constant(!value),
+      Output::Literal(literal) => Output::Literal(!literal),
+    }
+  }
+}
+
+/// a boolean circuit computing the next state of a cell from its state and its neighbour count
+///
+/// The circuit counts the `neighbours` encrypted neighbours of the cell, and adds the `offset`
+/// live neighbours known in the clear. Nodes are in topological order. The count is encoded on
+/// `count_bits` bits, as few as the rule allows: 3 bits for 8 neighbours with 8 identified with 0
+/// when the rule does not tell them apart, and 0 bits when the rule does not depend on the
+/// neighbours.
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_game_of_life::gol::*;
+///
+/// let circuit = Circuit::compile(&Rule::conway());
+/// assert_eq!(circuit.count_bits, 3);
+/// for count in 0..=8 {
+///   assert_eq!(circuit.evaluate(true, count), Rule::conway().next_state(true, count));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Circuit {
+  pub nodes: Vec<Node>,
+  pub output: Output,
+  pub count_bits: usize,
+  pub neighbours: usize,
+  pub offset: usize,
+}
+
+// an incompletely specified function of the inputs, as a truth table and a mask of the rows
+// where its value matters
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Function {
+  value: u32,
+  care: u32,
+}
+
+impl Function {
+  fn new(value: u32, care: u32) -> Function {
+    Function {
+      value: value & care,
+      care,
+    }
+  }
+
+  fn constant(&self) -> Option<bool> {
+    if self.value == 0 {
+      Some(false)
+    } else if self.value == self.care {
+      Some(true)
+    } else {
+      None
+    }
+  }
+
+  // whether `self` and `other` can be implemented by the same function
+  fn compatible(&self, other: &Function) -> bool {
+    (self.value ^ other.value) & self.care & other.care == 0
+  }
+
+  fn merge(&self, other: &Function) -> Function {
+    Function::new(self.value | other.value, self.care | other.care)
+  }
+
+  fn complement(&self) -> Function {
+    Function::new(!self.value, self.care)
+  }
+
+  fn matches(&self, truth_table: u32) -> bool {
+    (truth_table ^ self.value) & self.care == 0
+  }
+
+  // the function with `input` fixed to `value`, which does not depend on `input` any more
+  fn cofactor(&self, input: usize, value: bool) -> Function {
+    let bit = 1 << input;
+    let (mut f_value, mut f_care) = (0, 0);
+    for row in 0..N_ROWS {
+      let source = if value { row | bit } else { row & !bit };
+      f_value |= ((self.value >> source) & 1) << row;
+      f_care |= ((self.care >> source) & 1) << row;
+    }
+    Function::new(f_value, f_care)
+  }
+}
+
+// truth table of an input
+fn input_truth_table(input: usize) -> u32 {
+  (0..N_ROWS)
+    .filter(|row| (row >> input) & 1 == 1)
+    .fold(0, |table, row| table | (1 << row))
+}
+
+// builds a circuit from decision diagrams, sharing identical nodes
+#[derive(Clone, Default)]
+struct Builder {
+  nodes: Vec<Node>,
+  truth_tables: Vec<u32>,
+  gates: HashMap<(Op, Literal, Literal), usize>,
+}
+
+impl Builder {
+  fn gate_count(&self) -> usize {
+    self
+      .nodes
+      .iter()
+      .filter(|node| matches!(node, Node::Gate(..)))
+      .count()
+  }
+
+  fn truth_table(&self, output: Output) -> u32 {
+    match output {
+      Output::Constant(value) => {
+        if value {
+          u32::MAX
+        } else {
+          0
+        }
+      }
+      Output::Literal(literal) => {
+        let table = self.truth_tables[literal.node];
+        if literal.negated {
+          !table
+        } else {
+          table
+        }
+      }
+    }
+  }
+
+  fn push(&mut self, node: Node, truth_table: u32) -> Literal {
+    self.nodes.push(node);
+    self.truth_tables.push(truth_table);
+    Literal {
+      node: self.nodes.len() - 1,
+      negated: false,
+    }
+  }
+
+  fn input(&mut self, input: usize) -> Literal {
+    match self
+      .nodes
+      .iter()
+      .position(|&node| node == Node::Input(input))
+    {
+      Some(node) => Literal {
+        node,
+        negated: false,
+      },
+      None => self.push(Node::Input(input), input_truth_table(input)),
+    }
+  }
+
+  fn gate(&mut self, op: Op, a: Output, b: Output) -> Output {
+    let (a, b) = match (a, b) {
+      (Output::Literal(a), Output::Literal(b)) => (a, b),
+      (Output::Constant(c), other) | (other, Output::Constant(c)) => {
+        return match (op, c) {
+          (Op::And, false) => Output::Constant(false),
+          (Op::Or, true) => Output::Constant(true),
+          (Op::And, true) | (Op::Or, false) | (Op::Xor, false) => other,
+          (Op::Xor, true) => !other,
+        }
+      }
+    };
+    // normalize the operands so that equivalent gates are shared
+    let (a, b) = if (a.node, a.negated) <= (b.node, b.negated) {
+      (a, b)
+    } else {
+      (b, a)
+    };
+    // a xor gate only depends on the parity of the negations
+    let (a, b, negated) = if op == Op::Xor {
+      let negated = a.negated ^ b.negated;
+      (
+        Literal {
+          node: a.node,
+          negated: false,
+        },
+        Literal {
+          node: b.node,
+          negated: false,
+        },
+        negated,
+      )
+    } else {
+      (a, b, false)
+    };
+    let literal = match self.gates.get(&(op, a, b)) {
+      Some(&node) => Literal {
+        node,
+        negated: false,
+      },
+      None => {
+        let table_a = self.truth_table(Output::Literal(a));
+        let table_b = self.truth_table(Output::Literal(b));
+        let table = match op {
+          Op::And => table_a & table_b,
+          Op::Or => table_a | table_b,
+          Op::Xor => table_a ^ table_b,
+        };
+        let literal = self.push(Node::Gate(op, a, b), table);
+        self.gates.insert((op, a, b), literal.node);
+        literal
+      }
+    };
+    Output::Literal(if negated { !literal } else { literal })
+  }
+
+  // implement `f`, splitting on the inputs in `order`
+  fn synthesize(&mut self, f: Function, order: &[usize]) -> Output {
+    if let Some(value) = f.constant() {
+      return Output::Constant(value);
+    }
+
+    // use an input, or reuse an existing node, or their negations
+    for &input in order {
+      let table = input_truth_table(input);
+      if f.matches(table) || f.matches(!table) {
+        let literal = self.input(input);
+        let negated = !f.matches(table);
+        return Output::Literal(if negated { !literal } else { literal });
+      }
+    }
+    for node in 0..self.nodes.len() {
+      let literal = Literal {
+        node,
+        negated: false,
+      };
+      if f.matches(self.truth_tables[node]) {
+        return Output::Literal(literal);
+      }
+      if f.matches(!self.truth_tables[node]) {
+        return Output::Literal(!literal);
+      }
+    }
+
+    let (&input, rest) = order
+      .split_first()
+      .expect("a function of no input is a constant");
+    let f0 = f.cofactor(input, false);
+    let f1 = f.cofactor(input, true);
+    if f0.compatible(&f1) {
+      return self.synthesize(f0.merge(&f1), rest);
+    }
+    let x = Output::Literal(self.input(input));
+
+    // the cases costing a single gate
+    if let Some(c1) = f1.constant() {
+      let g0 = self.synthesize(f0, rest);
+      return if c1 {
+        self.gate(Op::Or, x, g0)
+      } else {
+        self.gate(Op::And, !x, g0)
+      };
+    }
+    if let Some(c0) = f0.constant() {
+      let g1 = self.synthesize(f1, rest);
+      return if c0 {
+        self.gate(Op::Or, !x, g1)
+      } else {
+        self.gate(Op::And, x, g1)
+      };
+    }
+    if f0.compatible(&f1.complement()) {
+      let g0 = self.synthesize(f0.merge(&f1.complement()), rest);
+      return self.gate(Op::Xor, x, g0);
+    }
+
+    // otherwise, keep the cheapest of the Shannon and Davio expansions
+    let mut candidates = Vec::new();
+
+    // f = (x & f1) | (!x & f0)
+    let mut shannon = self.clone();
+    let g0 = shannon.synthesize(f0, rest);
+    let g1 = shannon.synthesize(f1, rest);
+    let t1 = shannon.gate(Op::And, x, g1);
+    let t0 = shannon.gate(Op::And, !x, g0);
+    let output = shannon.gate(Op::Or, t1, t0);
+    candidates.push((shannon, output));
+
+    // f = f0 ^ (x & d) and f = f1 ^ (!x & d), where d only matters where the result is taken
+    // from it
+    for (fixed, other, selector) in [(f0, f1, x), (f1, f0, !x)] {
+      let mut davio = self.clone();
+      let g = davio.synthesize(fixed, rest);
+      let d = Function::new(davio.truth_table(g) ^ other.value, other.care);
+      let gd = davio.synthesize(d, rest);
+      let t = davio.gate(Op::And, selector, gd);
+      let output = davio.gate(Op::Xor, g, t);
+      candidates.push((davio, output));
+    }
+
+    let (builder, output) = candidates
+      .into_iter()
+      .min_by_key(|(builder, _)| builder.gate_count())
+      .unwrap();
+    *self = builder;
+    output
+  }
+}
+
+// all the permutations of `items`
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+  if items.is_empty() {
+    return vec![vec![]];
+  }
+  let mut result = Vec::new();
+  for (i, &first) in items.iter().enumerate() {
+    let mut rest = items.to_vec();
+    rest.remove(i);
+    for mut permutation in permutations(&rest) {
+      permutation.insert(0, first);
+      result.push(permutation);
+    }
+  }
+  result
+}
+
+impl Circuit {
+  /// compile a rule to a circuit for cells with 8 encrypted neighbours
+  pub fn compile(rule: &Rule) -> Circuit {
+    Circuit::compile_for(rule, 8, 0)
+  }
+
+  /// compile a rule to a circuit for cells with fewer encrypted neighbours
+  ///
+  /// The circuit is the cheapest one found, counting the gates of both the neighbour counter and
+  /// the rule, over the decision diagrams of the rule for every order of the inputs, where each
+  /// decision is implemented with the cheaper of a Shannon and a Davio expansion. The search is a
+  /// heuristic: it does not prove that no smaller circuit computes the rule.
+  ///
+  /// # Arguments
+  ///
+  /// * `rule`: the rule
+  /// * `neighbours`: the number of encrypted neighbours of the cells, from 0 to 8
+  /// * `offset`: the number of live neighbours known in the clear, which do not need to be
+  ///   counted
+  pub fn compile_for(rule: &Rule, neighbours: usize, offset: usize) -> Circuit {
+    assert!(neighbours + offset <= 8);
+
+    // rows of the truth table are indexed by the state of the cell in bit 0 and the count above
+    let row = |alive: bool, count: usize| (alive as usize) | (count << 1);
+    let mut candidates = Vec::new();
+
+    // the count can be computed on one bit less when the largest count wraps around to 0, which
+    // is only possible when the rule does not tell them apart
+    let full_width = (usize::BITS - neighbours.leading_zeros()) as usize;
+    let fold = neighbours.is_power_of_two()
+      && [false, true].iter().all(|&alive| {
+        rule.next_state(alive, offset) == rule.next_state(alive, offset + neighbours)
+      });
+    let widths = if fold {
+      vec![full_width - 1, full_width]
+    } else {
+      vec![full_width]
+    };
+
+    for count_bits in widths {
+      let (mut value, mut care) = (0u32, 0u32);
+      for alive in [false, true] {
+        for n in 0..=neighbours {
+          let r = row(alive, n % (1 << count_bits));
+          care |= 1 << r;
+          if rule.next_state(alive, offset + n) {
+            value |= 1 << r;
+          }
+        }
+      }
+      let f = Function::new(value, care);
+
+      // the inputs the rule depends on
+      let support: Vec<usize> = (0..=count_bits)
+        .filter(|&input| {
+          !f.cofactor(input, false)
+            .compatible(&f.cofactor(input, true))
+        })
+        .collect();
+      let counter_gates = if support.iter().any(|&input| input != ALIVE) {
+        count_gate_count(neighbours, count_bits)
+      } else {
+        0
+      };
+
+      for order in permutations(&support) {
+        let mut builder = Builder::default();
+        let output = builder.synthesize(f, &order);
+        candidates.push((
+          counter_gates + builder.gate_count(),
+          count_bits,
+          builder,
+          output,
+        ));
+      }
+    }
+
+    let (_, count_bits, builder, output) = candidates
+      .into_iter()
+      .min_by_key(|(gates, ..)| *gates)
+      .unwrap();
+    let count_bits = if builder
+      .nodes
+      .iter()
+      .any(|node| matches!(node, Node::Input(input) if *input != ALIVE))
+    {
+      count_bits
+    } else {
+      0
+    };
+
+    Circuit {
+      nodes: builder.nodes,
+      output,
+      count_bits,
+      neighbours,
+      offset,
+    }
+  }
+
+  /// number of gates of the rule, without the neighbour counter
+  pub fn gate_count(&self) -> usize {
+    self
+      .nodes
+      .iter()
+      .filter(|node| matches!(node, Node::Gate(..)))
+      .count()
+  }
+
+  /// number of bootstraps to update one cell, neighbour counter included
+  pub fn bootstrap_count(&self) -> usize {
+    let counter = if self.count_bits > 0 {
+      count_gate_count(self.neighbours, self.count_bits)
+    } else {
+      0
+    };
+    counter + self.gate_count()
+  }
+
+  /// evaluate the circuit in the clear
+  ///
+  /// # Arguments
+  ///
+  /// * `alive`: the state of the cell
+  /// * `neighbours`: the number of live encrypted neighbours, from 0 to `self.neighbours`
+  pub fn evaluate(&self, alive: bool, neighbours: usize) -> bool {
+    let mut values = Vec::<bool>::with_capacity(self.nodes.len());
+    let literal = |values: &Vec<bool>, literal: Literal| values[literal.node] ^ literal.negated;
+    for node in &self.nodes {
+      let value = match *node {
+        Node::Input(ALIVE) => alive,
+        Node::Input(input) => (neighbours >> (input - 1)) & 1 == 1,
+        Node::Gate(op, a, b) => op.apply(literal(&values, a), literal(&values, b)),
+      };
+      values.push(value);
+    }
+    match self.output {
+      Output::Constant(value) => value,
+      Output::Literal(l) => literal(&values, l),
+    }
+  }
+
+  /// evaluate the circuit on a batch of encrypted cells
+  ///
+  /// # Arguments
+  ///
+  /// * `server_key`: the server key
+  /// * `cells`: encrypted states of the cells
+  /// * `neighbours`: encrypted states of the neighbours of each cell, `self.neighbours` of them
+  /// * `zero`: encryptions of `false`, at least one per cell, used when the rule gives a constant
+  pub fn evaluate_packed(
+    &self,
+    server_key: &ServerKey,
+    cells: Vec<Ciphertext>,
+    neighbours: Vec<Vec<Ciphertext>>,
+    zero: &[Ciphertext],
+  ) -> Vec<Ciphertext> {
+    let count_bits = if self.count_bits > 0 {
+      count(
+        neighbours,
+        self.count_bits,
+        |a, b| server_key.xor_packed(a, b),
+        |a, b| server_key.and_packed(a, b),
+      )
+    } else {
+      Vec::new()
+    };
+
+    let n_cells = cells.len();
+    let mut values = Vec::<Vec<Ciphertext>>::with_capacity(self.nodes.len());
+    let literal = |values: &Vec<Vec<Ciphertext>>, literal: Literal| {
+      if literal.negated {
+        server_key.not_packed(&values[literal.node])
+      } else {
+        values[literal.node].clone()
+      }
+    };
+    for node in &self.nodes {
+      let value = match *node {
+        Node::Input(ALIVE) => cells.clone(),
+        Node::Input(input) => count_bits[input - 1].clone(),
+        Node::Gate(op, a, b) => {
+          let (a, b) = (literal(&values, a), literal(&values, b));
+          match op {
+            Op::And => server_key.and_packed(&a, &b),
+            Op::Or => server_key.or_packed(&a, &b),
+            Op::Xor => server_key.xor_packed(&a, &b),
+          }
+        }
+      };
+      values.push(value);
+    }
+
+    match self.output {
+      Output::Constant(false) => zero[..n_cells].to_vec(),
+      Output::Constant(true) => server_key.not_packed(&zero[..n_cells]),
+      Output::Literal(l) => literal(&values, l),
+    }
+  }
+}
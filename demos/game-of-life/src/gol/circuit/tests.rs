@@ -0,0 +1,208 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use tfhe::boolean::prelude::*;
+
+use super::*;
+use crate::gol::{Board, Boundary, Zeros};
+
+// rules with a name, see https://conwaylife.com/wiki/List_of_Life-like_rules
+const NAMED_RULES: [&str; 10] = [
+  "B3/S23",
+  "B36/S23",
+  "B2/S",
+  "B3678/S34678",
+  "B3/S012345678",
+  "B1357/S1357",
+  "B368/S245",
+  "B0/S8",
+  "B/S",
+  "B012345678/S",
+];
+
+fn check_circuit(rule: &Rule) {
+  let circuit = Circuit::compile(rule);
+  for alive in [false, true] {
+    for neighbours in 0..=8 {
+      assert_eq!(
+        circuit.evaluate(alive, neighbours),
+        rule.next_state(alive, neighbours),
+        "{rule}, alive: {alive}, {neighbours} neighbours"
+      );
+    }
+  }
+}
+
+#[test]
+fn test_named_rules() {
+  for rule in NAMED_RULES {
+    check_circuit(&rule.parse().unwrap());
+  }
+}
+
+#[test]
+fn test_random_rules() {
+  let mut rng = ChaCha8Rng::seed_from_u64(0);
+  for _ in 0..200 {
+    let rule = Rule {
+      birth: rng.gen(),
+      survival: rng.gen(),
+    };
+    check_circuit(&rule);
+  }
+}
+
+#[test]
+fn test_circuit_cost() {
+  // the previous hand-written circuit took 40 gates for the counter and 5 for the rule
+  let conway = Circuit::compile(&Rule::conway());
+  assert_eq!(conway.count_bits, 3);
+  assert_eq!(conway.bootstrap_count(), 32);
+
+  // Day & Night tells 0 and 8 neighbours apart
+  let day_and_night = Circuit::compile(&"B3678/S34678".parse().unwrap());
+  assert_eq!(day_and_night.count_bits, 4);
+
+  // rules which do not look at the neighbours cost nothing
+  for rule in ["B/S", "B/S012345678", "B012345678/S"] {
+    assert_eq!(
+      Circuit::compile(&rule.parse().unwrap()).bootstrap_count(),
+      0
+    );
+  }
+}
+
+#[test]
+fn test_count() {
+  for count_bits in [3, 4] {
+    for elements in 0..256usize {
+      let bits = count(
+        (0..8).map(|k| (elements >> k) & 1 == 1).collect(),
+        count_bits,
+        |a, b| a ^ b,
+        |a, b| a & b,
+      );
+      let value = bits
+        .iter()
+        .enumerate()
+        .fold(0, |value, (j, &bit)| value | ((bit as usize) << j));
+      assert_eq!(bits.len(), count_bits);
+      assert_eq!(value, elements.count_ones() as usize % (1 << count_bits));
+    }
+  }
+  assert_eq!(count_gate_count(8, 3), 29);
+  assert_eq!(count_gate_count(8, 4), 30);
+}
+
+#[test]
+fn test_border_circuits() {
+  let mut rng = ChaCha8Rng::seed_from_u64(1);
+  let rules = NAMED_RULES.iter().map(|rule| rule.parse().unwrap());
+  let random_rules = (0..20).map(|_| Rule {
+    birth: rng.gen(),
+    survival: rng.gen(),
+  });
+  for rule in rules.chain(random_rules).collect::<Vec<Rule>>() {
+    for neighbours in 0..=8 {
+      for offset in 0..=8 - neighbours {
+        let circuit = Circuit::compile_for(&rule, neighbours, offset);
+        for alive in [false, true] {
+          for count in 0..=neighbours {
+            assert_eq!(
+              circuit.evaluate(alive, count),
+              rule.next_state(alive, offset + count),
+              "{rule}, {neighbours} encrypted and {offset} live trivial neighbours, alive: \
+               {alive}, {count} live encrypted neighbours"
+            );
+          }
+        }
+      }
+    }
+  }
+
+  // fewer neighbours cost fewer gates
+  let conway = Rule::conway();
+  assert!(Circuit::compile_for(&conway, 5, 0).bootstrap_count() < 32);
+  assert_eq!(Circuit::compile_for(&conway, 0, 3).bootstrap_count(), 0);
+}
+
+#[test]
+fn test_encrypted_board() {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+  let zeros: Zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+
+  let mut rng = ChaCha8Rng::seed_from_u64(0);
+  let dimensions = (5, 6);
+  let initial_state: Vec<bool> = (0..30).map(|_| rng.gen()).collect();
+
+  for rule in ["B3/S23", "B36/S23", "B2/S", "B3678/S34678", "B/S"] {
+    let rule: Rule = rule.parse().unwrap();
+    let states = initial_state
+      .iter()
+      .map(|&x| client_key.encrypt(x))
+      .collect();
+    let mut board = Board::with_rule(dimensions.1, states, rule);
+    let mut expected = initial_state.clone();
+
+    for _ in 0..2 {
+      board.update(&server_key, &zeros);
+      expected = rule.step(dimensions, &expected, Boundary::Torus);
+      let decrypted: Vec<bool> = board
+        .states
+        .iter()
+        .map(|ct| client_key.decrypt(ct))
+        .collect();
+      assert_eq!(decrypted, expected, "{rule}");
+    }
+  }
+}
+
+#[test]
+fn test_encrypted_boundaries() {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+  let zeros: Zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+
+  let mut rng = ChaCha8Rng::seed_from_u64(2);
+  let dimensions = (4, 7);
+  let initial_state: Vec<bool> = (0..28).map(|_| rng.gen()).collect();
+
+  let mut bootstraps = Vec::new();
+  for boundary in [Boundary::Torus, Boundary::Dead, Boundary::Mirror] {
+    for rule in ["B3/S23", "B36/S23", "B1357/S1357"] {
+      let rule: Rule = rule.parse().unwrap();
+      let states = initial_state
+        .iter()
+        .map(|&x| client_key.encrypt(x))
+        .collect();
+      let mut board = Board::with_rule(dimensions.1, states, rule);
+      board.boundary = boundary;
+      let mut expected = initial_state.clone();
+
+      server_key.reset_packed_bootstrap_stats();
+      for _ in 0..2 {
+        board.update(&server_key, &zeros);
+        expected = rule.step(dimensions, &expected, boundary);
+        let decrypted: Vec<bool> = board
+          .states
+          .iter()
+          .map(|ct| client_key.decrypt(ct))
+          .collect();
+        assert_eq!(decrypted, expected, "{rule}, {boundary} boundary");
+      }
+      if rule == Rule::conway() {
+        bootstraps.push(server_key.packed_bootstrap_stats().bootstraps);
+      }
+    }
+  }
+
+  // the dead cells beyond the edges are not counted with gates
+  let (torus, dead, mirror) = (bootstraps[0], bootstraps[1], bootstraps[2]);
+  assert_eq!(torus, 2 * 28 * 32);
+  assert_eq!(mirror, torus);
+  assert!(dead < torus);
+}
@@ -0,0 +1,130 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tfhe::boolean::prelude::*;
+#[cfg(feature = "fpga")]
+use tfhe::boolean::server_key::FpgaGates;
+
+use super::board::{Board, DecryptedBoard};
+use super::protocol::Zeros;
+
+/// where the bootstraps of a board are computed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Device {
+  Cpu,
+  /// the FPGA, or its emulator, opened from the `FPGA_IMAGE` and `FPGA_INDEX` variables
+  Accelerator,
+}
+
+impl Device {
+  /// name of the device, as shown in the panes
+  pub fn name(&self) -> &'static str {
+    match self {
+      Device::Cpu => "CPU",
+      #[cfg(feature = "fpga")]
+      Device::Accelerator => "FPGA",
+      #[cfg(not(feature = "fpga"))]
+      Device::Accelerator => "CPU (built without the fpga feature)",
+    }
+  }
+}
+
+/// message sent by a device thread after each generation
+pub enum DeviceUpdate {
+  Generation {
+    device: Device,
+    board: DecryptedBoard,
+  },
+  Failed {
+    device: Device,
+    error: String,
+  },
+}
+
+/// evolve a board on a device in its own thread, until `stop` is set
+///
+/// The packed gates of a thread all run on the same device, so that the CPU and the accelerator
+/// can evolve copies of the same encrypted board concurrently. Each generation is decrypted and
+/// sent to `updates`.
+///
+/// # Arguments
+///
+/// * `device`: where the bootstraps are computed
+/// * `board`: the encrypted board
+/// * `server_key`, `client_key`: the keys
+/// * `zeros`: encryptions of `false`, see [`Board::update`]
+/// * `updates`: where the decrypted boards are sent
+/// * `stop`: set to stop the thread after the current generation
+pub fn spawn_device(
+  device: Device,
+  mut board: Board,
+  server_key: ServerKey,
+  client_key: ClientKey,
+  zeros: Zeros,
+  updates: Sender<DeviceUpdate>,
+  stop: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+  std::thread::spawn(move || {
+    #[cfg(feature = "fpga")]
+    if device == Device::Accelerator {
+      if let Err(err) = server_key.enable_fpga() {
+        let _ = updates.send(DeviceUpdate::Failed {
+          device,
+          error: err.to_string(),
+        });
+        return;
+      }
+    }
+
+    while !stop.load(Ordering::Relaxed) {
+      let now = Instant::now();
+      board.update(&server_key, &zeros);
+      board.tick_time = now.elapsed();
+
+      let decrypted = DecryptedBoard::decrypt(
+        board.dimensions,
+        &board.states,
+        board.tick_time,
+        &client_key,
+      );
+      let update = DeviceUpdate::Generation {
+        device,
+        board: decrypted,
+      };
+      // the receiver is gone when the user quits
+      if updates.send(update).is_err() {
+        return;
+      }
+    }
+  })
+}
+
+/// latencies of the generations computed by a device
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Latencies {
+  pub generations: u32,
+  pub last: Duration,
+  pub total: Duration,
+}
+
+impl Latencies {
+  pub fn record(&mut self, tick_time: Duration) {
+    self.generations += 1;
+    self.last = tick_time;
+    self.total += tick_time;
+  }
+
+  /// mean latency, `None` before the first generation
+  pub fn mean(&self) -> Option<Duration> {
+    (self.generations > 0).then(|| self.total / self.generations)
+  }
+
+  /// how many times faster the device of `self` is than the one of `reference`, from the mean
+  /// latencies
+  pub fn speed_up(&self, reference: &Latencies) -> Option<f64> {
+    Some(reference.mean()?.as_secs_f64() / self.mean()?.as_secs_f64())
+  }
+}
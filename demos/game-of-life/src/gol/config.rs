@@ -0,0 +1,170 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+/// the format of the config files, see [`Config::parse`]
+pub const CONFIG_FORMAT: &str = "a config file holds 10 numbers separated by whitespace: the \
+  wait time between generations in microseconds, the size of a cell in pixels, the numbers of \
+  rows and columns of the board, and the red, green and blue components, from 0 to 1, of the \
+  background and then of the live cells; # starts a comment";
+
+/// configuration of the graphical frontend
+///
+/// # Fields
+///
+/// * `wait_time_micros`: minimum time between two generations
+/// * `pixel_size`: size of a cell, in pixels
+/// * `dimensions`: the numbers of rows and columns of the board
+/// * `col1`: colour of the background, as red, green and blue components from 0 to 1
+/// * `col2`: colour of the live cells
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_game_of_life::gol::*;
+///
+/// let config = Config::parse("0 10  # no wait, 10 pixels\n 32 48\n 0 0 0\n 1 1 0.5").unwrap();
+/// assert_eq!(config.dimensions, (32, 48));
+/// assert_eq!(config.col2, (1., 1., 0.5));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+  pub wait_time_micros: u64,
+  pub pixel_size: usize,
+  pub dimensions: (usize, usize),
+  pub col1: (f32, f32, f32),
+  pub col2: (f32, f32, f32),
+}
+
+/// error returned when reading a config file
+#[derive(Debug)]
+pub enum ConfigError {
+  /// the config file could not be read
+  Io(std::io::Error),
+  /// the file ends before `field`
+  Missing { field: &'static str },
+  /// `value` is not a valid `field`
+  Invalid {
+    field: &'static str,
+    value: String,
+    expected: &'static str,
+  },
+  /// a value follows the last field
+  Trailing(String),
+}
+
+impl Display for ConfigError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ConfigError::Io(err) => write!(f, "cannot read the config file: {err}"),
+      ConfigError::Missing { field } => write!(f, "missing {field}, {CONFIG_FORMAT}"),
+      ConfigError::Invalid {
+        field,
+        value,
+        expected,
+      } => write!(f, "invalid {field} \"{value}\", expected {expected}"),
+      ConfigError::Trailing(value) => {
+        write!(
+          f,
+          "unexpected \"{value}\" after the last field, {CONFIG_FORMAT}"
+        )
+      }
+    }
+  }
+}
+
+impl std::error::Error for ConfigError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      ConfigError::Io(err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl From<std::io::Error> for ConfigError {
+  fn from(err: std::io::Error) -> ConfigError {
+    ConfigError::Io(err)
+  }
+}
+
+// the values of a config file, read one field after the other
+struct Fields<'a>(std::vec::IntoIter<&'a str>);
+
+impl Fields<'_> {
+  fn next<T: FromStr>(
+    &mut self,
+    field: &'static str,
+    expected: &'static str,
+    valid: impl Fn(&T) -> bool,
+  ) -> Result<T, ConfigError> {
+    let value = self.0.next().ok_or(ConfigError::Missing { field })?;
+    match value.parse::<T>() {
+      Ok(parsed) if valid(&parsed) => Ok(parsed),
+      _ => Err(ConfigError::Invalid {
+        field,
+        value: value.to_string(),
+        expected,
+      }),
+    }
+  }
+
+  fn size(&mut self, field: &'static str) -> Result<usize, ConfigError> {
+    self.next(field, "a positive integer", |&size| size > 0)
+  }
+
+  fn colour(&mut self, fields: [&'static str; 3]) -> Result<(f32, f32, f32), ConfigError> {
+    let mut component = |field| {
+      self.next(field, "a number from 0 to 1", |c: &f32| {
+        (0. ..=1.).contains(c)
+      })
+    };
+    Ok((
+      component(fields[0])?,
+      component(fields[1])?,
+      component(fields[2])?,
+    ))
+  }
+}
+
+impl Config {
+  /// read a config file, see [`Config::parse`]
+  pub fn read(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+    Config::parse(&std::fs::read_to_string(path)?)
+  }
+
+  /// parse the content of a config file, in the format described by [`CONFIG_FORMAT`]
+  pub fn parse(content: &str) -> Result<Config, ConfigError> {
+    let values: Vec<&str> = content
+      .lines()
+      .flat_map(|line| line.split('#').next().unwrap().split_whitespace())
+      .collect();
+    let mut fields = Fields(values.into_iter());
+
+    let config = Config {
+      wait_time_micros: fields.next("wait time", "a number of microseconds", |_: &u64| true)?,
+      pixel_size: fields.size("pixel size")?,
+      dimensions: (
+        fields.size("number of rows")?,
+        fields.size("number of columns")?,
+      ),
+      col1: fields.colour([
+        "background red component",
+        "background green component",
+        "background blue component",
+      ])?,
+      col2: fields.colour([
+        "live cell red component",
+        "live cell green component",
+        "live cell blue component",
+      ])?,
+    };
+    match fields.0.next() {
+      Some(value) => Err(ConfigError::Trailing(value.to_string())),
+      None => Ok(config),
+    }
+  }
+}
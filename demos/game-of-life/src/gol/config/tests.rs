@@ -0,0 +1,80 @@
+use super::*;
+
+#[test]
+fn test_parse_config() {
+  let config = Config::parse(
+    "# a commented config\n\
+     50000  # wait time\n\
+     12\n\
+     20 30\n\
+     0.1 0.2 0.3\n\
+     1 1 1\n",
+  )
+  .unwrap();
+  assert_eq!(
+    config,
+    Config {
+      wait_time_micros: 50000,
+      pixel_size: 12,
+      dimensions: (20, 30),
+      col1: (0.1, 0.2, 0.3),
+      col2: (1., 1., 1.),
+    }
+  );
+
+  // the shipped config is valid
+  Config::read(concat!(env!("CARGO_MANIFEST_DIR"), "/gui.config")).unwrap();
+}
+
+#[test]
+fn test_invalid_configs() {
+  let parse = |content: &str| Config::parse(content).unwrap_err();
+
+  assert!(matches!(
+    parse("0 10 20 20 0 0 0 1 1"),
+    ConfigError::Missing {
+      field: "live cell blue component"
+    }
+  ));
+  assert!(matches!(
+    parse(""),
+    ConfigError::Missing { field: "wait time" }
+  ));
+  assert!(matches!(
+    parse("0 10 20 20 0 0 0 1 1 1 1"),
+    ConfigError::Trailing(value) if value == "1"
+  ));
+  assert!(matches!(
+    parse("-1 10 20 20 0 0 0 1 1 1"),
+    ConfigError::Invalid { field: "wait time", value, .. } if value == "-1"
+  ));
+  assert!(matches!(
+    parse("0 0 20 20 0 0 0 1 1 1"),
+    ConfigError::Invalid {
+      field: "pixel size",
+      ..
+    }
+  ));
+  assert!(matches!(
+    parse("0 10 20 x 0 0 0 1 1 1"),
+    ConfigError::Invalid {
+      field: "number of columns",
+      ..
+    }
+  ));
+  assert!(matches!(
+    parse("0 10 20 20 0 0 1.5 1 1 1"),
+    ConfigError::Invalid {
+      field: "background blue component",
+      ..
+    }
+  ));
+
+  // the errors describe the format
+  let message = parse("0 10").to_string();
+  assert!(message.starts_with("missing number of rows, a config file holds 10 numbers"));
+  assert!(matches!(
+    Config::read("/nonexistent/gui.config"),
+    Err(ConfigError::Io(_))
+  ));
+}
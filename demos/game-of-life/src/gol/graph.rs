@@ -1,32 +1,77 @@
+use std::time::{Duration, Instant};
+
 use tfhe::boolean::prelude::*;
 
-pub use ggez::{conf, event, graphics, Context, GameResult};
+pub use ggez::{conf, event, graphics, Context, ContextBuilder, GameResult};
 
 use super::board::*;
+use super::compare::{Device, Latencies};
+use super::config::Config;
+use super::protocol::Zeros;
+
+// height of the lines below the board, in pixels
+const INFO_HEIGHT: f32 = 48.;
+
+// minimum width of the window, so that the lines below the board fit
+const MIN_WIDTH: f32 = 480.;
 
+/// dimensions of the window showing a board, in pixels
+pub fn window_dimensions(config: &Config) -> (f32, f32) {
+  let pixel_size = config.pixel_size as f32;
+  (
+    (config.dimensions.1 as f32 * pixel_size).max(MIN_WIDTH),
+    config.dimensions.0 as f32 * pixel_size + INFO_HEIGHT,
+  )
+}
+
+/// the state of the graphical frontend
+///
+/// The board is evolved in the event loop, and decrypted after each generation. The window
+/// shows the board, the latency of the updates, and where their bootstraps were computed.
 pub struct MainState {
   board: Board,
-  // time_step: Duration,
+  decrypted: DecryptedBoard,
+  time_step: Duration,
+  last_update: Instant,
   first_frame: bool,
   pixel_size: usize,
   col1: (f32, f32, f32),
   col2: (f32, f32, f32),
   server_key: ServerKey,
-  zeros: (Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
+  zeros: Zeros,
   client_key: ClientKey,
+  device: Device,
+  latencies: Latencies,
+  stats: PackedBootstrapStats,
 }
 
 impl MainState {
+  /// # Arguments
+  ///
+  /// * `board`: the encrypted board
+  /// * `config`: the config of the window
+  /// * `server_key`, `client_key`: the keys
+  /// * `zeros`: encryptions of `false`, see [`Board::update`]
+  /// * `device`: where the bootstraps are computed, only used to label the window
   pub fn new(
     board: Board,
     config: &Config,
     server_key: ServerKey,
-    zeros: (Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
+    zeros: Zeros,
     client_key: ClientKey,
+    device: Device,
   ) -> Result<MainState, Box<dyn std::error::Error>> {
+    let decrypted = DecryptedBoard::decrypt(
+      board.dimensions,
+      &board.states,
+      board.tick_time,
+      &client_key,
+    );
     Ok(MainState {
       board,
-      // time_step: Duration::from_micros(config.wait_time_micros),
+      decrypted,
+      time_step: Duration::from_micros(config.wait_time_micros),
+      last_update: Instant::now(),
       first_frame: true,
       pixel_size: config.pixel_size,
       col1: config.col1,
@@ -34,55 +79,98 @@ impl MainState {
       server_key,
       zeros,
       client_key,
+      device,
+      latencies: Latencies::default(),
+      stats: PackedBootstrapStats::default(),
     })
   }
 }
 
 impl event::EventHandler<ggez::GameError> for MainState {
   fn update(&mut self, _ctx: &mut Context) -> GameResult {
+    // show the initial configuration first
     if self.first_frame {
       self.first_frame = false;
-    } else {
-      // sleep(self.time_step);
-      use std::time::Instant;
-      let now = Instant::now();
-      self.board.update(&self.server_key, &self.zeros);
-      let elapsed = now.elapsed();
-      println!("Elapsed: {:.2?}", elapsed);
+      return Ok(());
+    }
+    if self.last_update.elapsed() < self.time_step {
+      return Ok(());
     }
+
+    self.server_key.reset_packed_bootstrap_stats();
+    let now = Instant::now();
+    self.board.update(&self.server_key, &self.zeros);
+    self.board.tick_time = now.elapsed();
+    self.last_update = Instant::now();
+    self.stats = self.server_key.packed_bootstrap_stats();
+    self.latencies.record(self.board.tick_time);
+
+    self.decrypted = DecryptedBoard::decrypt(
+      self.board.dimensions,
+      &self.board.states,
+      self.board.tick_time,
+      &self.client_key,
+    );
     Ok(())
   }
 
-  fn draw(&mut self, mut ctx: &mut Context) -> GameResult {
+  fn draw(&mut self, ctx: &mut Context) -> GameResult {
     // clear the window
     graphics::clear(ctx, [self.col1.0, self.col1.1, self.col1.2, 1.].into());
+    let col2 = graphics::Color::new(self.col2.0, self.col2.1, self.col2.2, 1.);
 
-    for i in 0..self.board.dimensions.0 {
-      for j in 0..self.board.dimensions.1 {
-        if self
-          .client_key
-          .decrypt(&self.board.states[i * self.board.dimensions.1 + j])
-        {
-          let pixel = graphics::MeshBuilder::new()
-            .rectangle(
-              graphics::DrawMode::Fill(graphics::FillOptions::DEFAULT),
-              graphics::Rect::new(0., 0., self.pixel_size as f32, self.pixel_size as f32),
-              graphics::Color::new(self.col2.0, self.col2.1, self.col2.2, 1.),
-            )
-            .unwrap()
-            .build(&mut ctx)
-            .unwrap();
-          graphics::draw(
-            ctx,
-            &pixel,
-            graphics::DrawParam::new().offset([
-              -((j * self.pixel_size) as f32),
-              -((i * self.pixel_size) as f32),
-            ]),
+    // the live cells, in a single mesh
+    let (n_rows, n_cols) = self.decrypted.dimensions;
+    let pixel_size = self.pixel_size as f32;
+    let mut cells = graphics::MeshBuilder::new();
+    let mut alive = false;
+    for i in 0..n_rows {
+      for j in 0..n_cols {
+        if self.decrypted.states[i * n_cols + j] {
+          alive = true;
+          cells.rectangle(
+            graphics::DrawMode::Fill(graphics::FillOptions::DEFAULT),
+            graphics::Rect::new(
+              j as f32 * pixel_size,
+              i as f32 * pixel_size,
+              pixel_size,
+              pixel_size,
+            ),
+            col2,
           )?;
         }
       }
     }
+    // a mesh cannot be empty
+    if alive {
+      let cells = cells.build(ctx)?;
+      graphics::draw(ctx, &cells, graphics::DrawParam::new())?;
+    }
+
+    // latency and accelerator state, below the board
+    let latency = format!(
+      "generation {} | latency {:.2?} | mean {:.2?}",
+      self.board.generation,
+      self.latencies.last,
+      self.latencies.mean().unwrap_or_default()
+    );
+    let accelerator = format!(
+      "{} | {} of {} batches on the FPGA | {} bootstraps",
+      self.device.name(),
+      self.stats.fpga_batches,
+      self.stats.batches,
+      self.stats.bootstraps
+    );
+    let y = n_rows as f32 * pixel_size;
+    for (k, line) in [latency, accelerator].into_iter().enumerate() {
+      graphics::draw(
+        ctx,
+        &graphics::Text::new(line),
+        graphics::DrawParam::new()
+          .dest([4., y + 4. + 20. * k as f32])
+          .color(col2),
+      )?;
+    }
 
     graphics::present(ctx)?;
     Ok(())
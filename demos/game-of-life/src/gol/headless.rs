@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::io::Write;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tfhe::boolean::prelude::*;
+
+use super::board::Board;
+use super::protocol::Zeros;
+
+#[cfg(test)]
+mod tests;
+
+/// measurements of one generation of a headless run, printed as one line of JSON
+///
+/// # Fields
+///
+/// * `generation`: number of the generation, starting at 1
+/// * `tick_time_micros`: duration of the encrypted update
+/// * `bootstraps`: number of bootstraps of the update
+/// * `batches`: number of packed batches of the update
+/// * `fpga_batches`: number of these batches computed on the FPGA
+/// * `batch_utilisation`: mean fill rate of the batches
+/// * `wrong_cells`: number of decrypted cells differing from the plaintext reference
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationReport {
+  pub generation: usize,
+  pub tick_time_micros: u64,
+  pub bootstraps: u64,
+  pub batches: u64,
+  pub fpga_batches: u64,
+  pub batch_utilisation: f64,
+  pub wrong_cells: usize,
+}
+
+/// evolve an encrypted board without any user interface
+///
+/// Every generation is decrypted and checked against the plaintext reference computed with the
+/// rule of the board, and reported as one line of JSON in `out`.
+///
+/// Returns the number of generations with wrong cells.
+///
+/// # Arguments
+///
+/// * `board`: the encrypted board
+/// * `initial_state`: the plaintext initial states of the cells of the board
+/// * `generations`: the number of generations to compute
+/// * `server_key`, `client_key`: the keys
+/// * `zeros`: encryptions of `false`, see [`Board::update`]
+/// * `out`: where the reports are written
+#[allow(clippy::too_many_arguments)]
+pub fn run_headless(
+  board: &mut Board,
+  initial_state: Vec<bool>,
+  generations: usize,
+  server_key: &ServerKey,
+  client_key: &ClientKey,
+  zeros: &Zeros,
+  out: &mut impl Write,
+) -> Result<usize, Box<dyn Error>> {
+  let mut expected = initial_state;
+  let mut failed_generations = 0;
+
+  for generation in 1..=generations {
+    server_key.reset_packed_bootstrap_stats();
+    let now = Instant::now();
+    board.update(server_key, zeros);
+    board.tick_time = now.elapsed();
+    let stats = server_key.packed_bootstrap_stats();
+
+    expected = board.rule.step(board.dimensions, &expected, board.boundary);
+    let wrong_cells = board
+      .states
+      .iter()
+      .zip(&expected)
+      .filter(|&(ct, &state)| client_key.decrypt(ct) != state)
+      .count();
+    if wrong_cells > 0 {
+      failed_generations += 1;
+    }
+
+    let report = GenerationReport {
+      generation,
+      tick_time_micros: board.tick_time.as_micros() as u64,
+      bootstraps: stats.bootstraps,
+      batches: stats.batches,
+      fpga_batches: stats.fpga_batches,
+      batch_utilisation: stats.batch_utilisation(),
+      wrong_cells,
+    };
+    serde_json::to_writer(&mut *out, &report)?;
+    writeln!(out)?;
+    out.flush()?;
+  }
+
+  Ok(failed_generations)
+}
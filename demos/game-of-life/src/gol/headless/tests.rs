@@ -0,0 +1,49 @@
+use super::*;
+use crate::gol::Rule;
+
+#[test]
+fn test_run_headless() {
+  let client_key = ClientKey::new(&DEMO_PARAMETERS);
+  let server_key = ServerKey::new(&client_key);
+  let zeros: Zeros = (0..FPGA_BOOTSTRAP_PACKING)
+    .map(|_| client_key.encrypt(false))
+    .collect();
+
+  // a glider on a 6x5 board, whose 30 cells need two batches
+  let initial_state: Vec<bool> = (0..30).map(|k| [1, 7, 10, 11, 12].contains(&k)).collect();
+  let states = initial_state
+    .iter()
+    .map(|&x| client_key.encrypt(x))
+    .collect();
+  let mut board = Board::with_rule(5, states, Rule::conway());
+
+  let mut out = Vec::<u8>::new();
+  let failed_generations = run_headless(
+    &mut board,
+    initial_state,
+    3,
+    &server_key,
+    &client_key,
+    &zeros,
+    &mut out,
+  )
+  .unwrap();
+  assert_eq!(failed_generations, 0);
+
+  let reports: Vec<GenerationReport> = String::from_utf8(out)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect();
+  assert_eq!(reports.len(), 3);
+
+  let gates = board.circuit.bootstrap_count() as u64;
+  for (generation, report) in reports.iter().enumerate() {
+    assert_eq!(report.generation, generation + 1);
+    assert_eq!(report.wrong_cells, 0);
+    assert_eq!(report.bootstraps, 30 * gates);
+    assert_eq!(report.batches, 2 * gates);
+    assert_eq!(report.fpga_batches, 0);
+    assert!((report.batch_utilisation - 30. / 46.).abs() < 1e-12);
+  }
+}
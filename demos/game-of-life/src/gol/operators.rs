@@ -1,49 +1,74 @@
+use std::cell::Cell;
+
 use tfhe::boolean::prelude::*;
 
-// add one encrypted bit `a` to the encrypted binary representation `b` of a 3-bit number, with 8
-// identified with 0
-fn add_1(
-  server_key: &ServerKey,
-  a: &Vec<Ciphertext>,
-  b: &(Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
-) -> (Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>) {
-  let c1 = server_key.xor_packed(a, &b.0);
-  let r = server_key.and_packed(a, &b.0);
-  let c2 = server_key.xor_packed(&r, &b.1);
-  let r = server_key.and_packed(&r, &b.1);
-  let c3 = server_key.xor_packed(&r, &b.2);
-  (c1, c2, c3)
+use super::circuit::Circuit;
+use super::protocol::Zeros;
+
+/// number of gates needed to count `n_elements` bits on `count_bits` bits, see [`count`]
+pub fn count_gate_count(n_elements: usize, count_bits: usize) -> usize {
+  let gates = Cell::new(0);
+  count(
+    vec![(); n_elements],
+    count_bits,
+    |_, _| gates.set(gates.get() + 1),
+    |_, _| gates.set(gates.get() + 1),
+  );
+  gates.get()
 }
 
-// sum the encrypted bits in `elements`, starting from an encrypted 3-bit representation of 0
-fn sum(
-  server_key: &ServerKey,
-  elements: [Vec<Ciphertext>; 8],
-  zeros: &(Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
-) -> (Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>) {
-  let mut result = add_1(server_key, &elements[0], zeros);
-  for i in 1..8 {
-    result = add_1(server_key, &elements[i], &result);
+/// count the bits in `elements` on `count_bits` bits, least significant first
+///
+/// The counter only keeps the bits the count can reach so far, and drops the carries which are
+/// known to be zero, which makes it cheaper than a ripple counter with a fixed width.
+///
+/// # Arguments
+///
+/// * `elements`: the bits to count, at least one
+/// * `count_bits`: the number of bits of the result, higher bits are dropped
+/// * `xor`, `and`: the gates
+pub fn count<T: Clone>(
+  elements: Vec<T>,
+  count_bits: usize,
+  mut xor: impl FnMut(&T, &T) -> T,
+  mut and: impl FnMut(&T, &T) -> T,
+) -> Vec<T> {
+  let mut elements = elements.into_iter();
+  let mut bits = vec![elements.next().expect("nothing to count")];
+  let mut max = 1;
+
+  for element in elements {
+    max += 1;
+    let mut carry = Some(element);
+    for (j, bit) in bits.iter_mut().enumerate() {
+      let c = match carry.take() {
+        Some(c) => c,
+        None => break,
+      };
+      // the carry out of bit j is zero when the count cannot reach 2^(j + 1)
+      if (1 << (j + 1)) <= max && j + 1 < count_bits {
+        carry = Some(and(&c, bit));
+      }
+      *bit = xor(&c, bit);
+    }
+    if let Some(c) = carry {
+      bits.push(c);
+    }
   }
-  result
+
+  bits.truncate(count_bits);
+  bits
 }
 
-// check if a cell will be alive after the update
+// compute the next state of a batch of cells with the circuit of the rule
 pub fn is_alive(
   server_key: &ServerKey,
+  circuit: &Circuit,
   cell_p: Vec<Ciphertext>,
-  neighbours_p: [Vec<Ciphertext>; 8],
-  zeros: &(Vec<Ciphertext>, Vec<Ciphertext>, Vec<Ciphertext>),
+  neighbours_p: Vec<Vec<Ciphertext>>,
+  zeros: &Zeros,
   new_states: &mut Vec<Ciphertext>,
 ) {
-  let sum_neighbours = sum(server_key, neighbours_p, zeros);
-  let sum_is_2_or_3 =
-    server_key.and_packed(&sum_neighbours.1, &server_key.not_packed(&sum_neighbours.2));
-  let sum_is_3 = server_key.and_packed(
-    &sum_neighbours.0,
-    &server_key.and_packed(&sum_neighbours.1, &server_key.not_packed(&sum_neighbours.2)),
-  );
-  let mut new_state =
-    server_key.or_packed(&sum_is_3, &server_key.and_packed(&cell_p, &sum_is_2_or_3));
+  let mut new_state = circuit.evaluate_packed(server_key, cell_p, neighbours_p, zeros);
   new_states.append(&mut new_state);
 }
@@ -0,0 +1,329 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use super::rule::Rule;
+
+#[cfg(test)]
+mod tests;
+
+/// largest number of cells of a pattern, so that a malformed pattern file cannot make the reader
+/// allocate arbitrary amounts of memory
+pub const MAX_PATTERN_CELLS: usize = 1 << 24;
+
+/// a Game of Life pattern, read from a pattern file
+///
+/// Three formats are supported, picked from the extension of the file:
+///
+/// * `.rle`: [run length encoded](https://conwaylife.com/wiki/Run_Length_Encoded) patterns, whose
+///   header may give the rule of the pattern
+/// * `.cells`: [plaintext](https://conwaylife.com/wiki/Plaintext) patterns, with `.` for dead and
+///   `O` for live cells
+/// * anything else: rows of space-separated `0`s and `1`s, as in the `initial_state` file of the
+///   demo
+///
+/// # Fields
+///
+/// * `dimensions`: the numbers of rows and columns of the pattern
+/// * `cells`: states of the cells, row by row
+/// * `rule`: the rule given by the pattern file, if any
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_game_of_life::gol::*;
+///
+/// let glider = Pattern::parse_rle("x = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+/// assert_eq!(glider.dimensions, (3, 3));
+/// assert_eq!(glider.rule, Some(Rule::conway()));
+///
+/// // put the glider in the middle of a 5x6 board
+/// let states = glider.place((5, 6)).unwrap();
+/// assert!(states[6 + 2]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+  pub dimensions: (usize, usize),
+  pub cells: Vec<bool>,
+  pub rule: Option<Rule>,
+}
+
+/// error returned when reading or placing a pattern
+#[derive(Debug)]
+pub enum PatternError {
+  /// the pattern file could not be read
+  Io(std::io::Error),
+  /// the pattern file is malformed, `line` starts at 1
+  Syntax { line: usize, message: String },
+  /// the pattern has more than [`MAX_PATTERN_CELLS`] cells
+  TooManyCells { dimensions: (usize, usize) },
+  /// the pattern does not fit in the board
+  TooLarge {
+    pattern: (usize, usize),
+    board: (usize, usize),
+  },
+}
+
+impl Display for PatternError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PatternError::Io(err) => write!(f, "cannot read the pattern: {err}"),
+      PatternError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+      PatternError::TooManyCells { dimensions } => write!(
+        f,
+        "the {}x{} pattern has more than {MAX_PATTERN_CELLS} cells",
+        dimensions.1, dimensions.0
+      ),
+      PatternError::TooLarge { pattern, board } => write!(
+        f,
+        "the {}x{} pattern does not fit in the {}x{} board",
+        pattern.1, pattern.0, board.1, board.0
+      ),
+    }
+  }
+}
+
+impl std::error::Error for PatternError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      PatternError::Io(err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl From<std::io::Error> for PatternError {
+  fn from(err: std::io::Error) -> PatternError {
+    PatternError::Io(err)
+  }
+}
+
+fn syntax_error(line: usize, message: impl Into<String>) -> PatternError {
+  PatternError::Syntax {
+    line: line + 1,
+    message: message.into(),
+  }
+}
+
+// check the dimensions of a pattern before allocating its cells
+fn check_dimensions(n_rows: usize, n_cols: usize) -> Result<(), PatternError> {
+  match n_rows.checked_mul(n_cols) {
+    Some(n_cells) if n_cells <= MAX_PATTERN_CELLS => Ok(()),
+    _ => Err(PatternError::TooManyCells {
+      dimensions: (n_rows, n_cols),
+    }),
+  }
+}
+
+impl Pattern {
+  // build a pattern from rows of at most `n_cols` cells, padded with dead cells
+  fn from_rows(
+    rows: Vec<Vec<bool>>,
+    n_cols: usize,
+    rule: Option<Rule>,
+  ) -> Result<Pattern, PatternError> {
+    let n_rows = rows.len();
+    check_dimensions(n_rows, n_cols)?;
+    let mut cells = Vec::with_capacity(n_rows * n_cols);
+    for mut row in rows {
+      row.resize(n_cols, false);
+      cells.append(&mut row);
+    }
+    Ok(Pattern {
+      dimensions: (n_rows, n_cols),
+      cells,
+      rule,
+    })
+  }
+
+  /// read a pattern file, in the format given by its extension
+  pub fn read(path: impl AsRef<Path>) -> Result<Pattern, PatternError> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("rle") => Pattern::parse_rle(&content),
+      Some("cells") => Pattern::parse_cells(&content),
+      _ => Pattern::parse_grid(&content),
+    }
+  }
+
+  /// parse a run length encoded pattern
+  pub fn parse_rle(content: &str) -> Result<Pattern, PatternError> {
+    let mut lines = content
+      .lines()
+      .enumerate()
+      .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'));
+
+    // header: x = <columns>, y = <rows>[, rule = <rule>]
+    let (header_line, header) = lines
+      .next()
+      .ok_or_else(|| syntax_error(0, "missing header"))?;
+    let (mut x, mut y, mut rule) = (None, None, None);
+    for field in header.split(',') {
+      let (key, value) = field
+        .split_once('=')
+        .ok_or_else(|| syntax_error(header_line, format!("invalid header field \"{field}\"")))?;
+      let value = value.trim();
+      let size = || {
+        value
+          .parse::<usize>()
+          .map_err(|_| syntax_error(header_line, format!("invalid size \"{value}\"")))
+      };
+      match key.trim() {
+        "x" => x = Some(size()?),
+        "y" => y = Some(size()?),
+        "rule" => {
+          rule = Some(
+            value
+              .parse::<Rule>()
+              .map_err(|err| syntax_error(header_line, format!("invalid rule: {err}")))?,
+          )
+        }
+        key => {
+          return Err(syntax_error(
+            header_line,
+            format!("unknown header field \"{key}\""),
+          ))
+        }
+      }
+    }
+    let n_cols = x.ok_or_else(|| syntax_error(header_line, "missing x in the header"))?;
+    let n_rows = y.ok_or_else(|| syntax_error(header_line, "missing y in the header"))?;
+    // the rows of the body are allocated as they are read
+    check_dimensions(n_rows, n_cols)?;
+
+    // body: runs of b (dead), o (alive) and $ (end of row), ended by !
+    let mut rows = vec![Vec::new()];
+    let mut run = None::<usize>;
+    let mut ended = false;
+    'body: for (n_line, line) in lines {
+      for c in line.chars().filter(|c| !c.is_whitespace()) {
+        match c {
+          '0'..='9' => {
+            let digit = c.to_digit(10).unwrap() as usize;
+            let longer = run
+              .unwrap_or(0)
+              .checked_mul(10)
+              .and_then(|run| run.checked_add(digit))
+              .ok_or_else(|| syntax_error(n_line, "run length overflow"))?;
+            run = Some(longer);
+            continue;
+          }
+          'b' | 'o' => {
+            // checked before resizing, the run comes from the file
+            let run = run.unwrap_or(1);
+            let row_number = rows.len();
+            let row = rows.last_mut().unwrap();
+            if run > n_cols - row.len() {
+              return Err(syntax_error(
+                n_line,
+                format!("row {row_number} is wider than x"),
+              ));
+            }
+            row.resize(row.len() + run, c == 'o');
+          }
+          '$' => {
+            let run = run.unwrap_or(1);
+            if run > n_rows.saturating_sub(rows.len()) {
+              return Err(syntax_error(n_line, "more rows than y"));
+            }
+            rows.resize(rows.len() + run, Vec::new());
+          }
+          '!' => {
+            ended = true;
+            break 'body;
+          }
+          c => return Err(syntax_error(n_line, format!("unexpected '{c}'"))),
+        }
+        run = None;
+      }
+    }
+    if !ended {
+      return Err(syntax_error(
+        content.lines().count().saturating_sub(1),
+        "missing ! at the end",
+      ));
+    }
+
+    rows.resize(n_rows, Vec::new());
+    Pattern::from_rows(rows, n_cols, rule)
+  }
+
+  /// parse a plaintext pattern
+  pub fn parse_cells(content: &str) -> Result<Pattern, PatternError> {
+    let mut rows = Vec::new();
+    for (n_line, line) in content.lines().enumerate() {
+      if line.starts_with('!') {
+        continue;
+      }
+      let row = line
+        .trim_end()
+        .chars()
+        .map(|c| match c {
+          '.' => Ok(false),
+          'O' | '*' => Ok(true),
+          c => Err(syntax_error(n_line, format!("unexpected '{c}'"))),
+        })
+        .collect::<Result<Vec<bool>, PatternError>>()?;
+      rows.push(row);
+    }
+    let n_cols = rows.iter().map(Vec::len).max().unwrap_or(0);
+    Pattern::from_rows(rows, n_cols, None)
+  }
+
+  /// parse rows of space-separated `0`s and `1`s, which all need to have the same length
+  pub fn parse_grid(content: &str) -> Result<Pattern, PatternError> {
+    let mut rows = Vec::<Vec<bool>>::new();
+    for (n_line, line) in content.lines().enumerate() {
+      if line.trim().is_empty() {
+        continue;
+      }
+      let row = line
+        .split_whitespace()
+        .map(|cell| match cell {
+          "0" => Ok(false),
+          "1" => Ok(true),
+          cell => Err(syntax_error(
+            n_line,
+            format!("\"{cell}\" is neither 0 nor 1"),
+          )),
+        })
+        .collect::<Result<Vec<bool>, PatternError>>()?;
+      if let Some(first) = rows.first() {
+        if row.len() != first.len() {
+          return Err(syntax_error(
+            n_line,
+            format!("{} cells, but the first row has {}", row.len(), first.len()),
+          ));
+        }
+      }
+      rows.push(row);
+    }
+    let n_cols = rows.first().map_or(0, Vec::len);
+    Pattern::from_rows(rows, n_cols, None)
+  }
+
+  /// place the pattern in the middle of an empty board
+  ///
+  /// # Arguments
+  ///
+  /// * `dimensions`: the numbers of rows and columns of the board
+  pub fn place(&self, dimensions: (usize, usize)) -> Result<Vec<bool>, PatternError> {
+    let (n_rows, n_cols) = dimensions;
+    let (p_rows, p_cols) = self.dimensions;
+    if p_rows > n_rows || p_cols > n_cols {
+      return Err(PatternError::TooLarge {
+        pattern: self.dimensions,
+        board: dimensions,
+      });
+    }
+
+    let (i0, j0) = ((n_rows - p_rows) / 2, (n_cols - p_cols) / 2);
+    let mut states = vec![false; n_rows * n_cols];
+    for i in 0..p_rows {
+      for j in 0..p_cols {
+        states[(i0 + i) * n_cols + j0 + j] = self.cells[i * p_cols + j];
+      }
+    }
+    Ok(states)
+  }
+}
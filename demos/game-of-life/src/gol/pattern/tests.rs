@@ -0,0 +1,132 @@
+use super::*;
+
+const GLIDER: [bool; 9] = [false, true, false, false, false, true, true, true, true];
+
+fn patterns_dir() -> std::path::PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).join("patterns")
+}
+
+#[test]
+fn test_parse_rle() {
+  let glider = Pattern::parse_rle("#N Glider\nx = 3, y = 3\nbob$2bo$3o!").unwrap();
+  assert_eq!(glider.dimensions, (3, 3));
+  assert_eq!(glider.cells, GLIDER);
+  assert_eq!(glider.rule, None);
+
+  // runs of rows, trailing dead cells omitted, body split over lines
+  let pattern = Pattern::parse_rle("x = 4, y = 4, rule = B36/S23\n2o2$\n3bo!").unwrap();
+  assert_eq!(pattern.rule, Some("B36/S23".parse().unwrap()));
+  let alive: Vec<usize> = (0..16).filter(|&k| pattern.cells[k]).collect();
+  assert_eq!(alive, [0, 1, 11]);
+}
+
+#[test]
+fn test_parse_invalid_rle() {
+  let line = |content: &str| match Pattern::parse_rle(content) {
+    Err(PatternError::Syntax { line, .. }) => line,
+    result => panic!("{result:?}"),
+  };
+  assert_eq!(line(""), 1);
+  assert_eq!(line("x = 3\nbob!"), 1);
+  assert_eq!(line("x = 3, y = 3, rule = 23/3\nbob!"), 1);
+  assert_eq!(line("#C comment\nx = 3, y = 3\nbob$4o!"), 3);
+  assert_eq!(line("x = 3, y = 1\nbob$o!"), 2);
+  assert_eq!(line("x = 3, y = 3\nbxb!"), 2);
+  assert_eq!(line("x = 3, y = 3\nbob$\n2bo"), 3);
+  // huge runs are rejected before anything is allocated
+  assert_eq!(line("x = 3, y = 3\n99999999999999999999999o!"), 2);
+  assert_eq!(line("x = 3, y = 3\n1000000000000o!"), 2);
+  assert_eq!(line("x = 3, y = 3\nbo1000000000000$!"), 2);
+}
+
+#[test]
+fn test_parse_too_many_cells() {
+  let too_many = |result: Result<Pattern, PatternError>| {
+    matches!(result, Err(PatternError::TooManyCells { .. }))
+  };
+  // huge dimensions are rejected from the header, before the body is read
+  assert!(too_many(Pattern::parse_rle(
+    "x = 100000000000, y = 100000000000\n!"
+  )));
+  assert!(too_many(Pattern::parse_rle(&format!(
+    "x = {}, y = 2\n!",
+    usize::MAX
+  ))));
+  assert!(too_many(Pattern::parse_rle(&format!(
+    "x = {}, y = 2\n!",
+    MAX_PATTERN_CELLS
+  ))));
+  assert!(Pattern::parse_rle(&format!("x = {}, y = 1\n!", MAX_PATTERN_CELLS)).is_ok());
+
+  // a long line and many empty lines make a wide and high pattern out of a small file
+  let content = format!("{}{}", ".".repeat(5000), "\n".repeat(5000));
+  assert!(too_many(Pattern::parse_cells(&content)));
+}
+
+#[test]
+fn test_parse_cells() {
+  let pattern = Pattern::parse_cells("!Name: Glider\n.O\n..O\nOOO\n").unwrap();
+  assert_eq!(pattern.dimensions, (3, 3));
+  assert_eq!(pattern.cells, GLIDER);
+
+  assert!(matches!(
+    Pattern::parse_cells(".O.\n.X.\n"),
+    Err(PatternError::Syntax { line: 2, .. })
+  ));
+}
+
+#[test]
+fn test_parse_grid() {
+  let pattern = Pattern::parse_grid("0 1 0 \n0 0 1 \n1 1 1 \n\n").unwrap();
+  assert_eq!(pattern.dimensions, (3, 3));
+  assert_eq!(pattern.cells, GLIDER);
+
+  assert!(matches!(
+    Pattern::parse_grid("0 1 0\n0 1\n"),
+    Err(PatternError::Syntax { line: 2, .. })
+  ));
+  assert!(matches!(
+    Pattern::parse_grid("0 2 0\n"),
+    Err(PatternError::Syntax { line: 1, .. })
+  ));
+}
+
+#[test]
+fn test_read_pattern_files() {
+  let glider = Pattern::read(patterns_dir().join("glider.rle")).unwrap();
+  assert_eq!(glider.cells, GLIDER);
+  assert_eq!(glider.rule, Some(Rule::conway()));
+
+  let replicator = Pattern::read(patterns_dir().join("replicator.rle")).unwrap();
+  assert_eq!(replicator.dimensions, (5, 5));
+
+  let pentadecathlon = Pattern::read(patterns_dir().join("pentadecathlon.cells")).unwrap();
+  assert_eq!(pentadecathlon.dimensions, (3, 10));
+
+  let initial_state = Pattern::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("initial_state"));
+  assert_eq!(initial_state.unwrap().dimensions, (8, 8));
+
+  assert!(matches!(
+    Pattern::read(patterns_dir().join("missing.rle")),
+    Err(PatternError::Io(_))
+  ));
+}
+
+#[test]
+fn test_place() {
+  let glider = Pattern::parse_cells(".O\n..O\nOOO\n").unwrap();
+
+  assert_eq!(glider.place((3, 3)).unwrap(), GLIDER);
+
+  let states = glider.place((5, 4)).unwrap();
+  let alive: Vec<usize> = (0..20).filter(|&k| states[k]).collect();
+  assert_eq!(alive, [5, 10, 12, 13, 14]);
+
+  assert!(matches!(
+    glider.place((2, 5)),
+    Err(PatternError::TooLarge {
+      pattern: (3, 3),
+      board: (2, 5)
+    })
+  ));
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use bincode::Options;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tfhe::boolean::parameters::FPGA_BOOTSTRAP_PACKING;
+use tfhe::boolean::prelude::*;
+
+use super::boundary::Boundary;
+use super::checkpoint::Checkpoint;
+use super::rule::Rule;
+
+#[cfg(test)]
+mod tests;
+
+/// default address of the server
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// size in bytes of the largest message a peer accepts, so that a malformed message cannot make
+/// it allocate arbitrary amounts of memory; server keys of every boolean parameter set fit
+pub const MAX_MESSAGE_SIZE: u64 = 1 << 30;
+
+/// encryptions of `false`, one per cell of a batch, for the rules which kill or give birth to
+/// cells whatever their neighbours
+pub type Zeros = Vec<Ciphertext>;
+
+/// message sent by the client to the server
+#[derive(Serialize, Deserialize)]
+pub enum ClientMessage {
+  /// start a new game
+  ///
+  /// # Fields
+  ///
+  /// * `server_key`: the server key, the server never sees the client key
+  /// * `n_cols`: number of columns of the board
+  /// * `states`: encrypted states of the cells in the initial configuration
+  /// * `rule`: the rule of the game
+  /// * `boundary`: what lies beyond the edges of the board
+  /// * `zeros`: encryptions of `false`, see [`Board::update`](super::Board::update)
+  Start {
+    server_key: ServerKey,
+    n_cols: usize,
+    states: Vec<Ciphertext>,
+    rule: Rule,
+    boundary: Boundary,
+    zeros: Zeros,
+  },
+  /// resume the game from the last checkpoint saved by the server, which was encrypted with the
+  /// same client key
+  ///
+  /// # Fields
+  ///
+  /// * `server_key`: the server key
+  /// * `zeros`: encryptions of `false`, see [`Board::update`](super::Board::update)
+  Resume { server_key: ServerKey, zeros: Zeros },
+  /// compute the next generation
+  Step,
+}
+
+/// message sent by the server to the client
+#[derive(Serialize, Deserialize)]
+pub enum ServerMessage {
+  /// the encrypted states of the cells after one more generation
+  Generation {
+    states: Vec<Ciphertext>,
+    tick_time: Duration,
+  },
+  /// the checkpoint the game was resumed from
+  Resumed(Checkpoint),
+  /// the reason why the server refused the message of the client, before closing the connection
+  Error(String),
+}
+
+/// check the board a client asks the server to start, before building it
+///
+/// # Arguments
+///
+/// * `n_cols`: number of columns of the board
+/// * `n_cells`: number of cells of the board
+pub fn check_board(n_cols: usize, n_cells: usize) -> Result<(), String> {
+  if n_cols == 0 || n_cells == 0 {
+    return Err("the board has no cell".into());
+  }
+  if n_cells % n_cols != 0 {
+    return Err(format!(
+      "{n_cells} cells do not make rows of {n_cols} cells"
+    ));
+  }
+  Ok(())
+}
+
+/// check that a client sent an encryption of `false` for every cell of a batch, see
+/// [`Board::update`](super::Board::update)
+pub fn check_zeros(zeros: &Zeros) -> Result<(), String> {
+  if zeros.len() < FPGA_BOOTSTRAP_PACKING {
+    return Err(format!(
+      "{} encryptions of false for batches of {FPGA_BOOTSTRAP_PACKING} cells",
+      zeros.len()
+    ));
+  }
+  Ok(())
+}
+
+/// the bincode options of the messages: the encoding of `bincode::serialize`, bounded by
+/// [`MAX_MESSAGE_SIZE`]
+fn options() -> impl Options {
+  bincode::DefaultOptions::new()
+    .with_fixint_encoding()
+    .allow_trailing_bytes()
+    .with_limit(MAX_MESSAGE_SIZE)
+}
+
+/// serialize a message with bincode and send it
+pub fn send<T: Serialize>(stream: &mut impl Write, message: &T) -> Result<(), Box<dyn Error>> {
+  options().serialize_into(&mut *stream, message)?;
+  stream.flush()?;
+  Ok(())
+}
+
+/// receive a bincode-serialized message of at most [`MAX_MESSAGE_SIZE`] bytes
+pub fn receive<T: DeserializeOwned>(stream: &mut impl Read) -> Result<T, Box<dyn Error>> {
+  Ok(options().deserialize_from(stream)?)
+}
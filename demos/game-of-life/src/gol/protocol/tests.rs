@@ -0,0 +1,20 @@
+use super::*;
+
+#[test]
+fn test_check_board() {
+  assert!(check_board(3, 6).is_ok());
+  assert!(check_board(1, 1).is_ok());
+  // no column, no cell, or cells that do not fill the last row
+  assert!(check_board(0, 6).is_err());
+  assert!(check_board(0, 0).is_err());
+  assert!(check_board(3, 0).is_err());
+  assert!(check_board(4, 6).is_err());
+}
+
+#[test]
+fn test_check_zeros() {
+  let zeros: Zeros = vec![Ciphertext::Trivial(false); FPGA_BOOTSTRAP_PACKING];
+  assert!(check_zeros(&zeros).is_ok());
+  assert!(check_zeros(&zeros[1..].to_vec()).is_err());
+  assert!(check_zeros(&Vec::new()).is_err());
+}
@@ -0,0 +1,163 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::boundary::Boundary;
+
+#[cfg(test)]
+mod tests;
+
+/// offsets in rows and columns of the 8 neighbours of a cell
+pub const NEIGHBOUR_OFFSETS: [(isize, isize); 8] = [
+  (-1, -1),
+  (-1, 0),
+  (-1, 1),
+  (0, -1),
+  (0, 1),
+  (1, -1),
+  (1, 0),
+  (1, 1),
+];
+
+/// a Life-like rule, in B/S notation
+///
+/// A dead cell with `n` live neighbours is born if `birth[n]`, a live cell with `n` live
+/// neighbours survives if `survival[n]`.
+///
+/// # Example
+///
+/// ```
+/// use homomorphic_game_of_life::gol::*;
+///
+/// let high_life: Rule = "B36/S23".parse().unwrap();
+/// assert!(high_life.next_state(false, 6));
+/// assert!(!Rule::conway().next_state(false, 6));
+/// assert_eq!(high_life.to_string(), "B36/S23");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+  pub birth: [bool; 9],
+  pub survival: [bool; 9],
+}
+
+/// error returned when a rule string is not in B/S notation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleError {
+  /// the rule has no birth (`B`) or no survival (`S`) part
+  MissingPart(char),
+  /// a part appears twice
+  DuplicatePart(char),
+  /// a part does not start with `B` or `S`
+  InvalidPart(String),
+  /// a neighbour count is not a digit from 0 to 8
+  InvalidCount(char),
+}
+
+impl Display for RuleError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RuleError::MissingPart(part) => write!(f, "the rule has no {part} part"),
+      RuleError::DuplicatePart(part) => write!(f, "the rule has two {part} parts"),
+      RuleError::InvalidPart(part) => {
+        write!(f, "\"{part}\" does not start with B or S")
+      }
+      RuleError::InvalidCount(count) => {
+        write!(f, "'{count}' is not a neighbour count from 0 to 8")
+      }
+    }
+  }
+}
+
+impl std::error::Error for RuleError {}
+
+impl Rule {
+  /// Conway's Game of Life, B3/S23
+  pub fn conway() -> Rule {
+    "B3/S23".parse().unwrap()
+  }
+
+  /// the state of a cell after one generation
+  ///
+  /// # Arguments
+  ///
+  /// * `alive`: the current state of the cell
+  /// * `neighbours`: the number of live neighbours, from 0 to 8
+  pub fn next_state(&self, alive: bool, neighbours: usize) -> bool {
+    if alive {
+      self.survival[neighbours]
+    } else {
+      self.birth[neighbours]
+    }
+  }
+
+  /// evolve a plaintext board by one generation
+  ///
+  /// This is the reference the encrypted boards are checked against.
+  ///
+  /// # Arguments
+  ///
+  /// * `dimensions`: the dimensions of the board
+  /// * `states`: states of the cells
+  /// * `boundary`: what lies beyond the edges of the board
+  pub fn step(&self, dimensions: (usize, usize), states: &[bool], boundary: Boundary) -> Vec<bool> {
+    let (nx, ny) = dimensions;
+    (0..nx * ny)
+      .map(|k| {
+        let neighbours = NEIGHBOUR_OFFSETS
+          .iter()
+          .filter_map(|&offset| boundary.neighbour(dimensions, (k / ny, k % ny), offset))
+          .filter(|&neighbour| states[neighbour])
+          .count();
+        self.next_state(states[k], neighbours)
+      })
+      .collect()
+  }
+}
+
+impl FromStr for Rule {
+  type Err = RuleError;
+
+  fn from_str(s: &str) -> Result<Rule, RuleError> {
+    let mut birth = None;
+    let mut survival = None;
+
+    for part in s.trim().split('/') {
+      let mut chars = part.chars();
+      let (name, set) = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some('B') => ('B', &mut birth),
+        Some('S') => ('S', &mut survival),
+        _ => return Err(RuleError::InvalidPart(part.to_string())),
+      };
+      if set.is_some() {
+        return Err(RuleError::DuplicatePart(name));
+      }
+
+      let mut counts = [false; 9];
+      for c in chars {
+        match c.to_digit(10) {
+          Some(n) if n <= 8 => counts[n as usize] = true,
+          _ => return Err(RuleError::InvalidCount(c)),
+        }
+      }
+      *set = Some(counts);
+    }
+
+    Ok(Rule {
+      birth: birth.ok_or(RuleError::MissingPart('B'))?,
+      survival: survival.ok_or(RuleError::MissingPart('S'))?,
+    })
+  }
+}
+
+impl Display for Rule {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let counts = |set: &[bool; 9]| -> String {
+      (0..9)
+        .filter(|&n| set[n])
+        .map(|n| char::from(b'0' + n as u8))
+        .collect()
+    };
+    write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+  }
+}
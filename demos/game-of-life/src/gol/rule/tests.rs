@@ -0,0 +1,85 @@
+use super::*;
+
+#[test]
+fn test_parse_rules() {
+  let conway = Rule::conway();
+  for n in 0..9 {
+    assert_eq!(conway.birth[n], n == 3);
+    assert_eq!(conway.survival[n], n == 2 || n == 3);
+  }
+
+  // case and part order do not matter
+  assert_eq!("s23/b3".parse::<Rule>(), Ok(conway));
+
+  let seeds: Rule = "B2/S".parse().unwrap();
+  assert_eq!(seeds.survival, [false; 9]);
+
+  for rule in ["B3/S23", "B36/S23", "B2/S", "B/S012345678", "B3678/S34678"] {
+    assert_eq!(rule.parse::<Rule>().unwrap().to_string(), rule);
+  }
+}
+
+#[test]
+fn test_parse_invalid_rules() {
+  assert_eq!("B3".parse::<Rule>(), Err(RuleError::MissingPart('S')));
+  assert_eq!("S23".parse::<Rule>(), Err(RuleError::MissingPart('B')));
+  assert_eq!(
+    "B3/S23/B4".parse::<Rule>(),
+    Err(RuleError::DuplicatePart('B'))
+  );
+  assert_eq!(
+    "23/3".parse::<Rule>(),
+    Err(RuleError::InvalidPart("23".to_string()))
+  );
+  assert_eq!("B39/S23".parse::<Rule>(), Err(RuleError::InvalidCount('9')));
+  assert_eq!("B3/S2,3".parse::<Rule>(), Err(RuleError::InvalidCount(',')));
+}
+
+#[test]
+fn test_step_blinker() {
+  // a blinker oscillates with period 2 under Conway's rule
+  let horizontal = [
+    false, false, false, false, false, //
+    false, false, false, false, false, //
+    false, true, true, true, false, //
+    false, false, false, false, false, //
+    false, false, false, false, false, //
+  ];
+  let vertical = [
+    false, false, false, false, false, //
+    false, false, true, false, false, //
+    false, false, true, false, false, //
+    false, false, true, false, false, //
+    false, false, false, false, false, //
+  ];
+  let conway = Rule::conway();
+  assert_eq!(conway.step((5, 5), &horizontal, Boundary::Torus), vertical);
+  assert_eq!(conway.step((5, 5), &vertical, Boundary::Torus), horizontal);
+}
+
+#[test]
+fn test_step_periodic_boundary() {
+  // every cell of a 3x3 torus has the 8 other cells as neighbours
+  let rule: Rule = "B8/S".parse().unwrap();
+  let mut states = [true; 9];
+  states[4] = false;
+  let next = rule.step((3, 3), &states, Boundary::Torus);
+  assert_eq!(next, (0..9).map(|k| k == 4).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_step_boundaries() {
+  // a block in the corner of a rectangular board
+  let rule = Rule::conway();
+  let states = [
+    true, true, false, false, //
+    true, true, false, false, //
+    false, false, false, false, //
+  ];
+  // the block is stable without the cells beyond the edges
+  assert_eq!(rule.step((3, 4), &states, Boundary::Dead), states);
+  // the copies of the block beyond the edges crowd its corner cells, which die
+  let next = rule.step((3, 4), &states, Boundary::Mirror);
+  assert!(!next[0] && !next[1] && !next[4]);
+  assert!(rule.step((3, 4), &states, Boundary::Torus) != next);
+}
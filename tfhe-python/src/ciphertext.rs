@@ -0,0 +1,50 @@
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use tfhe::boolean::ciphertext::Ciphertext;
+use tfhe::boolean::engine::Gate;
+
+/// An encrypted boolean, or a trivial one created by `ServerKey.trivial_encrypt`.
+#[pyclass(name = "Ciphertext", module = "tfhe_boolean")]
+#[derive(Clone)]
+pub struct PyCiphertext(pub(crate) Ciphertext);
+
+#[pymethods]
+impl PyCiphertext {
+    /// Whether the value is in clear, which the gates compute without bootstrap.
+    #[getter]
+    fn is_trivial(&self) -> bool {
+        matches!(self.0, Ciphertext::Trivial(_))
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        Ok(PyBytes::new(py, &crate::serialize(&self.0)?))
+    }
+
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        crate::deserialize(data).map(Self)
+    }
+}
+
+/// The gates of a packed batch.
+#[pyclass(name = "Gate", module = "tfhe_boolean")]
+#[derive(Copy, Clone)]
+pub enum PyGate {
+    #[pyo3(name = "AND")]
+    And,
+    #[pyo3(name = "OR")]
+    Or,
+    #[pyo3(name = "XOR")]
+    Xor,
+}
+
+impl From<PyGate> for Gate {
+    fn from(gate: PyGate) -> Self {
+        match gate {
+            PyGate::And => Gate::AND,
+            PyGate::Or => Gate::OR,
+            PyGate::Xor => Gate::XOR,
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use tfhe::boolean::ciphertext::Ciphertext;
+use tfhe::boolean::client_key::ClientKey;
+use tfhe::boolean::engine::Gate;
+#[cfg(feature = "fpga")]
+use tfhe::boolean::server_key::FpgaGates;
+use tfhe::boolean::server_key::{
+    BinaryBooleanGates, PackedBinaryBooleanGates, PackedBootstrapStats, ServerKey,
+};
+
+use crate::ciphertext::{PyCiphertext, PyGate};
+use crate::parameters::PyBooleanParameters;
+use crate::FpgaError;
+
+/// The secret key, encrypting and decrypting booleans.
+#[pyclass(name = "ClientKey", module = "tfhe_boolean")]
+#[derive(Clone)]
+pub struct PyClientKey(pub(crate) ClientKey);
+
+#[pymethods]
+impl PyClientKey {
+    #[new]
+    pub fn new(parameters: &PyBooleanParameters) -> Self {
+        Self(ClientKey::new(&parameters.0))
+    }
+
+    #[getter]
+    fn parameters(&self) -> PyBooleanParameters {
+        PyBooleanParameters(self.0.parameters)
+    }
+
+    fn encrypt(&self, value: bool) -> PyCiphertext {
+        PyCiphertext(self.0.encrypt(value))
+    }
+
+    fn decrypt(&self, ct: &PyCiphertext) -> bool {
+        self.0.decrypt(&ct.0)
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        Ok(PyBytes::new(py, &crate::serialize(&self.0)?))
+    }
+
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        crate::deserialize(data).map(Self)
+    }
+}
+
+/// Counters of the packed bootstraps computed by the calling thread.
+#[pyclass(name = "PackedBootstrapStats", module = "tfhe_boolean", get_all)]
+#[derive(Copy, Clone)]
+pub struct PyPackedBootstrapStats {
+    /// Number of batches, of at most `FPGA_BOOTSTRAP_PACKING` bootstraps.
+    batches: u64,
+    /// Number of bootstraps computed in these batches.
+    bootstraps: u64,
+    /// Number of batches computed on the FPGA.
+    fpga_batches: u64,
+}
+
+impl From<PackedBootstrapStats> for PyPackedBootstrapStats {
+    fn from(stats: PackedBootstrapStats) -> Self {
+        Self {
+            batches: stats.batches,
+            bootstraps: stats.bootstraps,
+            fpga_batches: stats.fpga_batches,
+        }
+    }
+}
+
+#[pymethods]
+impl PyPackedBootstrapStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "PackedBootstrapStats(batches={}, bootstraps={}, fpga_batches={})",
+            self.batches, self.bootstraps, self.fpga_batches
+        )
+    }
+}
+
+/// The public key computing the gates on the ciphertexts of a client key.
+///
+/// The gates named after a Python keyword end with an underscore, like in the `operator`
+/// module.
+#[pyclass(name = "ServerKey", module = "tfhe_boolean")]
+#[derive(Clone)]
+pub struct PyServerKey(pub(crate) ServerKey);
+
+#[pymethods]
+impl PyServerKey {
+    #[new]
+    pub fn new(client_key: &PyClientKey) -> Self {
+        Self(ServerKey::new(&client_key.0))
+    }
+
+    fn trivial_encrypt(&self, value: bool) -> PyCiphertext {
+        PyCiphertext(self.0.trivial_encrypt(value))
+    }
+
+    fn and_(&self, lhs: &PyCiphertext, rhs: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.and(&lhs.0, &rhs.0))
+    }
+
+    fn or_(&self, lhs: &PyCiphertext, rhs: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.or(&lhs.0, &rhs.0))
+    }
+
+    fn xor(&self, lhs: &PyCiphertext, rhs: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.xor(&lhs.0, &rhs.0))
+    }
+
+    fn nand(&self, lhs: &PyCiphertext, rhs: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.nand(&lhs.0, &rhs.0))
+    }
+
+    fn nor(&self, lhs: &PyCiphertext, rhs: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.nor(&lhs.0, &rhs.0))
+    }
+
+    fn xnor(&self, lhs: &PyCiphertext, rhs: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.xnor(&lhs.0, &rhs.0))
+    }
+
+    fn not_(&self, ct: &PyCiphertext) -> PyCiphertext {
+        PyCiphertext(self.0.not(&ct.0))
+    }
+
+    fn mux(
+        &self,
+        condition: &PyCiphertext,
+        then_ct: &PyCiphertext,
+        else_ct: &PyCiphertext,
+    ) -> PyCiphertext {
+        PyCiphertext(self.0.mux(&condition.0, &then_ct.0, &else_ct.0))
+    }
+
+    /// Compute `gates[i]` on `lhs[i]` and `rhs[i]` for every `i`.
+    ///
+    /// The bootstraps are packed in batches of `FPGA_BOOTSTRAP_PACKING` gates, computed by the
+    /// FPGA when it is enabled for the calling thread. The GIL is released meanwhile.
+    fn gates_packed(
+        &self,
+        py: Python<'_>,
+        gates: Vec<PyGate>,
+        lhs: Vec<PyCiphertext>,
+        rhs: Vec<PyCiphertext>,
+    ) -> PyResult<Vec<PyCiphertext>> {
+        let gates: Vec<Gate> = gates.into_iter().map(Gate::from).collect();
+        self.packed(py, gates, lhs, rhs)
+    }
+
+    fn and_packed(
+        &self,
+        py: Python<'_>,
+        lhs: Vec<PyCiphertext>,
+        rhs: Vec<PyCiphertext>,
+    ) -> PyResult<Vec<PyCiphertext>> {
+        self.packed(py, vec![Gate::AND; lhs.len()], lhs, rhs)
+    }
+
+    fn or_packed(
+        &self,
+        py: Python<'_>,
+        lhs: Vec<PyCiphertext>,
+        rhs: Vec<PyCiphertext>,
+    ) -> PyResult<Vec<PyCiphertext>> {
+        self.packed(py, vec![Gate::OR; lhs.len()], lhs, rhs)
+    }
+
+    fn xor_packed(
+        &self,
+        py: Python<'_>,
+        lhs: Vec<PyCiphertext>,
+        rhs: Vec<PyCiphertext>,
+    ) -> PyResult<Vec<PyCiphertext>> {
+        self.packed(py, vec![Gate::XOR; lhs.len()], lhs, rhs)
+    }
+
+    fn not_packed(&self, cts: Vec<PyCiphertext>) -> Vec<PyCiphertext> {
+        let cts: Vec<Ciphertext> = cts.into_iter().map(|ct| ct.0).collect();
+        self.0
+            .not_packed(&cts)
+            .into_iter()
+            .map(PyCiphertext)
+            .collect()
+    }
+
+    fn packed_bootstrap_stats(&self) -> PyPackedBootstrapStats {
+        self.0.packed_bootstrap_stats().into()
+    }
+
+    fn reset_packed_bootstrap_stats(&self) {
+        self.0.reset_packed_bootstrap_stats()
+    }
+
+    /// Upload the bootstrapping key to the FPGA, which then computes the packed gates of the
+    /// calling thread.
+    ///
+    /// Raises `FpgaError` when the module is built without the `fpga` feature, when
+    /// `FPGA_IMAGE` or `FPGA_INDEX` do not name a card, or when the key does not have the
+    /// parameters implemented by the image, `DEMO_PARAMETERS`.
+    fn enable_fpga(&self) -> PyResult<()> {
+        #[cfg(feature = "fpga")]
+        {
+            self.0
+                .enable_fpga()
+                .map_err(|err| FpgaError::new_err(err.to_string()))
+        }
+        #[cfg(not(feature = "fpga"))]
+        {
+            Err(FpgaError::new_err(
+                "tfhe_boolean is built without the fpga feature",
+            ))
+        }
+    }
+
+    /// Compute the packed gates of the calling thread on the CPU again.
+    fn disable_fpga(&self) {
+        #[cfg(feature = "fpga")]
+        self.0.disable_fpga();
+    }
+
+    /// Whether the packed gates of the calling thread run on the FPGA.
+    #[getter]
+    fn fpga_enabled(&self) -> bool {
+        #[cfg(feature = "fpga")]
+        {
+            self.0.fpga_enabled()
+        }
+        #[cfg(not(feature = "fpga"))]
+        {
+            false
+        }
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        Ok(PyBytes::new(py, &crate::serialize(&self.0)?))
+    }
+
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        crate::deserialize(data).map(Self)
+    }
+}
+
+impl PyServerKey {
+    // the packed gates, computed without the GIL: the engine, and the FPGA enabled for it, belong
+    // to the calling thread, which other Python threads do not need to wait for
+    fn packed(
+        &self,
+        py: Python<'_>,
+        gates: Vec<Gate>,
+        lhs: Vec<PyCiphertext>,
+        rhs: Vec<PyCiphertext>,
+    ) -> PyResult<Vec<PyCiphertext>> {
+        if lhs.len() != gates.len() || rhs.len() != gates.len() {
+            return Err(PyValueError::new_err(format!(
+                "The packed gates need as many operands as gates, got {} gates, {} left and {} \
+                right operands",
+                gates.len(),
+                lhs.len(),
+                rhs.len()
+            )));
+        }
+        let lhs: Vec<Ciphertext> = lhs.into_iter().map(|ct| ct.0).collect();
+        let rhs: Vec<Ciphertext> = rhs.into_iter().map(|ct| ct.0).collect();
+
+        let outputs = py.allow_threads(|| self.0.gates_packed(&gates, &lhs, &rhs));
+        Ok(outputs.into_iter().map(PyCiphertext).collect())
+    }
+}
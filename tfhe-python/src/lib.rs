@@ -0,0 +1,63 @@
+//! Python bindings of the boolean layer of TFHE-rs.
+//!
+//! The `tfhe_boolean` module exposes the boolean parameter sets, the client and server keys, the
+//! scalar gates and the packed gates, whose bootstraps are computed in batches of
+//! [`FPGA_BOOTSTRAP_PACKING`] by the FPT accelerator when it is enabled, or by the CPU otherwise.
+//!
+//! Like in the Rust API, the accelerator and the counters of the packed bootstraps belong to the
+//! thread calling the gates: a key enabling the FPGA from a Python thread only accelerates the
+//! gates called from this thread.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use tfhe::boolean::parameters::FPGA_BOOTSTRAP_PACKING;
+
+mod ciphertext;
+mod keys;
+mod parameters;
+
+use ciphertext::{PyCiphertext, PyGate};
+use keys::{PyClientKey, PyPackedBootstrapStats, PyServerKey};
+use parameters::PyBooleanParameters;
+
+create_exception!(
+    tfhe_boolean,
+    FpgaError,
+    PyException,
+    "The FPGA cannot be enabled for the calling thread."
+);
+
+fn serialize<T: Serialize>(value: &T) -> PyResult<Vec<u8>> {
+    bincode::serialize(value).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+fn deserialize<T: DeserializeOwned>(data: &[u8]) -> PyResult<T> {
+    bincode::deserialize(data).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Generate a client key and the matching server key.
+#[pyfunction]
+fn gen_keys(parameters: &PyBooleanParameters) -> (PyClientKey, PyServerKey) {
+    let client_key = PyClientKey::new(parameters);
+    let server_key = PyServerKey::new(&client_key);
+    (client_key, server_key)
+}
+
+#[pymodule]
+fn tfhe_boolean(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyBooleanParameters>()?;
+    m.add_class::<PyClientKey>()?;
+    m.add_class::<PyServerKey>()?;
+    m.add_class::<PyCiphertext>()?;
+    m.add_class::<PyGate>()?;
+    m.add_class::<PyPackedBootstrapStats>()?;
+    m.add_function(wrap_pyfunction!(gen_keys, m)?)?;
+    m.add("FpgaError", py.get_type::<FpgaError>())?;
+    m.add("FPGA_BOOTSTRAP_PACKING", FPGA_BOOTSTRAP_PACKING)?;
+    parameters::add_parameter_sets(m)?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use pyo3::basic::CompareOp;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use tfhe::boolean::parameters::{
+    BooleanParameters, EncryptionKeyChoice, DEFAULT_PARAMETERS, DEFAULT_PARAMETERS_KS_PBS,
+    DEMO_PARAMETERS, PARAMETERS_ERROR_PROB_2_POW_MINUS_165,
+    PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS, TFHE_LIB_PARAMETERS,
+};
+
+/// A set of boolean parameters.
+///
+/// Only the sets exported by the module, like `DEFAULT_PARAMETERS`, are correct and secure:
+/// `DEMO_PARAMETERS` is the set implemented by the FPGA image, and is only meant for tests and
+/// demonstrations.
+#[pyclass(name = "BooleanParameters", module = "tfhe_boolean")]
+#[derive(Clone)]
+pub struct PyBooleanParameters(pub(crate) BooleanParameters);
+
+#[pymethods]
+impl PyBooleanParameters {
+    #[getter]
+    fn lwe_dimension(&self) -> usize {
+        self.0.lwe_dimension.0
+    }
+
+    #[getter]
+    fn glwe_dimension(&self) -> usize {
+        self.0.glwe_dimension.0
+    }
+
+    #[getter]
+    fn polynomial_size(&self) -> usize {
+        self.0.polynomial_size.0
+    }
+
+    #[getter]
+    fn lwe_modular_std_dev(&self) -> f64 {
+        self.0.lwe_modular_std_dev.0
+    }
+
+    #[getter]
+    fn glwe_modular_std_dev(&self) -> f64 {
+        self.0.glwe_modular_std_dev.0
+    }
+
+    #[getter]
+    fn pbs_base_log(&self) -> usize {
+        self.0.pbs_base_log.0
+    }
+
+    #[getter]
+    fn pbs_level(&self) -> usize {
+        self.0.pbs_level.0
+    }
+
+    #[getter]
+    fn ks_base_log(&self) -> usize {
+        self.0.ks_base_log.0
+    }
+
+    #[getter]
+    fn ks_level(&self) -> usize {
+        self.0.ks_level.0
+    }
+
+    /// `"small"` or `"big"`, the LWE key encrypting the ciphertexts.
+    #[getter]
+    fn encryption_key_choice(&self) -> &'static str {
+        match self.0.encryption_key_choice {
+            EncryptionKeyChoice::Big => "big",
+            EncryptionKeyChoice::Small => "small",
+        }
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        Ok(PyBytes::new(py, &crate::serialize(&self.0)?))
+    }
+
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        crate::deserialize(data).map(Self)
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => (self.0 == other.0).into_py(py),
+            CompareOp::Ne => (self.0 != other.0).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+pub(crate) fn add_parameter_sets(m: &PyModule) -> PyResult<()> {
+    let sets = [
+        ("DEFAULT_PARAMETERS", DEFAULT_PARAMETERS),
+        ("DEFAULT_PARAMETERS_KS_PBS", DEFAULT_PARAMETERS_KS_PBS),
+        (
+            "PARAMETERS_ERROR_PROB_2_POW_MINUS_165",
+            PARAMETERS_ERROR_PROB_2_POW_MINUS_165,
+        ),
+        (
+            "PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS",
+            PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS,
+        ),
+        ("TFHE_LIB_PARAMETERS", TFHE_LIB_PARAMETERS),
+        ("DEMO_PARAMETERS", DEMO_PARAMETERS),
+    ];
+    for (name, parameters) in sets {
+        m.add(name, PyBooleanParameters(parameters).into_py(m.py()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+import itertools
+
+import pytest
+
+import tfhe_boolean as tb
+
+GATES = [tb.Gate.AND, tb.Gate.OR, tb.Gate.XOR]
+
+
+def evaluate(gate, lhs, rhs):
+    if gate == tb.Gate.AND:
+        return lhs and rhs
+    if gate == tb.Gate.OR:
+        return lhs or rhs
+    return lhs != rhs
+
+
+@pytest.fixture(scope="module")
+def keys():
+    return tb.gen_keys(tb.DEMO_PARAMETERS)
+
+
+def test_parameter_sets():
+    assert tb.DEMO_PARAMETERS.lwe_dimension == 16
+    assert tb.DEMO_PARAMETERS.encryption_key_choice == "small"
+    assert tb.DEFAULT_PARAMETERS_KS_PBS.encryption_key_choice == "big"
+    assert tb.DEFAULT_PARAMETERS != tb.TFHE_LIB_PARAMETERS
+    assert tb.FPGA_BOOTSTRAP_PACKING == 23
+
+
+def test_encrypt_decrypt(keys):
+    client_key, server_key = keys
+    assert client_key.parameters == tb.DEMO_PARAMETERS
+    for value in (False, True):
+        assert client_key.decrypt(client_key.encrypt(value)) == value
+        trivial = server_key.trivial_encrypt(value)
+        assert trivial.is_trivial
+        assert client_key.decrypt(trivial) == value
+
+
+def test_scalar_gates(keys):
+    client_key, server_key = keys
+    gates = [
+        (server_key.and_, lambda lhs, rhs: lhs and rhs),
+        (server_key.or_, lambda lhs, rhs: lhs or rhs),
+        (server_key.xor, lambda lhs, rhs: lhs != rhs),
+        (server_key.nand, lambda lhs, rhs: not (lhs and rhs)),
+        (server_key.nor, lambda lhs, rhs: not (lhs or rhs)),
+        (server_key.xnor, lambda lhs, rhs: lhs == rhs),
+    ]
+    for (gate, expected), lhs, rhs in itertools.product(gates, (False, True), (False, True)):
+        ct_lhs = client_key.encrypt(lhs)
+        ct_rhs = server_key.trivial_encrypt(rhs) if lhs else client_key.encrypt(rhs)
+        assert client_key.decrypt(gate(ct_lhs, ct_rhs)) == expected(lhs, rhs)
+
+    for value in (False, True):
+        assert client_key.decrypt(server_key.not_(client_key.encrypt(value))) == (not value)
+
+    for condition, then_value, else_value in itertools.product((False, True), repeat=3):
+        result = server_key.mux(
+            client_key.encrypt(condition),
+            client_key.encrypt(then_value),
+            client_key.encrypt(else_value),
+        )
+        assert client_key.decrypt(result) == (then_value if condition else else_value)
+
+
+def test_gates_packed(keys):
+    client_key, server_key = keys
+    # two full batches and a partial one
+    length = 2 * tb.FPGA_BOOTSTRAP_PACKING + 4
+    gates = [GATES[i % 3] for i in range(length)]
+    lhs = [i % 2 == 1 for i in range(length)]
+    rhs = [(i // 2) % 2 == 1 for i in range(length)]
+    ct_lhs = [client_key.encrypt(value) for value in lhs]
+    ct_rhs = [
+        server_key.trivial_encrypt(value) if i % 7 == 0 else client_key.encrypt(value)
+        for i, value in enumerate(rhs)
+    ]
+
+    server_key.reset_packed_bootstrap_stats()
+    results = server_key.gates_packed(gates, ct_lhs, ct_rhs)
+
+    stats = server_key.packed_bootstrap_stats()
+    assert stats.batches == 3
+    assert stats.bootstraps == length
+    assert stats.fpga_batches == 0
+    assert [client_key.decrypt(ct) for ct in results] == [
+        evaluate(gate, l, r) for gate, l, r in zip(gates, lhs, rhs)
+    ]
+
+
+def test_single_gate_packed(keys):
+    client_key, server_key = keys
+    lhs = [True, True, False, False]
+    rhs = [True, False, True, False]
+    ct_lhs = [client_key.encrypt(value) for value in lhs]
+    ct_rhs = [client_key.encrypt(value) for value in rhs]
+
+    packed_gates = [server_key.and_packed, server_key.or_packed, server_key.xor_packed]
+    for packed, gate in zip(packed_gates, GATES):
+        results = packed(ct_lhs, ct_rhs)
+        assert [client_key.decrypt(ct) for ct in results] == [
+            evaluate(gate, l, r) for l, r in zip(lhs, rhs)
+        ]
+
+    results = server_key.not_packed(ct_lhs)
+    assert [client_key.decrypt(ct) for ct in results] == [not value for value in lhs]
+
+
+def test_packed_lengths(keys):
+    client_key, server_key = keys
+    ct = client_key.encrypt(True)
+    with pytest.raises(ValueError):
+        server_key.gates_packed([tb.Gate.AND, tb.Gate.OR], [ct, ct], [ct])
+    with pytest.raises(ValueError):
+        server_key.and_packed([ct], [ct, ct])
+
+
+def test_serialization(keys):
+    client_key, server_key = keys
+    ct = client_key.encrypt(True)
+
+    client_key = tb.ClientKey.from_bytes(client_key.to_bytes())
+    server_key = tb.ServerKey.from_bytes(server_key.to_bytes())
+    ct = tb.Ciphertext.from_bytes(ct.to_bytes())
+    parameters = tb.BooleanParameters.from_bytes(tb.DEMO_PARAMETERS.to_bytes())
+
+    assert parameters == tb.DEMO_PARAMETERS
+    assert client_key.decrypt(ct)
+    assert client_key.decrypt(server_key.and_(ct, client_key.encrypt(True)))
+    with pytest.raises(ValueError):
+        tb.Ciphertext.from_bytes(b"\x07")
+
+
+def test_fpga_unavailable(keys, monkeypatch):
+    # without a card, enabling the FPGA fails and the packed gates keep running on the CPU
+    client_key, server_key = keys
+    monkeypatch.delenv("FPGA_IMAGE", raising=False)
+    with pytest.raises(tb.FpgaError):
+        server_key.enable_fpga()
+    assert not server_key.fpga_enabled
+
+    ct = client_key.encrypt(True)
+    server_key.reset_packed_bootstrap_stats()
+    results = server_key.xor_packed([ct], [ct])
+    assert not client_key.decrypt(results[0])
+    assert server_key.packed_bootstrap_stats().fpga_batches == 0
+
+    server_key.disable_fpga()
+    assert not server_key.fpga_enabled
@@ -1,6 +1,8 @@
 extern crate bindgen;
 
+#[cfg(any(feature = "__c_api", not(feature = "__xrt_mock")))]
 use std::env;
+#[cfg(any(feature = "__c_api", not(feature = "__xrt_mock")))]
 use std::path::PathBuf;
 
 #[cfg(feature = "__c_api")]
@@ -78,6 +80,13 @@ fn main() {
     #[cfg(feature = "__c_api")]
     gen_c_api();
 
+    // The fake of XRT replaces both the headers and the library
+    #[cfg(not(feature = "__xrt_mock"))]
+    gen_xrt_bindings();
+}
+
+#[cfg(not(feature = "__xrt_mock"))]
+fn gen_xrt_bindings() {
     // Tell cargo to look for shared libraries in the specified directory
     println!("cargo:rustc-link-search=/opt/xilinx/xrt/lib");
 
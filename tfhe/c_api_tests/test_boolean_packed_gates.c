@@ -0,0 +1,122 @@
+#include "tfhe.h"
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+bool c_gate(uint32_t gate_id, bool left, bool right) {
+  switch (gate_id) {
+  case BooleanGateAnd:
+    return left && right;
+  case BooleanGateOr:
+    return left || right;
+  default:
+    return left != right;
+  }
+}
+
+void test_gates_packed(BooleanClientKey *cks, BooleanServerKey *sks, bool fpga_enabled) {
+  size_t packing = 0;
+  int packing_ok = boolean_fpga_bootstrap_packing(&packing);
+  assert(packing_ok == 0);
+  assert(packing > 0);
+
+  // Two full batches and a partial one
+  size_t packed_len = 2 * packing + 4;
+  uint32_t gate_ids[packed_len];
+  bool lefts[packed_len];
+  bool rights[packed_len];
+  BooleanCiphertext *cts_left[packed_len];
+  BooleanCiphertext *cts_right[packed_len];
+  BooleanCiphertext *cts_result[packed_len];
+
+  for (size_t i = 0; i < packed_len; ++i) {
+    gate_ids[i] = (uint32_t)(i % 3);
+    lefts[i] = (bool)(i % 2);
+    rights[i] = (bool)((i / 2) % 2);
+
+    int encrypt_left_ok = boolean_client_key_encrypt(cks, lefts[i], &cts_left[i]);
+    assert(encrypt_left_ok == 0);
+
+    // Some trivial right operands, bootstrapped with their encrypted left operand
+    if (i % 7 == 0) {
+      int encrypt_right_ok = boolean_trivial_encrypt(rights[i], &cts_right[i]);
+      assert(encrypt_right_ok == 0);
+    } else {
+      int encrypt_right_ok = boolean_client_key_encrypt(cks, rights[i], &cts_right[i]);
+      assert(encrypt_right_ok == 0);
+    }
+  }
+
+  int reset_ok = boolean_server_key_reset_packed_bootstrap_stats(sks);
+  assert(reset_ok == 0);
+
+  int gates_ok = boolean_server_key_gates_packed(
+      sks, gate_ids, (const BooleanCiphertext *const *)cts_left,
+      (const BooleanCiphertext *const *)cts_right, packed_len, cts_result);
+  assert(gates_ok == 0);
+
+  BooleanPackedBootstrapStats stats;
+  int stats_ok = boolean_server_key_packed_bootstrap_stats(sks, &stats);
+  assert(stats_ok == 0);
+  assert(stats.batches == 3);
+  assert(stats.bootstraps == packed_len);
+  if (!fpga_enabled) {
+    assert(stats.fpga_batches == 0);
+  }
+
+  for (size_t i = 0; i < packed_len; ++i) {
+    bool decrypted_result = false;
+    int decrypt_ok = boolean_client_key_decrypt(cks, cts_result[i], &decrypted_result);
+    assert(decrypt_ok == 0);
+
+    assert(decrypted_result == c_gate(gate_ids[i], lefts[i], rights[i]));
+  }
+
+  // An unknown gate fails the whole call, and leaves null results
+  gate_ids[packed_len - 1] = 3;
+  BooleanCiphertext *cts_failed[packed_len];
+  int invalid_gate_ok = boolean_server_key_gates_packed(
+      sks, gate_ids, (const BooleanCiphertext *const *)cts_left,
+      (const BooleanCiphertext *const *)cts_right, packed_len, cts_failed);
+  assert(invalid_gate_ok == 1);
+  for (size_t i = 0; i < packed_len; ++i) {
+    assert(cts_failed[i] == NULL);
+  }
+
+  for (size_t i = 0; i < packed_len; ++i) {
+    boolean_destroy_ciphertext(cts_left[i]);
+    boolean_destroy_ciphertext(cts_right[i]);
+    boolean_destroy_ciphertext(cts_result[i]);
+  }
+}
+
+void test_packed_gates(void) {
+  BooleanClientKey *cks = NULL;
+  BooleanServerKey *sks = NULL;
+
+  int gen_keys_ok = boolean_gen_keys_with_default_parameters(&cks, &sks);
+  assert(gen_keys_ok == 0);
+
+  test_gates_packed(cks, sks, false);
+
+  // Without a device, or with parameters the FPGA image does not implement, enabling the FPGA
+  // fails with an error code and the packed gates keep running on the CPU
+  int enable_fpga_ok = boolean_server_key_enable_fpga(sks);
+  assert(enable_fpga_ok == 0 || enable_fpga_ok >= BooleanFpgaErrorNotSupported);
+
+  test_gates_packed(cks, sks, enable_fpga_ok == 0);
+
+  int disable_fpga_ok = boolean_server_key_disable_fpga(sks);
+  assert(disable_fpga_ok == 0);
+
+  test_gates_packed(cks, sks, false);
+
+  boolean_destroy_client_key(cks);
+  boolean_destroy_server_key(sks);
+}
+
+int main(void) {
+  test_packed_gates();
+  return EXIT_SUCCESS;
+}
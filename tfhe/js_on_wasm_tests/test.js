@@ -85,6 +85,52 @@ test('boolean_deterministic_keygen', (t) => {
     }
 });
 
+test('boolean_packed_gates', (t) => {
+    // a full batch of FPGA_BOOTSTRAP_PACKING gates and a partial one
+    const LENGTH = 25;
+
+    let params = Boolean.get_parameters(BooleanParameterSet.Default);
+    let cks = Boolean.new_client_key(params);
+    let compressed_sks = Boolean.new_compressed_server_key(cks);
+    let serialized_sks = Boolean.serialize_server_key(Boolean.decompress_server_key(compressed_sks));
+    let sks = Boolean.deserialize_server_key(serialized_sks);
+
+    let lefts = [];
+    let rights = [];
+    let cts_left = Boolean.new_ciphertext_array();
+    let cts_right = Boolean.new_ciphertext_array();
+    for (let i = 0; i < LENGTH; i++) {
+        lefts.push(i % 2 == 1);
+        rights.push(Math.floor(i / 2) % 2 == 1);
+        Boolean.ciphertext_array_push(cts_left, Boolean.encrypt(cks, lefts[i]));
+        Boolean.ciphertext_array_push(cts_right, Boolean.encrypt(cks, rights[i]));
+    }
+
+    let gates = [
+        [Boolean.and_packed, (a, b) => a && b],
+        [Boolean.or_packed, (a, b) => a || b],
+        [Boolean.xor_packed, (a, b) => a != b],
+        [Boolean.nand_packed, (a, b) => !(a && b)],
+        [Boolean.nor_packed, (a, b) => !(a || b)],
+        [Boolean.xnor_packed, (a, b) => a == b],
+    ];
+    for (const [packed_gate, expected] of gates) {
+        let results = packed_gate(sks, cts_left, cts_right);
+        assert.deepStrictEqual(Boolean.ciphertext_array_len(results), LENGTH);
+
+        let decrypted = Boolean.ciphertext_array_to_js(results).map((ct) => Boolean.decrypt(cks, ct));
+        assert.deepStrictEqual(decrypted, lefts.map((left, i) => expected(left, rights[i])));
+    }
+
+    let first = Boolean.ciphertext_array_get(cts_left, 0);
+    assert.deepStrictEqual(Boolean.decrypt(cks, first), lefts[0]);
+    assert.throws(() => Boolean.ciphertext_array_get(cts_left, LENGTH));
+
+    let too_short = Boolean.new_ciphertext_array();
+    Boolean.ciphertext_array_push(too_short, first);
+    assert.throws(() => Boolean.and_packed(sks, cts_left, too_short));
+});
+
 
 // Shortint tests
 test('shortint_encrypt_decrypt', (t) => {
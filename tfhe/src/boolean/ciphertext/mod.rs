@@ -2,8 +2,15 @@
 //!
 //! This module implements the ciphertext structure containing an encryption of a Boolean message.
 
+use crate::boolean::engine::bootstrapping::ServerKey;
+use crate::core_crypto::algorithms::*;
+use crate::core_crypto::commons::parameters::{LweSize, PBSOrder};
 use crate::core_crypto::entities::*;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[cfg(test)]
+mod tests;
 
 /// A structure containing a ciphertext, meant to encrypt a Boolean message.
 ///
@@ -27,3 +34,179 @@ impl From<CompressedCiphertext> for Ciphertext {
         Self::Encrypted(value.ciphertext.decompress_into_lwe_ciphertext())
     }
 }
+
+/// A structure containing a list of compressed ciphertexts, meant to encrypt Boolean messages.
+///
+/// Unlike a list of [`CompressedCiphertext`], the whole list is compressed with a single seed, so
+/// that each message only weighs the body of its ciphertext. It has to be decompressed before
+/// evaluating a Boolean circuit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompressedCiphertextList {
+    pub(crate) ciphertext_list: SeededLweCiphertextListOwned<u32>,
+}
+
+impl CompressedCiphertextList {
+    /// Return the number of ciphertexts in the list.
+    pub fn len(&self) -> usize {
+        self.ciphertext_list.lwe_ciphertext_count().0
+    }
+
+    /// Return whether the list holds no ciphertext.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<CompressedCiphertextList> for Vec<Ciphertext> {
+    fn from(value: CompressedCiphertextList) -> Self {
+        // The generator cannot be forked into zero ciphertexts
+        if value.is_empty() {
+            return Vec::new();
+        }
+        let lwe_size = value.ciphertext_list.lwe_size();
+        let ciphertext_modulus = value.ciphertext_list.ciphertext_modulus();
+        value
+            .ciphertext_list
+            .decompress_into_lwe_ciphertext_list()
+            .into_container()
+            .chunks_exact(lwe_size.0)
+            .map(|ct| {
+                Ciphertext::Encrypted(LweCiphertext::from_container(
+                    ct.to_vec(),
+                    ciphertext_modulus,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Error returned when a [`CompactCiphertextList`] cannot be expanded with a server key.
+#[derive(Debug)]
+pub enum CompactListExpandError {
+    /// The list is not encrypted under the large key of the server key.
+    LweSizeMismatch { found: LweSize, expected: LweSize },
+}
+
+impl Display for CompactListExpandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompactListExpandError::LweSizeMismatch { found, expected } => {
+                write!(
+                    f,
+                    "The list is encrypted with {found:?}, not under the large key of the server \
+                    key with {expected:?}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompactListExpandError {}
+
+/// A structure containing a list of ciphertexts encrypted with a
+/// [`CompactPublicKey`](crate::boolean::public_key::CompactPublicKey), meant to encrypt Boolean
+/// messages.
+///
+/// All the ciphertexts of the list share a single mask. The list has to be expanded by the server
+/// before evaluating a Boolean circuit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompactCiphertextList {
+    pub(crate) ct_list: LweCompactCiphertextListOwned<u32>,
+}
+
+impl CompactCiphertextList {
+    /// Return the number of ciphertexts in the list.
+    pub fn len(&self) -> usize {
+        self.ct_list.lwe_ciphertext_count().0
+    }
+
+    /// Return whether the list holds no ciphertext.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Expand the list into ciphertexts the server key can compute on.
+    ///
+    /// The list is encrypted under the large key: the expanded ciphertexts are keyswitched to the
+    /// small key when the server key bootstraps from it. An error is returned when the list is not
+    /// encrypted under the large key of `server_key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() {
+    /// use tfhe::boolean::prelude::*;
+    ///
+    /// // Generate the client key and the server key:
+    /// let (cks, sks) = gen_keys();
+    ///
+    /// let cpks = CompactPublicKey::new(&cks).unwrap();
+    ///
+    /// // Encryption of a list of messages:
+    /// let compact_list = cpks.encrypt_slice(&[true, false]);
+    ///
+    /// // Expansion on the server side:
+    /// let cts = compact_list.expand(&sks).unwrap();
+    /// let ct_res = sks.and(&cts[0], &cts[1]);
+    ///
+    /// // Decryption:
+    /// let dec = cks.decrypt(&ct_res);
+    /// assert_eq!(false, dec);
+    /// # }
+    /// ```
+    pub fn expand(
+        &self,
+        server_key: &ServerKey,
+    ) -> Result<Vec<Ciphertext>, CompactListExpandError> {
+        let large_lwe_size = server_key
+            .bootstrapping_key
+            .output_lwe_dimension()
+            .to_lwe_size();
+        if self.ct_list.lwe_size() != large_lwe_size {
+            return Err(CompactListExpandError::LweSizeMismatch {
+                found: self.ct_list.lwe_size(),
+                expected: large_lwe_size,
+            });
+        }
+        // The generators cannot be forked into zero ciphertexts
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut output_lwe_ciphertext_list = LweCiphertextList::new(
+            0u32,
+            self.ct_list.lwe_size(),
+            self.ct_list.lwe_ciphertext_count(),
+            self.ct_list.ciphertext_modulus(),
+        );
+
+        #[cfg(not(feature = "__wasm_api"))]
+        par_expand_lwe_compact_ciphertext_list(&mut output_lwe_ciphertext_list, &self.ct_list);
+
+        #[cfg(feature = "__wasm_api")]
+        expand_lwe_compact_ciphertext_list(&mut output_lwe_ciphertext_list, &self.ct_list);
+
+        Ok(output_lwe_ciphertext_list
+            .as_ref()
+            .chunks_exact(self.ct_list.lwe_size().0)
+            .map(|lwe_data| {
+                let ct = LweCiphertext::from_container(
+                    lwe_data.to_vec(),
+                    self.ct_list.ciphertext_modulus(),
+                );
+                match server_key.pbs_order {
+                    PBSOrder::KeyswitchBootstrap => Ciphertext::Encrypted(ct),
+                    PBSOrder::BootstrapKeyswitch => {
+                        let mut output = LweCiphertext::new(
+                            0u32,
+                            server_key.key_switching_key.output_lwe_size(),
+                            ct.ciphertext_modulus(),
+                        );
+                        keyswitch_lwe_ciphertext(&server_key.key_switching_key, &ct, &mut output);
+                        Ciphertext::Encrypted(output)
+                    }
+                }
+            })
+            .collect())
+    }
+}
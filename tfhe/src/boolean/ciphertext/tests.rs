@@ -0,0 +1,84 @@
+use super::*;
+use crate::boolean::keycache::KEY_CACHE;
+use crate::boolean::parameters::{
+    BooleanParameters, DEFAULT_PARAMETERS, DEFAULT_PARAMETERS_KS_PBS, TFHE_LIB_PARAMETERS,
+};
+use crate::boolean::random_boolean;
+use crate::boolean::server_key::BinaryBooleanGates;
+
+const NB_MESSAGES: usize = 100;
+
+fn test_compressed_list_encrypt_decrypt(parameters: BooleanParameters) {
+    let keys = KEY_CACHE.get_from_param(parameters);
+    let (cks, sks) = (keys.client_key(), keys.server_key());
+
+    let messages: Vec<bool> = (0..NB_MESSAGES).map(|_| random_boolean()).collect();
+    let compressed = cks.encrypt_compressed_list(&messages);
+    assert_eq!(compressed.len(), NB_MESSAGES);
+
+    // The list goes through serialization, as it would on an upload
+    let serialized = bincode::serialize(&compressed).unwrap();
+    let compressed: CompressedCiphertextList = bincode::deserialize(&serialized).unwrap();
+
+    let cts: Vec<Ciphertext> = compressed.into();
+    assert_eq!(cts.len(), NB_MESSAGES);
+    for (ct, &message) in cts.iter().zip(messages.iter()) {
+        assert_eq!(cks.decrypt(ct), message);
+    }
+
+    // The decompressed ciphertexts can be computed on
+    for (pair, messages) in cts.chunks_exact(2).zip(messages.chunks_exact(2)) {
+        let ct_res = sks.and(&pair[0], &pair[1]);
+        assert_eq!(cks.decrypt(&ct_res), messages[0] && messages[1]);
+    }
+}
+
+#[test]
+fn test_compressed_list_encrypt_decrypt_default_parameters() {
+    test_compressed_list_encrypt_decrypt(DEFAULT_PARAMETERS);
+}
+
+#[test]
+fn test_compressed_list_encrypt_decrypt_default_parameters_ks_pbs() {
+    test_compressed_list_encrypt_decrypt(DEFAULT_PARAMETERS_KS_PBS);
+}
+
+#[test]
+fn test_compressed_list_encrypt_decrypt_tfhe_lib_parameters() {
+    test_compressed_list_encrypt_decrypt(TFHE_LIB_PARAMETERS);
+}
+
+#[test]
+fn test_compressed_list_empty() {
+    let keys = KEY_CACHE.get_from_param(DEFAULT_PARAMETERS);
+    let compressed = keys.client_key().encrypt_compressed_list(&[]);
+    assert!(compressed.is_empty());
+
+    let serialized = bincode::serialize(&compressed).unwrap();
+    let compressed: CompressedCiphertextList = bincode::deserialize(&serialized).unwrap();
+    assert!(compressed.is_empty());
+
+    let cts: Vec<Ciphertext> = compressed.into();
+    assert!(cts.is_empty());
+}
+
+#[test]
+fn test_compressed_list_size() {
+    let keys = KEY_CACHE.get_from_param(DEFAULT_PARAMETERS);
+    let cks = keys.client_key();
+    let messages = [true; NB_MESSAGES];
+
+    let list_size = |len: usize| {
+        bincode::serialized_size(&cks.encrypt_compressed_list(&messages[..len])).unwrap()
+    };
+    let single_size = bincode::serialized_size(&cks.encrypt_compressed(true)).unwrap();
+    let uncompressed_size = bincode::serialized_size(&cks.encrypt(true)).unwrap();
+
+    // One body per message, and a single seed for the whole list
+    assert_eq!(
+        list_size(NB_MESSAGES) - list_size(NB_MESSAGES - 1),
+        std::mem::size_of::<u32>() as u64
+    );
+    assert!(list_size(NB_MESSAGES) * 10 < single_size * NB_MESSAGES as u64);
+    assert!(list_size(NB_MESSAGES) * 100 < uncompressed_size * NB_MESSAGES as u64);
+}
@@ -3,7 +3,7 @@
 //! This module implements the generation of the client' secret keys, together with the
 //! encryption and decryption methods.
 
-use crate::boolean::ciphertext::{Ciphertext, CompressedCiphertext};
+use crate::boolean::ciphertext::{Ciphertext, CompressedCiphertext, CompressedCiphertextList};
 use crate::boolean::engine::{BooleanEngine, WithThreadLocalEngine};
 use crate::boolean::parameters::BooleanParameters;
 use crate::core_crypto::entities::*;
@@ -94,6 +94,37 @@ impl ClientKey {
         BooleanEngine::with_thread_local_mut(|engine| engine.encrypt_compressed(message, self))
     }
 
+    /// Encrypt a list of Boolean messages using the client key returning a compressed list, which
+    /// stores a single seed for all its ciphertexts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() {
+    /// use tfhe::boolean::prelude::*;
+    ///
+    /// // Generate the client key and the server key:
+    /// let (cks, sks) = gen_keys();
+    ///
+    /// // Encryption of a list of messages:
+    /// let messages = [true, false, true];
+    /// let compressed = cks.encrypt_compressed_list(&messages);
+    /// assert_eq!(compressed.len(), 3);
+    ///
+    /// let cts: Vec<Ciphertext> = compressed.into();
+    ///
+    /// // Decryption:
+    /// for (ct, message) in cts.iter().zip(messages) {
+    ///     assert_eq!(cks.decrypt(ct), message);
+    /// }
+    /// # }
+    /// ```
+    pub fn encrypt_compressed_list(&self, messages: &[bool]) -> CompressedCiphertextList {
+        BooleanEngine::with_thread_local_mut(|engine| {
+            engine.encrypt_compressed_list(messages, self)
+        })
+    }
+
     /// Decrypt a ciphertext encrypting a Boolean message using the client key.
     ///
     /// # Example
@@ -7,9 +7,10 @@ use crate::core_crypto::commons::math::random::{ActivatedRandomGenerator, Seeder
 use crate::core_crypto::commons::parameters::{CiphertextModulus, PBSOrder};
 use crate::core_crypto::entities::*;
 use crate::core_crypto::fft_impl::fft64::math::fft::Fft;
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
-use std::os::raw::c_void;
 // use std::time::Instant;
 
 /// Memory used as buffer for the bootstrap
@@ -97,6 +98,10 @@ pub struct ServerKey {
     pub bootstrapping_key: FourierLweBootstrapKeyOwned,
     pub key_switching_key: LweKeyswitchKeyOwned<u32>,
     pub(crate) pbs_order: PBSOrder,
+    /// Fingerprint of `bootstrapping_key`, computed on first use and never serialized
+    #[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+    #[serde(skip)]
+    pub(crate) bootstrapping_key_fingerprint: OnceCell<u64>,
 }
 
 impl ServerKey {
@@ -108,6 +113,17 @@ impl ServerKey {
         std::mem::size_of_val(self.bootstrapping_key.as_view().data())
     }
 
+    /// Fingerprint of the bootstrapping key, identifying it among the keys uploaded to the FPGA.
+    ///
+    /// The fingerprint is computed on the first call and cached with the key, the bootstrapping
+    /// key must not be modified afterwards.
+    #[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+    pub fn bootstrapping_key_fingerprint(&self) -> u64 {
+        *self
+            .bootstrapping_key_fingerprint
+            .get_or_init(|| fpga_key::bootstrapping_key_fingerprint(&self.bootstrapping_key))
+    }
+
     pub fn key_switching_key_size_elements(&self) -> usize {
         self.key_switching_key.as_ref().len()
     }
@@ -134,6 +150,40 @@ pub struct CompressedServerKey {
     pub(crate) pbs_order: PBSOrder,
 }
 
+/// Counters of the packed bootstraps computed by the engine of a thread.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackedBootstrapStats {
+    /// Number of batches, of at most
+    /// [`FPGA_BOOTSTRAP_PACKING`](crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING) bootstraps.
+    pub batches: u64,
+    /// Number of bootstraps computed in these batches.
+    pub bootstraps: u64,
+    /// Number of batches computed on the FPGA.
+    pub fpga_batches: u64,
+}
+
+impl PackedBootstrapStats {
+    /// Mean fill rate of the batches, relative to
+    /// [`FPGA_BOOTSTRAP_PACKING`](crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING).
+    ///
+    /// Returns 0 when no batch was computed.
+    pub fn batch_utilisation(&self) -> f64 {
+        if self.batches == 0 {
+            return 0.;
+        }
+        self.bootstraps as f64
+            / (self.batches * crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING as u64) as f64
+    }
+
+    pub(crate) fn record(&mut self, bootstraps: usize, on_fpga: bool) {
+        self.batches += 1;
+        self.bootstraps += bootstraps as u64;
+        if on_fpga {
+            self.fpga_batches += 1;
+        }
+    }
+}
+
 /// Perform ciphertext bootstraps on the CPU
 pub struct Bootstrapper {
     pub memory: Memory,
@@ -146,11 +196,13 @@ pub struct Bootstrapper {
     pub(crate) encryption_generator: EncryptionRandomGenerator<ActivatedRandomGenerator>,
     pub(crate) computation_buffers: ComputationBuffers,
     pub(crate) seeder: DeterministicSeeder<ActivatedRandomGenerator>,
-    pub fpga_device: *mut c_void,
-    pub fpga_kernel: *mut c_void,
-    pub mem_lwe_in: *mut c_void,
-    pub mem_lwe_out: *mut c_void,    
-    pub is_fpga_enabled: bool,
+    pub(crate) packed_stats: PackedBootstrapStats,
+    /// The opened FPGA, `None` while bootstraps run on the CPU.
+    #[cfg(feature = "fpga")]
+    pub(crate) fpga: Option<fpga::FpgaAccelerator>,
+    /// The connection to the bootstrap server, `None` while bootstraps run locally.
+    #[cfg(feature = "remote-bootstrap")]
+    pub(crate) remote: Option<remote::RemoteBootstrapClient>,
 }
 
 impl Bootstrapper {
@@ -160,12 +212,11 @@ impl Bootstrapper {
             encryption_generator: EncryptionRandomGenerator::<_>::new(seeder.seed(), seeder),
             computation_buffers: Default::default(),
             seeder: DeterministicSeeder::<_>::new(seeder.seed()),
-            // FPGA initialisation stuff
-            fpga_device: std::ptr::null_mut(),
-            fpga_kernel: std::ptr::null_mut(),
-            mem_lwe_in: std::ptr::null_mut(),
-            mem_lwe_out: std::ptr::null_mut(),            
-            is_fpga_enabled: false,
+            packed_stats: Default::default(),
+            #[cfg(feature = "fpga")]
+            fpga: None,
+            #[cfg(feature = "remote-bootstrap")]
+            remote: None,
         }
     }
 
@@ -228,6 +279,8 @@ impl Bootstrapper {
             bootstrapping_key: fourier_bsk,
             key_switching_key: ksk,
             pbs_order: cks.parameters.encryption_key_choice.into(),
+            #[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+            bootstrapping_key_fingerprint: OnceCell::new(),
         })
     }
 
@@ -339,25 +392,38 @@ impl Bootstrapper {
         Ok(output)
     }
 
-    #[cfg(not(feature = "fpga"))]
+    /// Bootstrap a batch of linear combinations, in the PBS order of the server key.
+    ///
+    /// Under [`PBSOrder::KeyswitchBootstrap`] the inputs are keyswitched to the small key before
+    /// the batch is bootstrapped, and the outputs stay under the large key. The bootstraps
+    /// themselves run on the backend selected for this bootstrapper, see
+    /// [`Bootstrapper::bootstrap_packed`].
     pub fn bootstrap_and_keyswitch_packed(
         &mut self,
         ciphertexts: &mut Vec<LweCiphertextOwned<u32>>,
-        _server_key: &ServerKey,
+        server_key: &ServerKey,
     ) -> Vec<Ciphertext> {
-        let bootstrapped = {
-            // let start = Instant::now();
-            let result: Vec<LweCiphertextOwned<u32>> = ciphertexts
-                .iter_mut()
-                .map(|ct| self.bootstrap(ct, _server_key).unwrap())
+        let on_fpga = self.packed_on_fpga();
+        self.packed_stats.record(ciphertexts.len(), on_fpga);
+
+        if server_key.pbs_order == PBSOrder::KeyswitchBootstrap {
+            *ciphertexts = ciphertexts
+                .iter()
+                .map(|ct| self.keyswitch(ct, server_key).unwrap())
                 .collect();
-            // println!(
-            //     "{:?} SW bootstraps: {:?} ",
-            //     ciphertexts.len(),
-            //     start.elapsed()
-            // );
-            result
-        };
+        }
+
+        // Outputs keyswitched right away only need the precision of the keyswitch
+        let keyswitched = server_key.pbs_order == PBSOrder::BootstrapKeyswitch
+            && cfg!(not(feature = "without_keyswitch"));
+        let bootstrapped = self.bootstrap_packed_for(ciphertexts, server_key, keyswitched);
+
+        if server_key.pbs_order == PBSOrder::KeyswitchBootstrap {
+            return bootstrapped
+                .into_iter()
+                .map(Ciphertext::Encrypted)
+                .collect();
+        }
 
         #[cfg(feature = "without_keyswitch")]
         let result = bootstrapped
@@ -370,7 +436,7 @@ impl Bootstrapper {
             // let start = Instant::now();
             let result = bootstrapped
                 .iter()
-                .map(|ct| Ciphertext::Encrypted(self.keyswitch(ct, _server_key).unwrap()))
+                .map(|ct| Ciphertext::Encrypted(self.keyswitch(ct, server_key).unwrap()))
                 .collect();
             // print!(
             //     "{:?} SW keyswitches: {:?}",
@@ -383,6 +449,62 @@ impl Bootstrapper {
         result
     }
 
+    /// Bootstrap a batch of ciphertexts under the small key, without keyswitch.
+    ///
+    /// The batch goes to the bootstrap server when remote bootstrapping is enabled, to the FPGA
+    /// when it is enabled, and is bootstrapped on the CPU otherwise.
+    pub fn bootstrap_packed(
+        &mut self,
+        ciphertexts: &[LweCiphertextOwned<u32>],
+        server_key: &ServerKey,
+    ) -> Vec<LweCiphertextOwned<u32>> {
+        self.bootstrap_packed_for(ciphertexts, server_key, false)
+    }
+
+    /// [`Bootstrapper::bootstrap_packed`], for outputs which are keyswitched before any other use
+    /// when `_keyswitched` is set: their masks are then only exact up to the precision of the
+    /// keyswitch, which spares the bandwidth of a bootstrap server.
+    fn bootstrap_packed_for(
+        &mut self,
+        ciphertexts: &[LweCiphertextOwned<u32>],
+        server_key: &ServerKey,
+        _keyswitched: bool,
+    ) -> Vec<LweCiphertextOwned<u32>> {
+        #[cfg(feature = "remote-bootstrap")]
+        if let Some(remote) = self.remote.as_mut() {
+            return remote
+                .bootstrap(ciphertexts, server_key, _keyswitched)
+                .unwrap_or_else(|err| panic!("The remote bootstrap failed: {err}"));
+        }
+
+        #[cfg(feature = "fpga")]
+        if self.fpga.is_some() {
+            return self.fpga_bootstrap_packed(ciphertexts, server_key);
+        }
+
+        ciphertexts
+            .iter()
+            .map(|ct| self.bootstrap(ct, server_key).unwrap())
+            .collect()
+    }
+
+    /// Whether [`Bootstrapper::bootstrap_packed`] runs on the local FPGA.
+    fn packed_on_fpga(&self) -> bool {
+        #[cfg(feature = "remote-bootstrap")]
+        if self.remote.is_some() {
+            return false;
+        }
+
+        #[cfg(feature = "fpga")]
+        {
+            self.fpga.is_some()
+        }
+        #[cfg(not(feature = "fpga"))]
+        {
+            false
+        }
+    }
+
     pub(crate) fn bootstrap_keyswitch(
         &mut self,
         mut ciphertext: LweCiphertextOwned<u32>,
@@ -516,9 +638,20 @@ impl From<CompressedServerKey> for ServerKey {
             key_switching_key,
             bootstrapping_key,
             pbs_order,
+            #[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+            bootstrapping_key_fingerprint: OnceCell::new(),
         }
     }
 }
 
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+pub mod fpga_key;
+
 #[cfg(feature = "fpga")]
 mod fpga;
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+mod batch_format;
+
+#[cfg(feature = "remote-bootstrap")]
+pub mod remote;
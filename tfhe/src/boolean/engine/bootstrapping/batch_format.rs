@@ -0,0 +1,296 @@
+//! Bit-packed formats of the batches of ciphertexts exchanged with a bootstrap server.
+//!
+//! A packed bootstrap needs much less from its ciphertexts than the `u32` words they are stored in:
+//!
+//! * The inputs are modulus-switched to the `2N` of the blind rotation before they are sent, so
+//!   that each coefficient only has `log2(2N)` significant bits. [`ModulusSwitchedBatch`] keeps
+//!   exactly these bits, without any loss.
+//! * The outputs are keyswitched right away under [`PBSOrder::BootstrapKeyswitch`], and the
+//!   keyswitch only reads the mask coefficients through their rounding to the `base_log * level`
+//!   bits of its decomposition. [`BootstrappedBatch`] keeps these bits of the masks and the whole
+//!   bodies, so that the keyswitched outputs are bit for bit the ones of the full outputs. The
+//!   outputs travel sample-extracted: the `kN + 1` coefficients of an LWE ciphertext are fewer
+//!   than the `(k + 1)N` of the GLWE ciphertext the FPGA writes back.
+//!
+//! For full batches of [`FPGA_BOOTSTRAP_PACKING`] ciphertexts, the bincode serializations of the
+//! ciphertexts and of the packed batches, and for comparison the unpacked GLWE ciphertexts the
+//! FPGA writes back, weigh:
+//!
+//! | Parameters           | Inputs   | Packed inputs | Outputs  | Packed outputs | GLWE outputs |
+//! |----------------------|---------:|--------------:|---------:|---------------:|-------------:|
+//! | `DEMO_PARAMETERS`    |  2.3 KiB |       0.4 KiB | 12.3 KiB |        3.7 KiB |     23.0 KiB |
+//! | `DEFAULT_PARAMETERS` | 65.7 KiB |      20.3 KiB | 92.8 KiB |       34.6 KiB |    138.0 KiB |
+//!
+//! that is 5.3 times fewer input bytes and 3.3 times fewer output bytes at `DEMO_PARAMETERS`,
+//! where the ciphertext modulus each ciphertext carries weighs as much as its coefficients, and
+//! 3.2 and 2.7 times fewer at `DEFAULT_PARAMETERS`. Outputs under
+//! [`PBSOrder::KeyswitchBootstrap`] are not keyswitched before their next use, and keep their
+//! whole masks. With their headers, the `Bootstrap` request and response frames of the remote
+//! protocol for a full batch weigh 5.1 and 3.3 times less than with the whole ciphertexts at
+//! `DEMO_PARAMETERS`, 3.2 and 2.7 times less at `DEFAULT_PARAMETERS`.
+//!
+//! Only the remote bootstrap protocol sends these formats. The FPGA image reads one `u32` word per
+//! mask coefficient and writes whole GLWE ciphertexts, so the device buffers are not packed: the
+//! inputs are written as switched by [`modulus_switch`], and the GLWE outputs are read whole and
+//! sample-extracted on the host. Only the resulting LWE ciphertexts are packed, when a bootstrap
+//! server sends them back.
+//!
+//! [`FPGA_BOOTSTRAP_PACKING`]: crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING
+//! [`PBSOrder::BootstrapKeyswitch`]: crate::core_crypto::commons::parameters::PBSOrder::BootstrapKeyswitch
+//! [`PBSOrder::KeyswitchBootstrap`]: crate::core_crypto::commons::parameters::PBSOrder::KeyswitchBootstrap
+
+// Only the remote bootstrap exchanges batches, the FPGA only switches coefficients
+#![cfg_attr(not(feature = "remote-bootstrap"), allow(dead_code))]
+
+use crate::core_crypto::commons::math::decomposition::SignedDecomposer;
+use crate::core_crypto::fft_impl::common::pbs_modulus_switch;
+use crate::core_crypto::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// Values of `bits` bits each, packed from the least significant bit of 64-bit words.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BitPacked {
+  bits: usize,
+  len: usize,
+  words: Vec<u64>,
+}
+
+impl BitPacked {
+  /// Pack `values`, which must all fit in `bits` bits.
+  pub(crate) fn pack(values: impl IntoIterator<Item = u32>, bits: usize) -> Self {
+    assert!((1..=32).contains(&bits));
+    let mut words = Vec::new();
+    let mut len = 0;
+    for value in values {
+      debug_assert!(bits == 32 || value >> bits == 0);
+      let position = len * bits;
+      let (word, offset) = (position / 64, position % 64);
+      if word == words.len() {
+        words.push(0);
+      }
+      words[word] |= (value as u64) << offset;
+      // The value straddles two words
+      if offset + bits > 64 {
+        words.push((value as u64) >> (64 - offset));
+      }
+      len += 1;
+    }
+    Self { bits, len, words }
+  }
+
+  pub(crate) fn bits(&self) -> usize {
+    self.bits
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Whether the words hold exactly `len` values of `bits` bits, as packed by
+  /// [`BitPacked::pack`]: packed values received from a peer must be checked before they are
+  /// unpacked.
+  pub(crate) fn is_valid(&self) -> bool {
+    (1..=32).contains(&self.bits)
+      && self
+        .len
+        .checked_mul(self.bits)
+        .map(|bit_count| (bit_count + 63) / 64)
+        == Some(self.words.len())
+  }
+
+  pub(crate) fn unpack(&self) -> impl Iterator<Item = u32> + '_ {
+    let mask = u64::MAX >> (64 - self.bits);
+    (0..self.len).map(move |index| {
+      let position = index * self.bits;
+      let (word, offset) = (position / 64, position % 64);
+      let mut value = self.words[word] >> offset;
+      if offset + self.bits > 64 {
+        value |= self.words[word + 1] << (64 - offset);
+      }
+      (value & mask) as u32
+    })
+  }
+}
+
+/// Switch a coefficient to the modulus `2N` of the blind rotation.
+pub(crate) fn modulus_switch(coefficient: u32, polynomial_size: PolynomialSize) -> u32 {
+  // The rounding can reach 2N, which the blind rotation reads as 0
+  (pbs_modulus_switch(
+    coefficient,
+    polynomial_size,
+    ModulusSwitchOffset(0),
+    LutCountLog(0),
+  ) % (2 * polynomial_size.0)) as u32
+}
+
+/// A batch of LWE ciphertexts whose coefficients are switched to the modulus `2N` of the blind
+/// rotation, the only precision the bootstrap needs from them, packed on `log2(2N)` bits.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ModulusSwitchedBatch {
+  polynomial_size: PolynomialSize,
+  lwe_size: LweSize,
+  /// The masks and body of each ciphertext, one ciphertext after the other.
+  coefficients: BitPacked,
+}
+
+impl ModulusSwitchedBatch {
+  pub(crate) fn new(
+    ciphertexts: &[LweCiphertextOwned<u32>],
+    polynomial_size: PolynomialSize,
+  ) -> Self {
+    let lwe_size = ciphertexts.first().map_or(LweSize(1), |ct| ct.lwe_size());
+    let coefficients = ciphertexts
+      .iter()
+      .flat_map(|ct| ct.as_ref().iter())
+      .map(|&coefficient| modulus_switch(coefficient, polynomial_size));
+    Self {
+      polynomial_size,
+      lwe_size,
+      coefficients: BitPacked::pack(coefficients, Self::bits(polynomial_size)),
+    }
+  }
+
+  /// Number of bits of a coefficient switched to the modulus `2N`.
+  fn bits(polynomial_size: PolynomialSize) -> usize {
+    polynomial_size.log2().0 + 1
+  }
+
+  /// The switched coefficients, masks then body of each ciphertext.
+  pub(crate) fn coefficients(&self) -> impl Iterator<Item = u32> + '_ {
+    self.coefficients.unpack()
+  }
+
+  /// Check that the batch can be bootstrapped with a key of the given geometry.
+  pub(crate) fn check_geometry(
+    &self,
+    polynomial_size: PolynomialSize,
+    lwe_size: LweSize,
+  ) -> Result<(), String> {
+    if self.polynomial_size != polynomial_size {
+      Err(format!(
+        "the batch is switched for {:?}, the key has {polynomial_size:?}",
+        self.polynomial_size
+      ))
+    } else if self.lwe_size != lwe_size {
+      Err(format!(
+        "the ciphertexts have {:?}, the key bootstraps {lwe_size:?}",
+        self.lwe_size
+      ))
+    } else if !self.coefficients.is_valid()
+      || self.coefficients.bits() != Self::bits(polynomial_size)
+    {
+      Err("the coefficients are not packed on log2(2N) bits".to_string())
+    } else if self.coefficients.len() % lwe_size.0 != 0 {
+      Err(format!(
+        "{} coefficients do not make whole ciphertexts",
+        self.coefficients.len()
+      ))
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Lift the batch back to ciphertexts modulo `2^32`.
+  ///
+  /// Each coefficient `c` becomes `c * 2^32 / 2N`, which the modulus switch of the bootstrap maps
+  /// back to exactly `c`.
+  pub(crate) fn to_ciphertexts(&self) -> Vec<LweCiphertextOwned<u32>> {
+    let shift = u32::BITS as usize - Self::bits(self.polynomial_size);
+    let coefficients: Vec<u32> = self.coefficients().collect();
+    coefficients
+      .chunks_exact(self.lwe_size.0)
+      .map(|coefficients| {
+        LweCiphertext::from_container(
+          coefficients
+            .iter()
+            .map(|&coefficient| coefficient << shift)
+            .collect(),
+          CiphertextModulus::new_native(),
+        )
+      })
+      .collect()
+  }
+}
+
+/// A batch of bootstrapped LWE ciphertexts, whose mask coefficients are rounded to their
+/// `mask_bits` most significant bits.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BootstrappedBatch {
+  lwe_size: LweSize,
+  /// The rounded mask coefficients, one ciphertext after the other.
+  masks: BitPacked,
+  bodies: Vec<u32>,
+}
+
+impl BootstrappedBatch {
+  /// Pack the ciphertexts, keeping the `mask_bits` bits a keyswitch with the given decomposition
+  /// reads from each mask coefficient, or the whole masks without decomposition.
+  pub(crate) fn new(
+    ciphertexts: &[LweCiphertextOwned<u32>],
+    keyswitch_decomposition: Option<(DecompositionBaseLog, DecompositionLevelCount)>,
+  ) -> Self {
+    let lwe_size = ciphertexts.first().map_or(LweSize(1), |ct| ct.lwe_size());
+    let mask_bits =
+      keyswitch_decomposition.map_or(u32::BITS as usize, |(base_log, level)| base_log.0 * level.0);
+    let round = |coefficient: u32| match keyswitch_decomposition {
+      Some((base_log, level)) if mask_bits < u32::BITS as usize => {
+        SignedDecomposer::new(base_log, level).closest_representable(coefficient)
+          >> (u32::BITS as usize - mask_bits)
+      }
+      _ => coefficient,
+    };
+    let masks = ciphertexts
+      .iter()
+      .flat_map(|ct| {
+        // All the coefficients but the last one, the body
+        let coefficients = ct.as_ref();
+        coefficients[..coefficients.len() - 1]
+          .iter()
+          .map(|&coefficient| round(coefficient))
+      })
+      .collect::<Vec<u32>>();
+    Self {
+      lwe_size,
+      masks: BitPacked::pack(masks, mask_bits),
+      bodies: ciphertexts.iter().map(|ct| *ct.get_body().data).collect(),
+    }
+  }
+
+  /// Check that the batch holds `len` ciphertexts of `lwe_size` coefficients.
+  pub(crate) fn check_geometry(&self, len: usize, lwe_size: LweSize) -> Result<(), String> {
+    if !self.masks.is_valid()
+      || self.bodies.len() != len
+      || (len > 0 && self.lwe_size != lwe_size)
+      || Some(self.masks.len()) != len.checked_mul(lwe_size.0 - 1)
+    {
+      return Err(format!(
+        "expected {len} ciphertexts of {lwe_size:?}, got {} of {:?}",
+        self.bodies.len(),
+        self.lwe_size
+      ));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn to_ciphertexts(&self) -> Vec<LweCiphertextOwned<u32>> {
+    let shift = u32::BITS as usize - self.masks.bits();
+    let masks: Vec<u32> = self.masks.unpack().collect();
+    let mask_len = self.lwe_size.0 - 1;
+    self
+      .bodies
+      .iter()
+      .enumerate()
+      .map(|(index, &body)| {
+        let mut coefficients: Vec<u32> = masks[index * mask_len..(index + 1) * mask_len]
+          .iter()
+          .map(|&coefficient| coefficient << shift)
+          .collect();
+        coefficients.push(body);
+        LweCiphertext::from_container(coefficients, CiphertextModulus::new_native())
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,229 @@
+use super::*;
+use crate::boolean::ciphertext::Ciphertext;
+use crate::boolean::engine::bootstrapping::Bootstrapper;
+use crate::boolean::keycache::KEY_CACHE;
+use crate::boolean::parameters::{
+  BooleanParameters, DEFAULT_PARAMETERS, DEMO_PARAMETERS, FPGA_BOOTSTRAP_PACKING,
+};
+use crate::core_crypto::seeders::new_seeder;
+
+/// Deterministic pseudo-random coefficients, with both extremes first.
+fn coefficients(len: usize, seed: u32) -> Vec<u32> {
+  let mut state = seed;
+  [0, u32::MAX]
+    .into_iter()
+    .chain(std::iter::repeat_with(move || {
+      state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+      state
+    }))
+    .take(len)
+    .collect()
+}
+
+fn ciphertexts(count: usize, lwe_size: LweSize, seed: u32) -> Vec<LweCiphertextOwned<u32>> {
+  coefficients(count * lwe_size.0, seed)
+    .chunks_exact(lwe_size.0)
+    .map(|coefficients| {
+      LweCiphertext::from_container(coefficients.to_vec(), CiphertextModulus::new_native())
+    })
+    .collect()
+}
+
+#[test]
+fn test_bit_packed_round_trip() {
+  for bits in 1..=32 {
+    for len in [0, 1, 63, 64, 65, 1000] {
+      let values: Vec<u32> = coefficients(len, bits as u32)
+        .into_iter()
+        .map(|value| (value as u64 & (u64::MAX >> (64 - bits))) as u32)
+        .collect();
+      let packed = BitPacked::pack(values.iter().copied(), bits);
+      assert!(packed.is_valid());
+      assert_eq!(packed.len(), len);
+      assert_eq!(packed.words.len(), (len * bits + 63) / 64);
+      assert_eq!(packed.unpack().collect::<Vec<u32>>(), values, "{bits} bits");
+
+      let mut truncated = packed.clone();
+      truncated.len += 64;
+      assert!(!truncated.is_valid());
+    }
+  }
+
+  for bits in [0, 33] {
+    let packed = BitPacked {
+      bits,
+      len: 0,
+      words: Vec::new(),
+    };
+    assert!(!packed.is_valid());
+  }
+}
+
+#[test]
+fn test_modulus_switched_batch_round_trip() {
+  let polynomial_size = DEMO_PARAMETERS.polynomial_size;
+  let lwe_size = DEMO_PARAMETERS.lwe_dimension.to_lwe_size();
+
+  // The extremes come first, u32::MAX rounding up to 2N
+  let ciphertexts = ciphertexts(5, lwe_size, 0x2545_f491);
+  let batch = ModulusSwitchedBatch::new(&ciphertexts, polynomial_size);
+  assert_eq!(batch.coefficients().take(2).collect::<Vec<u32>>(), [0, 0]);
+  assert!(batch
+    .coefficients()
+    .all(|coefficient| (coefficient as usize) < 2 * polynomial_size.0));
+  assert!(batch.check_geometry(polynomial_size, lwe_size).is_ok());
+
+  // The lifted ciphertexts switch back to exactly the same batch
+  let lifted = batch.to_ciphertexts();
+  assert_eq!(lifted.len(), ciphertexts.len());
+  assert_eq!(ModulusSwitchedBatch::new(&lifted, polynomial_size), batch);
+
+  let serialized = bincode::serialize(&batch).unwrap();
+  let deserialized: ModulusSwitchedBatch = bincode::deserialize(&serialized).unwrap();
+  assert_eq!(deserialized, batch);
+
+  assert!(batch
+    .check_geometry(polynomial_size, LweSize(lwe_size.0 + 1))
+    .is_err());
+  assert!(batch
+    .check_geometry(PolynomialSize(2 * polynomial_size.0), lwe_size)
+    .is_err());
+  let mut corrupted = batch.clone();
+  corrupted.coefficients.words.pop();
+  assert!(corrupted.check_geometry(polynomial_size, lwe_size).is_err());
+}
+
+#[test]
+fn test_bootstrapped_batch_keyswitch() {
+  let parameter_sets: [BooleanParameters; 2] = [DEMO_PARAMETERS, DEFAULT_PARAMETERS];
+  for parameters in parameter_sets {
+    let keys = KEY_CACHE.get_from_param(parameters);
+    let (client_key, server_key) = (keys.client_key(), keys.server_key());
+    let mut bootstrapper = Bootstrapper::new(new_seeder().as_mut());
+
+    let inputs: Vec<LweCiphertextOwned<u32>> = (0..FPGA_BOOTSTRAP_PACKING)
+      .map(|i| match client_key.encrypt(i % 3 == 0) {
+        Ciphertext::Encrypted(ct) => ct,
+        Ciphertext::Trivial(_) => unreachable!(),
+      })
+      .collect();
+    let outputs = bootstrapper.bootstrap_packed(&inputs, server_key);
+    let output_size = server_key
+      .bootstrapping_key
+      .output_lwe_dimension()
+      .to_lwe_size();
+
+    // Without decomposition, the outputs are sent whole
+    let exact = BootstrappedBatch::new(&outputs, None);
+    assert!(exact.check_geometry(outputs.len(), output_size).is_ok());
+    assert_eq!(exact.to_ciphertexts(), outputs);
+
+    // With the decomposition of the keyswitch, the keyswitched outputs do not change
+    let ksk = &server_key.key_switching_key;
+    let rounded = BootstrappedBatch::new(
+      &outputs,
+      Some((
+        ksk.decomposition_base_log(),
+        ksk.decomposition_level_count(),
+      )),
+    );
+    assert!(rounded.check_geometry(outputs.len(), output_size).is_ok());
+    let serialized = bincode::serialize(&rounded).unwrap();
+    let deserialized: BootstrappedBatch = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(deserialized, rounded);
+
+    for (output, rounded) in outputs.iter().zip(deserialized.to_ciphertexts()) {
+      assert_eq!(output.get_body().data, rounded.get_body().data);
+      let keyswitch = |ct: &LweCiphertextOwned<u32>| {
+        let mut keyswitched =
+          LweCiphertext::new(0u32, ksk.output_lwe_size(), CiphertextModulus::new_native());
+        keyswitch_lwe_ciphertext(ksk, ct, &mut keyswitched);
+        keyswitched
+      };
+      let (expected, keyswitched) = (keyswitch(output), keyswitch(&rounded));
+      assert_eq!(keyswitched, expected);
+      assert_eq!(
+        client_key.decrypt(&Ciphertext::Encrypted(keyswitched)),
+        client_key.decrypt(&Ciphertext::Encrypted(expected))
+      );
+    }
+
+    assert!(rounded
+      .check_geometry(outputs.len() + 1, output_size)
+      .is_err());
+    assert!(rounded
+      .check_geometry(outputs.len(), LweSize(output_size.0 + 1))
+      .is_err());
+  }
+}
+
+fn size<T: Serialize>(value: &T) -> usize {
+  bincode::serialized_size(value).unwrap() as usize
+}
+
+/// Check the serialized sizes of full batches and the savings given in the documentation of the
+/// module.
+#[test]
+fn test_batch_bandwidth() {
+  // Inputs, packed inputs, outputs, packed outputs and GLWE outputs, in bytes, then how many times
+  // fewer bytes the packed inputs and outputs weigh
+  let parameter_sets: [(BooleanParameters, [usize; 5], [&str; 2]); 2] = [
+    (
+      DEMO_PARAMETERS,
+      [2_308, 432, 12_612, 3_812, 23_552],
+      ["5.3", "3.3"],
+    ),
+    (
+      DEFAULT_PARAMETERS,
+      [67_260, 20_832, 95_044, 35_460, 141_312],
+      ["3.2", "2.7"],
+    ),
+  ];
+  for (parameters, expected, expected_savings) in parameter_sets {
+    let polynomial_size = parameters.polynomial_size;
+    let output_dimension = parameters.glwe_dimension.0 * polynomial_size.0;
+    let decomposition = (parameters.ks_base_log, parameters.ks_level);
+
+    let inputs = ciphertexts(
+      FPGA_BOOTSTRAP_PACKING,
+      parameters.lwe_dimension.to_lwe_size(),
+      1,
+    );
+    let outputs = ciphertexts(
+      FPGA_BOOTSTRAP_PACKING,
+      LweDimension(output_dimension).to_lwe_size(),
+      2,
+    );
+
+    let inputs_size = size(&inputs);
+    let packed_inputs_size = size(&ModulusSwitchedBatch::new(&inputs, polynomial_size));
+    let outputs_size = size(&outputs);
+    let packed_outputs_size = size(&BootstrappedBatch::new(&outputs, Some(decomposition)));
+    // What the FPGA writes back for a batch
+    let glwe_outputs_size =
+      FPGA_BOOTSTRAP_PACKING * (parameters.glwe_dimension.0 + 1) * polynomial_size.0 * 4;
+    assert_eq!(
+      [
+        inputs_size,
+        packed_inputs_size,
+        outputs_size,
+        packed_outputs_size,
+        glwe_outputs_size,
+      ],
+      expected
+    );
+
+    // Inputs keep log2(2N) bits out of 32, output masks the bits of the keyswitch decomposition
+    let input_ratio = inputs_size as f64 / packed_inputs_size as f64;
+    let output_ratio = outputs_size as f64 / packed_outputs_size as f64;
+    let switched_bits = polynomial_size.log2().0 + 1;
+    let mask_bits = parameters.ks_base_log.0 * parameters.ks_level.0;
+    assert_eq!(
+      [format!("{input_ratio:.1}"), format!("{output_ratio:.1}")],
+      expected_savings
+    );
+    assert!(input_ratio > 0.9 * 32. / switched_bits as f64);
+    assert!(output_ratio > 0.9 * 32. / mask_bits as f64);
+    assert!(packed_outputs_size * 3 < glwe_outputs_size);
+  }
+}
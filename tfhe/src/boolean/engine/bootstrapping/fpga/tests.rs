@@ -0,0 +1,279 @@
+use super::*;
+use crate::boolean::keycache::KEY_CACHE_FPGA;
+use crate::core_crypto::seeders::new_seeder;
+
+// The tests share the fake card and the environment of the process: each of them holds the card
+// for its whole duration.
+
+fn new_bootstrapper() -> Bootstrapper {
+  env::set_var("FPGA_IMAGE", "accel.xclbin");
+  env::set_var("FPGA_INDEX", "0");
+  Bootstrapper::new(new_seeder().as_mut())
+}
+
+fn open_buffers() -> usize {
+  xrt_mock::open_handles()
+    .iter()
+    .filter(|&&kind| kind == "buffer")
+    .count()
+}
+
+#[test]
+fn test_enable_disable_cycles_release_handles() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let fingerprint = keys.server_key().bootstrapping_key_fingerprint();
+
+  let mut bootstrapper = new_bootstrapper();
+  for _ in 0..3 {
+    bootstrapper
+      .enable_fpga(fingerprint, keys.fpga_key())
+      .unwrap();
+    assert!(!xrt_mock::open_handles().is_empty());
+
+    bootstrapper.disable_fpga();
+    assert_eq!(xrt_mock::open_handles(), Vec::<&str>::new());
+  }
+}
+
+#[test]
+fn test_dropping_bootstrapper_releases_handles() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let fingerprint = keys.server_key().bootstrapping_key_fingerprint();
+
+  {
+    let mut bootstrapper = new_bootstrapper();
+    bootstrapper
+      .enable_fpga(fingerprint, keys.fpga_key())
+      .unwrap();
+
+    let mut open = xrt_mock::open_handles();
+    open.sort_unstable();
+    // The image is released as soon as it is loaded
+    assert_eq!(
+      open,
+      ["buffer", "buffer", "buffer", "device", "kernel", "run"]
+    );
+
+    bootstrapper.evict_fpga_key(fingerprint).unwrap();
+    assert_eq!(open_buffers(), 2);
+  }
+
+  assert_eq!(xrt_mock::open_handles(), Vec::<&str>::new());
+}
+
+#[test]
+fn test_key_slots() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let mut bootstrapper = new_bootstrapper();
+
+  // The same image stands for several tenants, told apart by their fingerprints
+  for fingerprint in 0..FPGA_KEY_SLOTS as u64 {
+    bootstrapper
+      .enable_fpga(fingerprint, keys.fpga_key())
+      .unwrap();
+  }
+  assert_eq!(open_buffers(), 2 + FPGA_KEY_SLOTS);
+
+  // Resident keys are selected again without being uploaded
+  let uploads = xrt_mock::call_names()
+    .iter()
+    .filter(|&&call| call == "xrtBOWrite")
+    .count();
+  bootstrapper.enable_fpga(0, keys.fpga_key()).unwrap();
+  assert_eq!(
+    xrt_mock::call_names()
+      .iter()
+      .filter(|&&call| call == "xrtBOWrite")
+      .count(),
+    uploads
+  );
+
+  let full = FPGA_KEY_SLOTS as u64;
+  assert_eq!(
+    bootstrapper.enable_fpga(full, keys.fpga_key()),
+    Err(FpgaKeyError::KeySlotsFull {
+      capacity: FPGA_KEY_SLOTS
+    })
+  );
+
+  bootstrapper.evict_fpga_key(3).unwrap();
+  assert_eq!(
+    bootstrapper.evict_fpga_key(3),
+    Err(FpgaKeyError::KeyNotResident { fingerprint: 3 })
+  );
+  assert_eq!(
+    bootstrapper.select_fpga_key(3),
+    Err(FpgaKeyError::KeyNotResident { fingerprint: 3 })
+  );
+  bootstrapper.enable_fpga(full, keys.fpga_key()).unwrap();
+  assert_eq!(open_buffers(), 2 + FPGA_KEY_SLOTS);
+}
+
+#[test]
+fn test_evicting_active_key_unbinds_it() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let fingerprint = server_key.bootstrapping_key_fingerprint();
+  let mut bootstrapper = new_bootstrapper();
+  bootstrapper
+    .enable_fpga(fingerprint, keys.fpga_key())
+    .unwrap();
+
+  // The run bound to the key is replaced before the key buffer is freed
+  let first_call = xrt_mock::calls().len();
+  bootstrapper.evict_fpga_key(fingerprint).unwrap();
+  assert_eq!(
+    xrt_mock::call_names()[first_call..],
+    [
+      "xrtRunOpen",
+      "xrtRunSetArg",
+      "xrtRunSetArg",
+      "xrtRunClose",
+      "xrtBOFree",
+    ]
+  );
+
+  // The next batch uploads the key again and binds it to the new run
+  let mut inputs = encrypted_inputs(1);
+  bootstrapper.bootstrap_and_keyswitch_packed(&mut inputs, server_key);
+  assert_eq!(open_buffers(), 3);
+}
+
+#[test]
+fn test_failed_open_releases_handles() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let mut bootstrapper = new_bootstrapper();
+
+  // Fail the allocation of the output buffer, halfway through opening the accelerator
+  xrt_mock::fail_call("xrtBOAlloc", 1);
+  let err = bootstrapper.enable_fpga(0, keys.fpga_key()).unwrap_err();
+  assert!(matches!(
+    err,
+    FpgaKeyError::DeviceFailure {
+      call: "xrtBOAlloc",
+      ..
+    }
+  ));
+  assert_eq!(xrt_mock::open_handles(), Vec::<&str>::new());
+
+  // The next attempt starts from scratch
+  bootstrapper.enable_fpga(0, keys.fpga_key()).unwrap();
+}
+
+#[test]
+fn test_failed_key_upload_keeps_device_open() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let mut bootstrapper = new_bootstrapper();
+
+  xrt_mock::fail_call("xrtBOSync", 0);
+  let err = bootstrapper.enable_fpga(0, keys.fpga_key()).unwrap_err();
+  assert!(matches!(
+    err,
+    FpgaKeyError::DeviceFailure {
+      call: "xrtBOSync",
+      ..
+    }
+  ));
+  // The key buffer is released, the accelerator stays open
+  assert_eq!(open_buffers(), 2);
+  assert_eq!(
+    bootstrapper.select_fpga_key(0),
+    Err(FpgaKeyError::KeyNotResident { fingerprint: 0 })
+  );
+
+  bootstrapper.disable_fpga();
+  assert_eq!(xrt_mock::open_handles(), Vec::<&str>::new());
+}
+
+fn encrypted_inputs(count: usize) -> Vec<LweCiphertextOwned<u32>> {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  (0..count)
+    .map(|i| match keys.client_key().encrypt(i % 2 == 0) {
+      Ciphertext::Encrypted(ct) => ct,
+      Ciphertext::Trivial(_) => unreachable!(),
+    })
+    .collect()
+}
+
+#[test]
+fn test_packed_bootstrap_driver() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let mut bootstrapper = new_bootstrapper();
+  bootstrapper
+    .enable_fpga(server_key.bootstrapping_key_fingerprint(), keys.fpga_key())
+    .unwrap();
+
+  // A partial batch only transfers its own ciphertexts
+  let count = FPGA_BOOTSTRAP_PACKING / 2;
+  let mut inputs = encrypted_inputs(count);
+  let first_call = xrt_mock::calls().len();
+  let outputs = bootstrapper.bootstrap_and_keyswitch_packed(&mut inputs, server_key);
+  assert_eq!(outputs.len(), count);
+
+  let calls = &xrt_mock::calls()[first_call..];
+  let names: Vec<&str> = calls.iter().map(|call| call.function).collect();
+  assert_eq!(
+    names,
+    [
+      "xrtBOWrite",
+      "xrtBOSync",
+      "xrtRunStart",
+      "xrtRunWait",
+      "xrtBOSync",
+      "xrtBORead",
+      "xrtRunSetArg",
+    ]
+  );
+  assert_eq!(
+    calls[0].size,
+    count * DEMO_PARAMETERS.lwe_dimension.0 * std::mem::size_of::<u32>()
+  );
+  assert_eq!(calls[5].size, MEM_LWE_OUT_SIZE);
+}
+
+#[test]
+fn test_packed_bootstrap_switches_keys() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let mut bootstrapper = new_bootstrapper();
+  bootstrapper
+    .enable_fpga(
+      keys.server_key().bootstrapping_key_fingerprint(),
+      keys.fpga_key(),
+    )
+    .unwrap();
+  assert_eq!(open_buffers(), 3);
+
+  // Another tenant's key is uploaded on first use, then selected back and forth
+  let other_server_key = ServerKey::new(keys.client_key());
+  let mut inputs = encrypted_inputs(2);
+  bootstrapper.bootstrap_and_keyswitch_packed(&mut inputs, &other_server_key);
+  assert_eq!(open_buffers(), 4);
+
+  bootstrapper.bootstrap_and_keyswitch_packed(&mut inputs, keys.server_key());
+  assert_eq!(open_buffers(), 4);
+}
+
+#[test]
+#[should_panic(expected = "The FPGA bootstrap failed")]
+fn test_packed_bootstrap_run_failure() {
+  let _card = xrt_mock::lock();
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let mut bootstrapper = new_bootstrapper();
+  bootstrapper
+    .enable_fpga(server_key.bootstrapping_key_fingerprint(), keys.fpga_key())
+    .unwrap();
+
+  xrt_mock::fail_call("xrtRunWait", 0);
+  let mut inputs = encrypted_inputs(1);
+  bootstrapper.bootstrap_and_keyswitch_packed(&mut inputs, server_key);
+}
@@ -0,0 +1,303 @@
+//! Owned wrappers around the XRT handles driving the FPT accelerator.
+//!
+//! Each wrapper owns exactly one XRT handle and releases it when dropped. Objects created from a
+//! device or a kernel keep it alive through an [`Arc`], so a buffer or a run can never outlive
+//! the device it was allocated on, whatever order the owner drops them in.
+//!
+//! The XRT handles are not bound to the thread that opened them, so every wrapper is [`Send`].
+//! Devices and kernels are only used to create other objects or to query them, which XRT
+//! synchronizes internally, so they are also [`Sync`]. Runs and buffers are not: setting kernel
+//! arguments, starting a run and writing or syncing a buffer all mutate the underlying object
+//! without locking, which is why their methods take `&mut self`.
+
+use crate::boolean::engine::bootstrapping::fpga_key::FpgaKeyError;
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::sync::Arc;
+
+#[cfg(not(feature = "__xrt_mock"))]
+mod ffi {
+  #![allow(non_upper_case_globals)]
+  #![allow(non_camel_case_types)]
+  #![allow(non_snake_case)]
+  // Supress not FFI-safe warning
+  #![allow(improper_ctypes)]
+  #![allow(dead_code)]
+
+  include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+// Tests without a card link the fake of XRT instead, through the same C API
+#[cfg(feature = "__xrt_mock")]
+use xrt_mock::bindings as ffi;
+
+use ffi::*;
+
+/// Turn the status code of an XRT call into an error.
+fn check(call: &'static str, code: c_int) -> Result<(), FpgaKeyError> {
+  if code == 0 {
+    Ok(())
+  } else {
+    Err(FpgaKeyError::DeviceFailure { call, code })
+  }
+}
+
+/// Turn the handle returned by an XRT constructor into an error if it is null.
+fn check_handle(call: &'static str, handle: *mut c_void) -> Result<*mut c_void, FpgaKeyError> {
+  if handle.is_null() {
+    // XRT reports the cause of a failed open through errno
+    let code = std::io::Error::last_os_error().raw_os_error().unwrap_or(-1);
+    Err(FpgaKeyError::DeviceFailure { call, code })
+  } else {
+    Ok(handle)
+  }
+}
+
+pub(crate) struct XrtDevice {
+  handle: xrtDeviceHandle,
+}
+
+// SAFETY: the device handle is owned and XRT device calls are internally synchronized.
+unsafe impl Send for XrtDevice {}
+unsafe impl Sync for XrtDevice {}
+
+impl XrtDevice {
+  pub(crate) fn open(index: u32) -> Result<Arc<Self>, FpgaKeyError> {
+    let handle = check_handle("xrtDeviceOpen", unsafe { xrtDeviceOpen(index) })?;
+    Ok(Arc::new(Self { handle }))
+  }
+
+  pub(crate) fn load_xclbin(&self, xclbin: &XrtXclbin) -> Result<(), FpgaKeyError> {
+    check("xrtDeviceLoadXclbinHandle", unsafe {
+      xrtDeviceLoadXclbinHandle(self.handle, xclbin.handle)
+    })
+  }
+
+  pub(crate) fn xclbin_uuid(&self) -> Result<[u8; 16], FpgaKeyError> {
+    let mut uuid = [0u8; 16];
+    check("xrtDeviceGetXclbinUUID", unsafe {
+      xrtDeviceGetXclbinUUID(self.handle, uuid.as_mut_ptr())
+    })?;
+    Ok(uuid)
+  }
+}
+
+impl Drop for XrtDevice {
+  fn drop(&mut self) {
+    unsafe {
+      xrtDeviceClose(self.handle);
+    }
+  }
+}
+
+/// An FPGA image read from disk. It can be dropped once loaded on a device.
+pub(crate) struct XrtXclbin {
+  handle: xrtXclbinHandle,
+}
+
+// SAFETY: the xclbin handle is owned and only read after it has been created.
+unsafe impl Send for XrtXclbin {}
+unsafe impl Sync for XrtXclbin {}
+
+impl XrtXclbin {
+  pub(crate) fn from_file(path: &str) -> Result<Self, FpgaKeyError> {
+    let path = CString::new(path).unwrap();
+    let handle = check_handle("xrtXclbinAllocFilename", unsafe {
+      xrtXclbinAllocFilename(path.as_ptr())
+    })?;
+    Ok(Self { handle })
+  }
+}
+
+impl Drop for XrtXclbin {
+  fn drop(&mut self) {
+    unsafe {
+      xrtXclbinFreeHandle(self.handle);
+    }
+  }
+}
+
+pub(crate) struct XrtKernel {
+  handle: xrtKernelHandle,
+  _device: Arc<XrtDevice>,
+}
+
+// SAFETY: the kernel handle is owned, and a kernel is only queried or used to open runs, which
+// XRT synchronizes internally.
+unsafe impl Send for XrtKernel {}
+unsafe impl Sync for XrtKernel {}
+
+impl XrtKernel {
+  /// Open the kernel `name` of the image loaded on `device`, for this process only.
+  pub(crate) fn open_exclusive(
+    device: &Arc<XrtDevice>,
+    name: &str,
+  ) -> Result<Arc<Self>, FpgaKeyError> {
+    let uuid = device.xclbin_uuid()?;
+    let name = CString::new(name).unwrap();
+    let handle = check_handle("xrtPLKernelOpenExclusive", unsafe {
+      xrtPLKernelOpenExclusive(device.handle, uuid.as_ptr(), name.as_ptr())
+    })?;
+    Ok(Arc::new(Self {
+      handle,
+      _device: Arc::clone(device),
+    }))
+  }
+
+  /// The memory bank the kernel argument `index` is connected to.
+  pub(crate) fn arg_group_id(&self, index: i32) -> Result<u32, FpgaKeyError> {
+    let group = unsafe { xrtKernelArgGroupId(self.handle, index) };
+    if group < 0 {
+      return Err(FpgaKeyError::DeviceFailure {
+        call: "xrtKernelArgGroupId",
+        code: group,
+      });
+    }
+    Ok(group as u32)
+  }
+}
+
+impl Drop for XrtKernel {
+  fn drop(&mut self) {
+    unsafe {
+      xrtKernelClose(self.handle);
+    }
+  }
+}
+
+pub(crate) struct XrtRun {
+  handle: xrtRunHandle,
+  _kernel: Arc<XrtKernel>,
+}
+
+// SAFETY: the run handle is owned; mutating calls go through `&mut self`.
+unsafe impl Send for XrtRun {}
+
+impl XrtRun {
+  pub(crate) fn open(kernel: &Arc<XrtKernel>) -> Result<Self, FpgaKeyError> {
+    let handle = check_handle("xrtRunOpen", unsafe { xrtRunOpen(kernel.handle) })?;
+    Ok(Self {
+      handle,
+      _kernel: Arc::clone(kernel),
+    })
+  }
+
+  /// Pass `buffer` as the kernel argument `index`.
+  ///
+  /// The run only records the handle: `buffer` must be alive whenever the run is started.
+  pub(crate) fn set_buffer_arg(
+    &mut self,
+    index: i32,
+    buffer: &XrtBuffer,
+  ) -> Result<(), FpgaKeyError> {
+    check("xrtRunSetArg", unsafe {
+      xrtRunSetArg(self.handle, index, buffer.handle)
+    })
+  }
+
+  pub(crate) fn set_scalar_arg(&mut self, index: i32, value: i32) -> Result<(), FpgaKeyError> {
+    check("xrtRunSetArg", unsafe {
+      xrtRunSetArg(self.handle, index, value)
+    })
+  }
+
+  /// Start the kernel and block until it completes.
+  pub(crate) fn start_and_wait(&mut self) -> Result<(), FpgaKeyError> {
+    check("xrtRunStart", unsafe { xrtRunStart(self.handle) })?;
+    let state = unsafe { xrtRunWait(self.handle) };
+    if state != ert_cmd_state_ERT_CMD_STATE_COMPLETED {
+      return Err(FpgaKeyError::DeviceFailure {
+        call: "xrtRunWait",
+        code: state as c_int,
+      });
+    }
+    Ok(())
+  }
+}
+
+impl Drop for XrtRun {
+  fn drop(&mut self) {
+    unsafe {
+      xrtRunClose(self.handle);
+    }
+  }
+}
+
+pub(crate) struct XrtBuffer {
+  handle: xrtBufferHandle,
+  size: usize,
+  _device: Arc<XrtDevice>,
+}
+
+// SAFETY: the buffer handle is owned; reads and writes go through `&mut self`.
+unsafe impl Send for XrtBuffer {}
+
+impl XrtBuffer {
+  /// Allocate `size` bytes in the memory bank `group` of `device`.
+  pub(crate) fn new(
+    device: &Arc<XrtDevice>,
+    size: usize,
+    group: u32,
+  ) -> Result<Self, FpgaKeyError> {
+    let handle = check_handle("xrtBOAlloc", unsafe {
+      xrtBOAlloc(device.handle, size, 0u64, group)
+    })?;
+    Ok(Self {
+      handle,
+      size,
+      _device: Arc::clone(device),
+    })
+  }
+
+  /// Copy `data` to the start of the buffer, then sync it to the device.
+  pub(crate) fn write_to_device<T: Copy>(&mut self, data: &[T]) -> Result<(), FpgaKeyError> {
+    let bytes = std::mem::size_of_val(data);
+    assert!(
+      bytes <= self.size,
+      "{bytes} bytes do not fit in a {} bytes buffer",
+      self.size
+    );
+
+    check("xrtBOWrite", unsafe {
+      xrtBOWrite(self.handle, data.as_ptr() as *const c_void, bytes, 0)
+    })?;
+    check("xrtBOSync", unsafe {
+      xrtBOSync(
+        self.handle,
+        xclBOSyncDirection_XCL_BO_SYNC_BO_TO_DEVICE,
+        bytes,
+        0,
+      )
+    })
+  }
+
+  /// Sync the start of the buffer from the device, then copy it to `data`.
+  pub(crate) fn read_from_device<T: Copy>(&mut self, data: &mut [T]) -> Result<(), FpgaKeyError> {
+    let bytes = std::mem::size_of_val(data);
+    assert!(
+      bytes <= self.size,
+      "{bytes} bytes do not fit in a {} bytes buffer",
+      self.size
+    );
+
+    check("xrtBOSync", unsafe {
+      xrtBOSync(
+        self.handle,
+        xclBOSyncDirection_XCL_BO_SYNC_BO_FROM_DEVICE,
+        bytes,
+        0,
+      )
+    })?;
+    check("xrtBORead", unsafe {
+      xrtBORead(self.handle, data.as_mut_ptr() as *mut c_void, bytes, 0)
+    })
+  }
+}
+
+impl Drop for XrtBuffer {
+  fn drop(&mut self) {
+    unsafe {
+      xrtBOFree(self.handle);
+    }
+  }
+}
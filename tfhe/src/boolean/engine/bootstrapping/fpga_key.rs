@@ -0,0 +1,660 @@
+//! Bootstrapping key in the memory layout of the FPT accelerator.
+//!
+//! The FPT kernel reads its bootstrapping key from a dedicated device buffer, as fixed-point
+//! values and in the order in which its streaming FFT consumes them. Deriving this image from a
+//! [`FourierLweBootstrapKeyOwned`] requires copying, regrouping and quantizing the whole key, so
+//! [`FpgaBootstrapKey`] keeps the result: it can be computed once by the client, serialized or
+//! cached on disk, and uploaded to the card as is.
+
+use crate::boolean::engine::bootstrapping::{CompressedServerKey, ServerKey};
+use crate::boolean::parameters::{
+  BSK_AXI_WIDTH, BSK_DEPTH, BSK_INT_WIDTH, BSK_SIZE, BSK_WIDTH, DEMO_PARAMETERS, FFT_STREAMING_SIZE,
+};
+use crate::core_crypto::commons::parameters::{
+  DecompositionBaseLog, DecompositionLevelCount, GlweDimension, LweDimension, PolynomialSize,
+};
+use crate::core_crypto::entities::*;
+use concrete_fft::c64;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[cfg(test)]
+mod tests;
+
+/// Version of the [`FpgaBootstrapKey`] format, to be bumped whenever the memory layout changes.
+pub const FPGA_BOOTSTRAP_KEY_VERSION: u32 = 2;
+
+/// Number of bits reserved for each fixed-point coefficient in an AXI transfer.
+pub const BSK_COEFF_SEPARATION: usize = 64;
+
+/// Shape of the bootstrapping key stored in an [`FpgaBootstrapKey`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpgaKeyGeometry {
+  pub input_lwe_dimension: LweDimension,
+  pub glwe_dimension: GlweDimension,
+  pub polynomial_size: PolynomialSize,
+  pub decomposition_base_log: DecompositionBaseLog,
+  pub decomposition_level_count: DecompositionLevelCount,
+}
+
+impl FpgaKeyGeometry {
+  /// The geometry supported by the FPT image, fixed by [`DEMO_PARAMETERS`].
+  pub fn device() -> Self {
+    Self {
+      input_lwe_dimension: DEMO_PARAMETERS.lwe_dimension,
+      glwe_dimension: DEMO_PARAMETERS.glwe_dimension,
+      polynomial_size: DEMO_PARAMETERS.polynomial_size,
+      decomposition_base_log: DEMO_PARAMETERS.pbs_base_log,
+      decomposition_level_count: DEMO_PARAMETERS.pbs_level,
+    }
+  }
+
+  pub fn from_bootstrap_key(bsk: &FourierLweBootstrapKeyOwned) -> Self {
+    Self {
+      input_lwe_dimension: bsk.input_lwe_dimension(),
+      glwe_dimension: bsk.glwe_size().to_glwe_dimension(),
+      polynomial_size: bsk.polynomial_size(),
+      decomposition_base_log: bsk.decomposition_base_log(),
+      decomposition_level_count: bsk.decomposition_level_count(),
+    }
+  }
+
+  pub fn from_standard_bootstrap_key(bsk: &LweBootstrapKeyOwned<u32>) -> Self {
+    Self {
+      input_lwe_dimension: bsk.input_lwe_dimension(),
+      glwe_dimension: bsk.glwe_size().to_glwe_dimension(),
+      polynomial_size: bsk.polynomial_size(),
+      decomposition_base_log: bsk.decomposition_base_log(),
+      decomposition_level_count: bsk.decomposition_level_count(),
+    }
+  }
+}
+
+/// Fixed-point format and streaming widths the memory image was built for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpgaKeyWidths {
+  /// Total width of a fixed-point coefficient.
+  pub bsk_width: usize,
+  /// Width of the integer part of a fixed-point coefficient.
+  pub bsk_int_width: usize,
+  /// Width of one AXI transfer.
+  pub bsk_axi_width: usize,
+  /// Bits reserved for each coefficient in an AXI transfer.
+  pub bsk_coeff_separation: usize,
+  /// Number of complex coefficients streamed per polynomial.
+  pub fft_streaming_size: usize,
+  /// Number of BSK ROM addresses.
+  pub bsk_depth: usize,
+}
+
+impl FpgaKeyWidths {
+  /// The widths of the FPT image.
+  pub fn device() -> Self {
+    Self {
+      bsk_width: BSK_WIDTH,
+      bsk_int_width: BSK_INT_WIDTH,
+      bsk_axi_width: BSK_AXI_WIDTH,
+      bsk_coeff_separation: BSK_COEFF_SEPARATION,
+      fft_streaming_size: FFT_STREAMING_SIZE,
+      bsk_depth: BSK_DEPTH,
+    }
+  }
+}
+
+/// Versioned description of the content of an [`FpgaBootstrapKey`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpgaKeyHeader {
+  pub version: u32,
+  pub geometry: FpgaKeyGeometry,
+  pub widths: FpgaKeyWidths,
+  /// Fingerprint of `geometry` and `widths`, see [`FpgaKeyHeader::parameter_fingerprint`].
+  pub parameter_fingerprint: u64,
+}
+
+impl FpgaKeyHeader {
+  pub fn new(geometry: FpgaKeyGeometry, widths: FpgaKeyWidths) -> Self {
+    Self {
+      version: FPGA_BOOTSTRAP_KEY_VERSION,
+      geometry,
+      widths,
+      parameter_fingerprint: Self::parameter_fingerprint(&geometry, &widths),
+    }
+  }
+
+  /// The header the FPT image expects.
+  pub fn device() -> Self {
+    Self::new(FpgaKeyGeometry::device(), FpgaKeyWidths::device())
+  }
+
+  /// Compute a stable 64-bit fingerprint of the parameters a memory image depends on.
+  ///
+  /// The fingerprint does not depend on the platform or on the compiler version, so it can be
+  /// compared between the client producing an image and the server uploading it.
+  pub fn parameter_fingerprint(geometry: &FpgaKeyGeometry, widths: &FpgaKeyWidths) -> u64 {
+    fingerprint_words([
+      geometry.input_lwe_dimension.0 as u64,
+      geometry.glwe_dimension.0 as u64,
+      geometry.polynomial_size.0 as u64,
+      geometry.decomposition_base_log.0 as u64,
+      geometry.decomposition_level_count.0 as u64,
+      widths.bsk_width as u64,
+      widths.bsk_int_width as u64,
+      widths.bsk_axi_width as u64,
+      widths.bsk_coeff_separation as u64,
+      widths.fft_streaming_size as u64,
+      widths.bsk_depth as u64,
+    ])
+  }
+}
+
+/// 64-bit FNV-1a hash of a sequence of words, each hashed in little-endian order.
+pub(crate) fn fingerprint_words<I: IntoIterator<Item = u64>>(words: I) -> u64 {
+  const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+  const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+  words
+    .into_iter()
+    .flat_map(u64::to_le_bytes)
+    .fold(FNV_OFFSET_BASIS, |hash, byte| {
+      (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Fingerprint identifying a Fourier bootstrapping key, used to track which keys the FPGA holds.
+pub fn bootstrapping_key_fingerprint(bsk: &FourierLweBootstrapKeyOwned) -> u64 {
+  fingerprint_words(
+    bsk
+      .as_view()
+      .data()
+      .iter()
+      .flat_map(|c| [c.re.to_bits(), c.im.to_bits()]),
+  )
+}
+
+/// Error returned when an [`FpgaBootstrapKey`] cannot be built or uploaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FpgaKeyError {
+  UnsupportedVersion {
+    found: u32,
+    expected: u32,
+  },
+  GeometryMismatch {
+    found: FpgaKeyGeometry,
+    expected: FpgaKeyGeometry,
+  },
+  WidthsMismatch {
+    found: FpgaKeyWidths,
+    expected: FpgaKeyWidths,
+  },
+  FingerprintMismatch {
+    found: u64,
+    expected: u64,
+  },
+  /// The memory image was computed from another bootstrapping key than the one it is used
+  /// with.
+  SourceFingerprintMismatch {
+    found: u64,
+    expected: u64,
+  },
+  InvalidImageSize {
+    found: usize,
+    expected: usize,
+  },
+  KeySlotsFull {
+    capacity: usize,
+  },
+  KeyNotResident {
+    fingerprint: u64,
+  },
+  /// The environment does not name the image or the card to open.
+  DeviceNotConfigured {
+    variable: &'static str,
+  },
+  /// An XRT call failed while driving the FPGA.
+  DeviceFailure {
+    call: &'static str,
+    code: i32,
+  },
+}
+
+impl Display for FpgaKeyError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      FpgaKeyError::UnsupportedVersion { found, expected } => {
+        write!(
+          f,
+          "Unsupported FPGA bootstrapping key version {found}, expected {expected}"
+        )
+      }
+      FpgaKeyError::GeometryMismatch { found, expected } => {
+        write!(
+          f,
+          "The bootstrapping key geometry {found:?} does not match the FPGA image \
+                    geometry {expected:?}"
+        )
+      }
+      FpgaKeyError::WidthsMismatch { found, expected } => {
+        write!(
+          f,
+          "The bootstrapping key widths {found:?} do not match the FPGA image widths \
+                    {expected:?}"
+        )
+      }
+      FpgaKeyError::FingerprintMismatch { found, expected } => {
+        write!(
+          f,
+          "The parameter fingerprint {found:#018x} does not match the expected \
+                    fingerprint {expected:#018x}"
+        )
+      }
+      FpgaKeyError::SourceFingerprintMismatch { found, expected } => {
+        write!(
+          f,
+          "The memory image was computed from the bootstrapping key {found:#018x}, not \
+                    from the bootstrapping key {expected:#018x} of the server key"
+        )
+      }
+      FpgaKeyError::InvalidImageSize { found, expected } => {
+        write!(
+          f,
+          "The memory image holds {found} words, the FPGA expects {expected} words"
+        )
+      }
+      FpgaKeyError::KeySlotsFull { capacity } => {
+        write!(
+          f,
+          "All {capacity} FPGA key slots are in use, evict a key before uploading \
+                    another one"
+        )
+      }
+      FpgaKeyError::KeyNotResident { fingerprint } => {
+        write!(
+          f,
+          "No bootstrapping key with fingerprint {fingerprint:#018x} is resident on \
+                    the FPGA"
+        )
+      }
+      FpgaKeyError::DeviceNotConfigured { variable } => {
+        write!(
+          f,
+          "The {variable} environment variable is missing or invalid, set FPGA_IMAGE \
+                    to the FPGA image and FPGA_INDEX to the index of the card"
+        )
+      }
+      FpgaKeyError::DeviceFailure { call, code } => {
+        write!(f, "{call} failed with code {code}")
+      }
+    }
+  }
+}
+
+impl std::error::Error for FpgaKeyError {}
+
+/// A bootstrapping key laid out as the FPT accelerator reads it from device memory.
+///
+/// # Example
+///
+/// ```rust
+/// use tfhe::boolean::prelude::*;
+/// use tfhe::boolean::server_key::FpgaBootstrapKey;
+///
+/// let client_key = ClientKey::new(&DEMO_PARAMETERS);
+/// let server_key = ServerKey::new(&client_key);
+///
+/// // The client computes the device image once and ships it along with the server key
+/// let fpga_key = FpgaBootstrapKey::from_server_key(&server_key).unwrap();
+/// let serialized = bincode::serialize(&fpga_key).unwrap();
+///
+/// let fpga_key: FpgaBootstrapKey = bincode::deserialize(&serialized).unwrap();
+/// assert!(fpga_key.check_compatibility().is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpgaBootstrapKey {
+  header: FpgaKeyHeader,
+  /// Fingerprint of the Fourier bootstrapping key the image was computed from, if any.
+  source_fingerprint: Option<u64>,
+  data: Vec<u64>,
+}
+
+impl FpgaBootstrapKey {
+  /// Convert a Fourier bootstrapping key into the FPT memory image.
+  ///
+  /// Returns an error if the key does not have the geometry supported by the FPGA image.
+  pub fn new(bsk: &FourierLweBootstrapKeyOwned) -> Result<Self, FpgaKeyError> {
+    Self::from_fourier_key(bsk, bootstrapping_key_fingerprint(bsk))
+  }
+
+  pub fn from_server_key(server_key: &ServerKey) -> Result<Self, FpgaKeyError> {
+    // Reuse the fingerprint cached in the server key
+    Self::from_fourier_key(
+      &server_key.bootstrapping_key,
+      server_key.bootstrapping_key_fingerprint(),
+    )
+  }
+
+  fn from_fourier_key(
+    bsk: &FourierLweBootstrapKeyOwned,
+    source_fingerprint: u64,
+  ) -> Result<Self, FpgaKeyError> {
+    let header = FpgaKeyHeader::device();
+    let geometry = FpgaKeyGeometry::from_bootstrap_key(bsk);
+    if geometry != header.geometry {
+      return Err(FpgaKeyError::GeometryMismatch {
+        found: geometry,
+        expected: header.geometry,
+      });
+    }
+
+    let data = bsk_for_fpga(&bsk_reorder(bsk));
+
+    Ok(Self {
+      header,
+      source_fingerprint: Some(source_fingerprint),
+      data,
+    })
+  }
+
+  /// Convert a standard bootstrapping key straight into the FPT memory image.
+  ///
+  /// Unlike [`FpgaBootstrapKey::new`], this does not quantize the floating-point values of a
+  /// Fourier key: every fixed-point coefficient is computed from the torus polynomials and
+  /// rounded once, so the image does not depend on the FFT plan of the machine producing it.
+  ///
+  /// For the same reason, the image is not tied to the Fourier key the server computes from
+  /// `bsk`, and cannot be checked against it when it is uploaded.
+  pub fn from_standard_bootstrap_key(
+    bsk: &LweBootstrapKeyOwned<u32>,
+  ) -> Result<Self, FpgaKeyError> {
+    let header = FpgaKeyHeader::device();
+    let geometry = FpgaKeyGeometry::from_standard_bootstrap_key(bsk);
+    if geometry != header.geometry {
+      return Err(FpgaKeyError::GeometryMismatch {
+        found: geometry,
+        expected: header.geometry,
+      });
+    }
+
+    let data = standard_bsk_for_fpga(bsk);
+
+    Ok(Self {
+      header,
+      source_fingerprint: None,
+      data,
+    })
+  }
+
+  pub fn from_compressed_server_key(
+    server_key: &CompressedServerKey,
+  ) -> Result<Self, FpgaKeyError> {
+    let standard_bsk = server_key
+      .bootstrapping_key
+      .clone()
+      .decompress_into_lwe_bootstrap_key();
+    Self::from_standard_bootstrap_key(&standard_bsk)
+  }
+
+  pub fn header(&self) -> &FpgaKeyHeader {
+    &self.header
+  }
+
+  /// Fingerprint of the Fourier bootstrapping key the image was computed from, see
+  /// [`ServerKey::bootstrapping_key_fingerprint`].
+  ///
+  /// Images computed from a standard bootstrapping key have none.
+  pub fn source_fingerprint(&self) -> Option<u64> {
+    self.source_fingerprint
+  }
+
+  /// Check that the image was computed from the bootstrapping key of `server_key`.
+  ///
+  /// Images without a source fingerprint cannot be checked and are accepted.
+  pub fn check_source(&self, server_key: &ServerKey) -> Result<(), FpgaKeyError> {
+    match self.source_fingerprint {
+      Some(found) => {
+        let expected = server_key.bootstrapping_key_fingerprint();
+        if found == expected {
+          Ok(())
+        } else {
+          Err(FpgaKeyError::SourceFingerprintMismatch { found, expected })
+        }
+      }
+      None => Ok(()),
+    }
+  }
+
+  /// The memory image, as written to the device bootstrapping key buffer.
+  pub fn data(&self) -> &[u64] {
+    &self.data
+  }
+
+  pub fn size_bytes(&self) -> usize {
+    std::mem::size_of_val(self.data.as_slice())
+  }
+
+  /// Check that the key can be uploaded to the FPT image this crate was built for.
+  ///
+  /// This should be called on keys obtained from an untrusted or outdated source, e.g. after
+  /// deserialization.
+  pub fn check_compatibility(&self) -> Result<(), FpgaKeyError> {
+    let expected = FpgaKeyHeader::device();
+
+    if self.header.version != expected.version {
+      return Err(FpgaKeyError::UnsupportedVersion {
+        found: self.header.version,
+        expected: expected.version,
+      });
+    }
+    if self.header.geometry != expected.geometry {
+      return Err(FpgaKeyError::GeometryMismatch {
+        found: self.header.geometry,
+        expected: expected.geometry,
+      });
+    }
+    if self.header.widths != expected.widths {
+      return Err(FpgaKeyError::WidthsMismatch {
+        found: self.header.widths,
+        expected: expected.widths,
+      });
+    }
+    if self.header.parameter_fingerprint != expected.parameter_fingerprint {
+      return Err(FpgaKeyError::FingerprintMismatch {
+        found: self.header.parameter_fingerprint,
+        expected: expected.parameter_fingerprint,
+      });
+    }
+
+    let expected_len = BSK_SIZE / std::mem::size_of::<u64>();
+    if self.data.len() != expected_len {
+      return Err(FpgaKeyError::InvalidImageSize {
+        found: self.data.len(),
+        expected: expected_len,
+      });
+    }
+
+    Ok(())
+  }
+}
+
+/// Quantize the reordered Fourier key to the FPT fixed-point format and interleave it in AXI
+/// transfers.
+pub(crate) fn bsk_for_fpga(bsk_reordered: &[Vec<Vec<c64>>]) -> Vec<u64> {
+  let coeff_pair_per_transfer: usize = BSK_AXI_WIDTH / (BSK_COEFF_SEPARATION * 2);
+
+  fn c64_to_memval(value: f64) -> u64 {
+    assert!(
+      BSK_WIDTH < 64,
+      "The float to integer conversion works for upto 64-bit"
+    );
+    let factor = 2_u64.pow((BSK_WIDTH - BSK_INT_WIDTH) as u32);
+    let rounded_value = (value * factor as f64).round();
+    (rounded_value as i64) as u64
+  }
+
+  let mut bsk_mem_content: Vec<u64> = Vec::new();
+
+  for gdim in 0..(DEMO_PARAMETERS.glwe_dimension.0 + 1) {
+    for coeff in (0..FFT_STREAMING_SIZE).step_by(coeff_pair_per_transfer) {
+      for d in 0..BSK_DEPTH {
+        for c in 0..coeff_pair_per_transfer {
+          let coeff_index = coeff + c;
+          let complex = bsk_reordered[d][gdim][coeff_index];
+          bsk_mem_content.push(c64_to_memval(complex.im));
+          bsk_mem_content.push(c64_to_memval(complex.re));
+        }
+      }
+    }
+  }
+  bsk_mem_content
+}
+
+// Reorders for Vec<Vec<Vec>>:
+//   The outer vector is for BROM addresses
+//   The middle vector is for glwe-dimension
+//   The inner vector is for polynomial coefficients
+// The bootstrapp hw reads a 2D vector from given BSK ROM address
+pub(crate) fn bsk_reorder(bsk: &FourierLweBootstrapKeyOwned) -> Vec<Vec<Vec<c64>>> {
+  let glwe_dimension = DEMO_PARAMETERS.glwe_dimension.0;
+  let pbs_level = DEMO_PARAMETERS.pbs_level.0;
+  let polynomial_size = DEMO_PARAMETERS.polynomial_size.0;
+
+  bsk
+    .as_view()
+    .data()
+    // Get the data as polynomial chunks: Vec<Vec<c64>>
+    .chunks(polynomial_size / 2)
+    // The first element should be put at the last position
+    // likely because of different twist twiddles
+    .map(|v| {
+      let mut x = v.to_vec();
+      x.rotate_left(1);
+      x
+    })
+    .collect::<Vec<Vec<c64>>>()
+    // Group by Columns: Vec<Vec<Vec<c64>>>
+    .chunks(glwe_dimension + 1)
+    .map(|v| v.to_owned())
+    .collect::<Vec<Vec<Vec<c64>>>>()
+    // Group by Rows: Vec<Vec<Vec<Vec<c64>>>>
+    .chunks(glwe_dimension + 1)
+    .map(|v| v.to_owned())
+    .collect::<Vec<Vec<Vec<Vec<c64>>>>>()
+    // Group by Levels: Vec<Vec<Vec<Vec<Vec<c64>>>>>
+    .chunks(pbs_level)
+    .map(|v| v.to_owned())
+    .collect::<Vec<Vec<Vec<Vec<Vec<c64>>>>>>()
+    // Reverse: Vec<Vec<Vec<Vec<Vec<c64>>>>>
+    .into_iter()
+    .map(|v| v.into_iter().rev().collect())
+    .collect::<Vec<Vec<Vec<Vec<Vec<c64>>>>>>()
+    // Transpose: Vec<Vec<Vec<Vec<Vec<c64>>>>>
+    .into_iter()
+    .map(|v| {
+      let rows = v.len();
+      let cols = v[0].len();
+      (0..cols)
+        .map(|col| (0..rows).map(|row| v[row][col].clone()).collect())
+        .collect()
+    })
+    .collect::<Vec<Vec<Vec<Vec<Vec<c64>>>>>>()
+    // Flatten twice: Vec<Vec<Vec<c64>>>
+    .into_iter()
+    .flatten()
+    .flatten()
+    .collect::<Vec<Vec<Vec<c64>>>>()
+}
+
+/// Fractional bits of the fixed-point roots of unity used by [`standard_bsk_for_fpga`].
+const FPT_ROOT_FRACTIONAL_BITS: u32 = 62;
+
+/// The `2N`-th roots of unity $\zeta^t = e^{i\pi t/N}$, as fixed-point `(re, im)` pairs.
+fn fpt_roots(polynomial_size: usize) -> Vec<(i128, i128)> {
+  let scale = (1u64 << FPT_ROOT_FRACTIONAL_BITS) as f64;
+  (0..2 * polynomial_size)
+    .map(|t| {
+      let (sin, cos) = (t as f64 * std::f64::consts::PI / polynomial_size as f64).sin_cos();
+      ((cos * scale).round() as i128, (sin * scale).round() as i128)
+    })
+    .collect()
+}
+
+/// Compute the `N/2` fixed-point `(re, im)` values the FPT streams for one torus polynomial.
+///
+/// Slot $m$ holds $\sum_{j<N/2} (p_j + i \cdot p_{j+N/2}) \cdot \zeta^{-j(4m+3)}$, with the $p_j$
+/// read as signed torus elements. This is the twisted FFT computed by
+/// [`Fft`](`crate::core_crypto::fft_impl::fft64::math::fft::Fft`), with its first output moved to
+/// the last position. The sums are accumulated exactly and rounded once to the fixed-point format.
+fn polynomial_to_fpt_slots(polynomial: &[u32], roots: &[(i128, i128)]) -> Vec<(u64, u64)> {
+  let polynomial_size = polynomial.len();
+  let half_size = polynomial_size / 2;
+  let root_count = 2 * polynomial_size;
+
+  // Torus elements have 32 fractional bits, the roots FPT_ROOT_FRACTIONAL_BITS
+  let shift = u32::BITS + FPT_ROOT_FRACTIONAL_BITS - (BSK_WIDTH - BSK_INT_WIDTH) as u32;
+  let to_memval = |acc: i128| -> u64 {
+    // Round half away from zero, like f64::round
+    let half = 1i128 << (shift - 1);
+    let rounded = if acc >= 0 {
+      (acc + half) >> shift
+    } else {
+      -((half - acc) >> shift)
+    };
+    (rounded as i64) as u64
+  };
+
+  let (poly_re, poly_im) = polynomial.split_at(half_size);
+
+  (0..half_size)
+    .map(|m| {
+      let (mut acc_re, mut acc_im) = (0i128, 0i128);
+      for (j, (&re, &im)) in poly_re.iter().zip(poly_im.iter()).enumerate() {
+        let (re, im) = (re as i32 as i128, im as i32 as i128);
+        let (w_re, w_im) = roots[(root_count - (j * (4 * m + 3)) % root_count) % root_count];
+        acc_re += re * w_re - im * w_im;
+        acc_im += re * w_im + im * w_re;
+      }
+      (to_memval(acc_re), to_memval(acc_im))
+    })
+    .collect()
+}
+
+/// Reference conversion of a standard bootstrapping key into the FPT memory image.
+///
+/// Produces the same layout as [`bsk_for_fpga`] applied to [`bsk_reorder`], up to one LSB of
+/// rounding, without going through the Fourier key.
+pub(crate) fn standard_bsk_for_fpga(bsk: &LweBootstrapKeyOwned<u32>) -> Vec<u64> {
+  let glwe_size = bsk.glwe_size().0;
+  let level_count = bsk.decomposition_level_count().0;
+  let polynomial_size = bsk.polynomial_size().0;
+  let coeff_pair_per_transfer: usize = BSK_AXI_WIDTH / (BSK_COEFF_SEPARATION * 2);
+
+  let roots = fpt_roots(polynomial_size);
+  let slots: Vec<Vec<(u64, u64)>> = bsk
+    .as_ref()
+    .chunks_exact(polynomial_size)
+    .map(|polynomial| polynomial_to_fpt_slots(polynomial, &roots))
+    .collect();
+
+  // BSK ROM address d holds, for one GGSW and one row, the levels in reverse order; the
+  // polynomials of the standard key are stored by GGSW, level, row and column.
+  let polynomial_index = |address: usize, column: usize| {
+    let (ggsw_row, reversed_level) = (address / level_count, address % level_count);
+    let (ggsw, row) = (ggsw_row / glwe_size, ggsw_row % glwe_size);
+    let level = level_count - 1 - reversed_level;
+    ((ggsw * level_count + level) * glwe_size + row) * glwe_size + column
+  };
+
+  let mut bsk_mem_content: Vec<u64> = Vec::with_capacity(BSK_SIZE / std::mem::size_of::<u64>());
+
+  for gdim in 0..glwe_size {
+    for coeff in (0..FFT_STREAMING_SIZE).step_by(coeff_pair_per_transfer) {
+      for d in 0..BSK_DEPTH {
+        for c in 0..coeff_pair_per_transfer {
+          let (re, im) = slots[polynomial_index(d, gdim)][coeff + c];
+          bsk_mem_content.push(im);
+          bsk_mem_content.push(re);
+        }
+      }
+    }
+  }
+  bsk_mem_content
+}
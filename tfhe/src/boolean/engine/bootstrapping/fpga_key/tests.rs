@@ -0,0 +1,173 @@
+use super::*;
+use crate::boolean::keycache::{FpgaImageStorage, KEY_CACHE, KEY_CACHE_FPGA};
+use crate::boolean::parameters::DEFAULT_PARAMETERS;
+use crate::core_crypto::prelude::*;
+use crate::keycache::PersistentStorage;
+
+#[test]
+fn test_fpga_key_serialization_round_trip() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let fpga_key = keys.fpga_key();
+
+  assert!(fpga_key.check_compatibility().is_ok());
+  assert_eq!(*fpga_key.header(), FpgaKeyHeader::device());
+  assert_eq!(fpga_key.size_bytes(), BSK_SIZE);
+
+  let serialized = bincode::serialize(fpga_key).unwrap();
+  let deserialized: FpgaBootstrapKey = bincode::deserialize(&serialized).unwrap();
+
+  assert_eq!(&deserialized, fpga_key);
+  assert!(deserialized.check_compatibility().is_ok());
+}
+
+#[test]
+fn test_fpga_key_matches_server_key() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let fpga_key = FpgaBootstrapKey::from_server_key(keys.server_key()).unwrap();
+
+  assert_eq!(&fpga_key, keys.fpga_key());
+  assert_eq!(
+    fpga_key.source_fingerprint(),
+    Some(keys.server_key().bootstrapping_key_fingerprint())
+  );
+  assert!(fpga_key.check_source(keys.server_key()).is_ok());
+}
+
+#[test]
+fn test_fpga_image_storage_discards_stale_images() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let directory = std::env::temp_dir().join(format!("tfhe-fpga-images-{}", std::process::id()));
+  let storage = FpgaImageStorage::new(directory.to_str().unwrap().to_string());
+
+  // An image left over from a regenerated key cache is computed again
+  let stale = FpgaBootstrapKey::from_server_key(&ServerKey::new(keys.client_key())).unwrap();
+  storage.store(DEMO_PARAMETERS, &stale);
+  assert_eq!(storage.load(DEMO_PARAMETERS), None);
+
+  storage.store(DEMO_PARAMETERS, keys.fpga_key());
+  assert_eq!(
+    storage.load(DEMO_PARAMETERS).as_ref(),
+    Some(keys.fpga_key())
+  );
+
+  std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn test_fpga_key_rejects_other_server_key() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let other_server_key = ServerKey::new(keys.client_key());
+
+  assert!(matches!(
+    keys.fpga_key().check_source(&other_server_key),
+    Err(FpgaKeyError::SourceFingerprintMismatch { .. })
+  ));
+}
+
+#[test]
+fn test_fpga_key_unsupported_geometry() {
+  let keys = KEY_CACHE.get_from_param(DEFAULT_PARAMETERS);
+
+  let err = FpgaBootstrapKey::from_server_key(keys.server_key()).unwrap_err();
+  assert!(matches!(err, FpgaKeyError::GeometryMismatch { .. }));
+}
+
+#[test]
+fn test_fpga_key_rejects_altered_header() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+
+  let mut fpga_key = keys.fpga_key().clone();
+  fpga_key.header.version += 1;
+  assert!(matches!(
+    fpga_key.check_compatibility(),
+    Err(FpgaKeyError::UnsupportedVersion { .. })
+  ));
+
+  let mut fpga_key = keys.fpga_key().clone();
+  fpga_key.header.parameter_fingerprint ^= 1;
+  assert!(matches!(
+    fpga_key.check_compatibility(),
+    Err(FpgaKeyError::FingerprintMismatch { .. })
+  ));
+
+  let mut fpga_key = keys.fpga_key().clone();
+  fpga_key.data.pop();
+  assert!(matches!(
+    fpga_key.check_compatibility(),
+    Err(FpgaKeyError::InvalidImageSize { .. })
+  ));
+}
+
+#[test]
+fn test_fpt_slots_of_constant_polynomial() {
+  // A constant 1/4 evaluates to 1/4 at every root, with no imaginary part
+  let polynomial_size = DEMO_PARAMETERS.polynomial_size.0;
+  let mut polynomial = vec![0u32; polynomial_size];
+  polynomial[0] = 1 << 30;
+
+  let slots = polynomial_to_fpt_slots(&polynomial, &fpt_roots(polynomial_size));
+
+  let quarter = 1u64 << (BSK_WIDTH - BSK_INT_WIDTH - 2);
+  assert!(slots.iter().all(|&slot| slot == (quarter, 0)));
+}
+
+#[test]
+fn test_fpga_key_from_standard_bootstrap_key() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let client_key = keys.client_key();
+
+  let mut seeder = new_seeder();
+  let mut encryption_generator =
+    EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed(), seeder.as_mut());
+  let standard_bsk = par_allocate_and_generate_new_lwe_bootstrap_key(
+    &client_key.lwe_secret_key,
+    &client_key.glwe_secret_key,
+    DEMO_PARAMETERS.pbs_base_log,
+    DEMO_PARAMETERS.pbs_level,
+    DEMO_PARAMETERS.glwe_modular_std_dev,
+    CiphertextModulus::new_native(),
+    &mut encryption_generator,
+  );
+
+  let mut fourier_bsk = FourierLweBootstrapKey::new(
+    standard_bsk.input_lwe_dimension(),
+    standard_bsk.glwe_size(),
+    standard_bsk.polynomial_size(),
+    standard_bsk.decomposition_base_log(),
+    standard_bsk.decomposition_level_count(),
+  );
+  convert_standard_lwe_bootstrap_key_to_fourier(&standard_bsk, &mut fourier_bsk);
+
+  let reference = bsk_for_fpga(&bsk_reorder(&fourier_bsk));
+  let direct = FpgaBootstrapKey::from_standard_bootstrap_key(&standard_bsk).unwrap();
+
+  assert!(direct.check_compatibility().is_ok());
+  assert_eq!(direct.data().len(), reference.len());
+  for (index, (&direct, &reference)) in direct.data().iter().zip(reference.iter()).enumerate() {
+    let difference = (direct as i64).wrapping_sub(reference as i64);
+    assert!(
+      difference.abs() <= 1,
+      "word {index}: direct {direct:#x}, reference {reference:#x}"
+    );
+  }
+}
+
+#[test]
+fn test_bootstrapping_key_fingerprint() {
+  let keys = KEY_CACHE_FPGA.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+
+  let serialized = bincode::serialize(server_key).unwrap();
+  let deserialized: ServerKey = bincode::deserialize(&serialized).unwrap();
+  assert_eq!(
+    deserialized.bootstrapping_key_fingerprint(),
+    server_key.bootstrapping_key_fingerprint()
+  );
+
+  // A fresh key for the same client key must not be mistaken for the cached one
+  let other_server_key = ServerKey::new(keys.client_key());
+  assert_ne!(
+    other_server_key.bootstrapping_key_fingerprint(),
+    server_key.bootstrapping_key_fingerprint()
+  );
+}
@@ -0,0 +1,931 @@
+//! Packed bootstraps computed by a bootstrap server over TCP.
+//!
+//! A node without an accelerator can forward its packed bootstraps to a node holding one: the
+//! client keeps the keyswitches, modulus-switches each batch of linear combinations to the `2N`
+//! of the blind rotation, and sends it to a [`BootstrapServer`], which bootstraps it with the CPU
+//! or the FPGA and sends the bootstrapped ciphertexts back.
+//!
+//! # Protocol
+//!
+//! Every message is a frame made of its length in bytes, as a little-endian `u64`, followed by
+//! its bincode serialization. The client speaks first and every request gets exactly one
+//! response:
+//!
+//! 1. `Hello` carries [`REMOTE_BOOTSTRAP_PROTOCOL_VERSION`], the server answers with its own
+//!    version and the client hangs up if they differ.
+//! 2. Keys are negotiated by id, the [`ServerKey::remote_key_id`]: `HasKey` asks whether the
+//!    server already holds the key, and only when it does not is the whole server key sent
+//!    with `UploadKey`. A server whose key store is full answers `HasKey` for another key with an
+//!    error, so that the key is not sent for nothing. Keys are kept by the server across
+//!    connections until `EvictKey` removes them, so a key is uploaded once per server rather than
+//!    once per session.
+//! 3. `Bootstrap` sends a batch and the id of the key to bootstrap it with. The batch is sent
+//!    packed on the `log2(2N)` bits of its switched coefficients, and the bootstrapped ciphertexts
+//!    come back with their masks rounded to the precision of the keyswitch when the client
+//!    keyswitches them right away, see the [`batch_format`](super::batch_format) module.
+//!
+//! A request the server cannot serve is answered with an error message and the connection stays
+//! usable. Only `UploadKey` requests may be larger than [`REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE`]:
+//! when the key store cannot take such an upload, the server refuses it from the id in its
+//! header, answers with an error and hangs up without reading the key.
+
+use super::batch_format::{BootstrappedBatch, ModulusSwitchedBatch};
+#[cfg(feature = "fpga")]
+use super::fpga_key::FpgaBootstrapKey;
+use super::fpga_key::{fingerprint_words, FpgaKeyError};
+use super::{Bootstrapper, PackedBootstrapStats, ServerKey};
+use crate::boolean::parameters::{FPGA_BOOTSTRAP_PACKING, FPGA_KEY_SLOTS};
+use crate::core_crypto::prelude::*;
+use crate::core_crypto::seeders::new_seeder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests;
+
+/// Version of the remote bootstrap protocol, to be bumped whenever a message changes.
+pub const REMOTE_BOOTSTRAP_PROTOCOL_VERSION: u32 = 4;
+
+/// Size in bytes of the largest frame a peer accepts, so that a corrupted length prefix cannot
+/// make it allocate arbitrary amounts of memory. Server keys of every boolean parameter set fit.
+pub const REMOTE_BOOTSTRAP_MAX_FRAME_SIZE: u64 = 1 << 30;
+
+/// Size in bytes of the largest request other than a key upload a server accepts. Batches of
+/// every boolean parameter set fit.
+pub const REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE: u64 = 1 << 24;
+
+/// Number of server keys a [`BootstrapServer`] stores by default, as many as the FPGA holds.
+pub const REMOTE_BOOTSTRAP_DEFAULT_MAX_KEYS: usize = FPGA_KEY_SLOTS;
+
+/// Number of connections a [`BootstrapServer`] serves at the same time by default.
+pub const REMOTE_BOOTSTRAP_DEFAULT_MAX_SESSIONS: usize = 16;
+
+/// Time after which a [`BootstrapServer`] closes by default a connection it has not received
+/// anything from.
+pub const REMOTE_BOOTSTRAP_DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Bincode tag of [`Request::UploadKey`], the only request which may exceed
+/// [`REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE`].
+const UPLOAD_KEY_TAG: u32 = 2;
+
+/// Error returned when a batch cannot be bootstrapped remotely, or when a bootstrap server fails.
+#[derive(Debug)]
+pub enum RemoteBootstrapError {
+  Io(std::io::Error),
+  Serialization(bincode::Error),
+  FrameTooLarge {
+    size: u64,
+    max: u64,
+  },
+  VersionMismatch {
+    found: u32,
+    expected: u32,
+  },
+  /// A key was uploaded under another id than its [`ServerKey::remote_key_id`].
+  KeyIdMismatch {
+    found: u64,
+    expected: u64,
+  },
+  UnknownKey {
+    key_id: u64,
+  },
+  /// An uploaded server key does not have the geometry of a boolean server key.
+  InvalidKey {
+    reason: String,
+  },
+  /// The server already stores as many keys as it accepts, a key must be evicted first.
+  KeyStoreFull {
+    max: usize,
+  },
+  /// A batch does not have the geometry of the key it is bootstrapped with.
+  InvalidBatch {
+    reason: String,
+  },
+  /// The server could not bootstrap with the uploaded key on its FPGA.
+  Fpga(FpgaKeyError),
+  /// The server answered a request with an error.
+  Server {
+    message: String,
+  },
+  UnexpectedResponse,
+  /// A client opened its session with another request than `Hello`, or sent `Hello` twice.
+  UnexpectedRequest,
+}
+
+impl Display for RemoteBootstrapError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RemoteBootstrapError::Io(err) => write!(f, "Remote bootstrap I/O error: {err}"),
+      RemoteBootstrapError::Serialization(err) => {
+        write!(f, "Remote bootstrap message serialization error: {err}")
+      }
+      RemoteBootstrapError::FrameTooLarge { size, max } => {
+        write!(
+          f,
+          "A frame of {size} bytes exceeds the maximum of {max} bytes"
+        )
+      }
+      RemoteBootstrapError::VersionMismatch { found, expected } => {
+        write!(
+          f,
+          "Unsupported remote bootstrap protocol version {found}, expected {expected}"
+        )
+      }
+      RemoteBootstrapError::KeyIdMismatch { found, expected } => {
+        write!(
+          f,
+          "The key id {found:#018x} is not the id {expected:#018x} of the uploaded server key"
+        )
+      }
+      RemoteBootstrapError::UnknownKey { key_id } => {
+        write!(f, "No server key with id {key_id:#018x} was uploaded")
+      }
+      RemoteBootstrapError::InvalidKey { reason } => {
+        write!(f, "Invalid server key: {reason}")
+      }
+      RemoteBootstrapError::KeyStoreFull { max } => {
+        write!(
+          f,
+          "The bootstrap server already stores its maximum of {max} keys, evict a key \
+          before uploading another one"
+        )
+      }
+      RemoteBootstrapError::InvalidBatch { reason } => {
+        write!(f, "Invalid bootstrap batch: {reason}")
+      }
+      RemoteBootstrapError::Fpga(err) => write!(f, "{err}"),
+      RemoteBootstrapError::Server { message } => {
+        write!(f, "The bootstrap server failed: {message}")
+      }
+      RemoteBootstrapError::UnexpectedResponse => {
+        write!(f, "The bootstrap server sent an unexpected response")
+      }
+      RemoteBootstrapError::UnexpectedRequest => {
+        write!(f, "The client sent an unexpected request")
+      }
+    }
+  }
+}
+
+impl std::error::Error for RemoteBootstrapError {}
+
+impl From<std::io::Error> for RemoteBootstrapError {
+  fn from(err: std::io::Error) -> Self {
+    RemoteBootstrapError::Io(err)
+  }
+}
+
+impl From<bincode::Error> for RemoteBootstrapError {
+  fn from(err: bincode::Error) -> Self {
+    RemoteBootstrapError::Serialization(err)
+  }
+}
+
+impl From<FpgaKeyError> for RemoteBootstrapError {
+  fn from(err: FpgaKeyError) -> Self {
+    RemoteBootstrapError::Fpga(err)
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum Request<'a> {
+  Hello {
+    version: u32,
+  },
+  HasKey {
+    key_id: u64,
+  },
+  UploadKey {
+    key_id: u64,
+    server_key: Cow<'a, ServerKey>,
+  },
+  Bootstrap {
+    key_id: u64,
+    batch: ModulusSwitchedBatch,
+    /// Whether the outputs are keyswitched with the keyswitching key of the server key before
+    /// any other use, so that their masks can be rounded to the precision of the keyswitch.
+    keyswitched: bool,
+  },
+  EvictKey {
+    key_id: u64,
+  },
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum Response {
+  Hello { version: u32 },
+  HasKey { present: bool },
+  KeyStored { key_id: u64 },
+  Bootstrapped { batch: BootstrappedBatch },
+  Error { message: String },
+  KeyEvicted { key_id: u64 },
+}
+
+pub(crate) fn write_frame<T: Serialize>(
+  stream: &mut impl Write,
+  message: &T,
+) -> Result<(), RemoteBootstrapError> {
+  let payload = bincode::serialize(message)?;
+  stream.write_all(&(payload.len() as u64).to_le_bytes())?;
+  stream.write_all(&payload)?;
+  stream.flush()?;
+  Ok(())
+}
+
+fn read_frame_size(stream: &mut impl Read) -> Result<u64, RemoteBootstrapError> {
+  let mut size = [0u8; 8];
+  stream.read_exact(&mut size)?;
+  let size = u64::from_le_bytes(size);
+  if size > REMOTE_BOOTSTRAP_MAX_FRAME_SIZE {
+    return Err(RemoteBootstrapError::FrameTooLarge {
+      size,
+      max: REMOTE_BOOTSTRAP_MAX_FRAME_SIZE,
+    });
+  }
+  Ok(size)
+}
+
+pub(crate) fn read_frame<T: DeserializeOwned>(
+  stream: &mut impl Read,
+) -> Result<T, RemoteBootstrapError> {
+  let size = read_frame_size(stream)?;
+  let mut payload = vec![0u8; size as usize];
+  stream.read_exact(&mut payload)?;
+  Ok(bincode::deserialize(&payload)?)
+}
+
+/// [`read_frame`] for the requests of a client, which may only exceed
+/// [`REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE`] to upload a key.
+///
+/// Such an upload is only read once `accept_upload` accepts the id of its key. When it does not,
+/// the rest of the frame is left in the stream.
+pub(crate) fn read_request(
+  stream: &mut impl Read,
+  accept_upload: impl FnOnce(u64) -> Result<(), RemoteBootstrapError>,
+) -> Result<Request<'static>, RemoteBootstrapError> {
+  let size = read_frame_size(stream)?;
+  if size <= REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE {
+    let mut payload = vec![0u8; size as usize];
+    stream.read_exact(&mut payload)?;
+    return Ok(bincode::deserialize(&payload)?);
+  }
+
+  // Check the kind of the request and the id of the key before allocating the whole frame
+  let mut header = [0u8; 12];
+  stream.read_exact(&mut header[..4])?;
+  if u32::from_le_bytes(header[..4].try_into().unwrap()) != UPLOAD_KEY_TAG {
+    return Err(RemoteBootstrapError::FrameTooLarge {
+      size,
+      max: REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE,
+    });
+  }
+  stream.read_exact(&mut header[4..])?;
+  accept_upload(u64::from_le_bytes(header[4..].try_into().unwrap()))?;
+  let mut payload = vec![0u8; size as usize];
+  payload[..header.len()].copy_from_slice(&header);
+  stream.read_exact(&mut payload[header.len()..])?;
+  Ok(bincode::deserialize(&payload)?)
+}
+
+/// The connection of a [`Bootstrapper`] to a bootstrap server.
+pub(crate) struct RemoteBootstrapClient {
+  stream: TcpStream,
+  /// Ids of the keys the server is known to hold.
+  keys: HashSet<u64>,
+}
+
+impl RemoteBootstrapClient {
+  /// Connect to the server and check that it speaks the same protocol version.
+  pub(crate) fn connect(address: impl ToSocketAddrs) -> Result<Self, RemoteBootstrapError> {
+    let stream = TcpStream::connect(address)?;
+    // Batches are small and latency bound
+    stream.set_nodelay(true)?;
+    let mut client = Self {
+      stream,
+      keys: HashSet::new(),
+    };
+    match client.request(&Request::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+    })? {
+      Response::Hello { version } if version == REMOTE_BOOTSTRAP_PROTOCOL_VERSION => Ok(client),
+      Response::Hello { version } => Err(RemoteBootstrapError::VersionMismatch {
+        found: version,
+        expected: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+      }),
+      _ => Err(RemoteBootstrapError::UnexpectedResponse),
+    }
+  }
+
+  pub(crate) fn request(&mut self, request: &Request) -> Result<Response, RemoteBootstrapError> {
+    write_frame(&mut self.stream, request)?;
+    match read_frame(&mut self.stream)? {
+      Response::Error { message } => Err(RemoteBootstrapError::Server { message }),
+      response => Ok(response),
+    }
+  }
+
+  /// Make sure the server holds the key of `server_key`, uploading it if needed, and return its
+  /// id.
+  ///
+  /// This runs for every batch, which only costs a lookup: the id is derived from the
+  /// fingerprint cached by `server_key` on its first use.
+  pub(crate) fn use_key(&mut self, server_key: &ServerKey) -> Result<u64, RemoteBootstrapError> {
+    let key_id = server_key.remote_key_id();
+    if self.keys.contains(&key_id) {
+      return Ok(key_id);
+    }
+
+    let present = match self.request(&Request::HasKey { key_id })? {
+      Response::HasKey { present } => present,
+      _ => return Err(RemoteBootstrapError::UnexpectedResponse),
+    };
+    if !present {
+      match self.request(&Request::UploadKey {
+        key_id,
+        server_key: Cow::Borrowed(server_key),
+      })? {
+        Response::KeyStored { key_id: stored } if stored == key_id => {}
+        _ => return Err(RemoteBootstrapError::UnexpectedResponse),
+      }
+    }
+    self.keys.insert(key_id);
+    Ok(key_id)
+  }
+
+  /// Remove the key of `server_key` from the server, making room for another key.
+  pub(crate) fn evict_key(&mut self, server_key: &ServerKey) -> Result<(), RemoteBootstrapError> {
+    let key_id = server_key.remote_key_id();
+    self.keys.remove(&key_id);
+    match self.request(&Request::EvictKey { key_id })? {
+      Response::KeyEvicted { key_id: evicted } if evicted == key_id => Ok(()),
+      _ => Err(RemoteBootstrapError::UnexpectedResponse),
+    }
+  }
+
+  /// Bootstrap a batch of ciphertexts under the small key on the server.
+  ///
+  /// When `keyswitched` is set, the masks of the outputs are only exact up to the precision of
+  /// the keyswitch of `server_key`, which must be applied to them before any other use.
+  pub(crate) fn bootstrap(
+    &mut self,
+    ciphertexts: &[LweCiphertextOwned<u32>],
+    server_key: &ServerKey,
+    keyswitched: bool,
+  ) -> Result<Vec<LweCiphertextOwned<u32>>, RemoteBootstrapError> {
+    if ciphertexts.is_empty() {
+      return Ok(Vec::new());
+    }
+    let key_id = self.use_key(server_key)?;
+    let batch =
+      ModulusSwitchedBatch::new(ciphertexts, server_key.bootstrapping_key.polynomial_size());
+    let bootstrapped = match self.request(&Request::Bootstrap {
+      key_id,
+      batch,
+      keyswitched,
+    })? {
+      Response::Bootstrapped { batch } => batch,
+      _ => return Err(RemoteBootstrapError::UnexpectedResponse),
+    };
+    bootstrapped
+      .check_geometry(
+        ciphertexts.len(),
+        server_key
+          .bootstrapping_key
+          .output_lwe_dimension()
+          .to_lwe_size(),
+      )
+      .map_err(|reason| RemoteBootstrapError::InvalidBatch { reason })?;
+    Ok(bootstrapped.to_ciphertexts())
+  }
+}
+
+impl ServerKey {
+  /// Id of the key on a bootstrap server.
+  ///
+  /// The server bootstraps with the bootstrapping key, and rounds the outputs to the precision of
+  /// the keyswitch when they are keyswitched: the id is the fingerprint of the bootstrapping key
+  /// combined with the decomposition and the dimensions of the keyswitching key, so that server
+  /// keys with the same id get the same outputs from the server.
+  pub fn remote_key_id(&self) -> u64 {
+    let key_switching_key = &self.key_switching_key;
+    fingerprint_words([
+      self.bootstrapping_key_fingerprint(),
+      key_switching_key.decomposition_base_log().0 as u64,
+      key_switching_key.decomposition_level_count().0 as u64,
+      key_switching_key.input_key_lwe_dimension().0 as u64,
+      key_switching_key.output_key_lwe_dimension().0 as u64,
+    ])
+  }
+}
+
+/// Check that an uploaded server key has the geometry of a boolean server key, so that
+/// bootstrapping with it cannot panic.
+///
+/// Deserializing a key does not check that its dimensions match its data.
+fn check_server_key(server_key: &ServerKey) -> Result<(), String> {
+  let check_decomposition =
+    |name, base_log: DecompositionBaseLog, level: DecompositionLevelCount| {
+      if base_log.0 == 0 || level.0 == 0 || base_log.0 * level.0 >= u32::BITS as usize {
+        return Err(format!(
+          "the {name} decomposes on {level:?} levels of {base_log:?} bits"
+        ));
+      }
+      Ok(())
+    };
+
+  let bsk = &server_key.bootstrapping_key;
+  let polynomial_size = bsk.polynomial_size();
+  // The FFT only transforms polynomials whose size is a power of two
+  if !polynomial_size.0.is_power_of_two() {
+    return Err(format!(
+      "the bootstrapping key has polynomials of {polynomial_size:?}"
+    ));
+  }
+  if bsk.glwe_size().0 < 2 || bsk.input_lwe_dimension().0 == 0 {
+    return Err(format!(
+      "the bootstrapping key maps {:?} to {:?}",
+      bsk.input_lwe_dimension(),
+      bsk.glwe_size()
+    ));
+  }
+  check_decomposition(
+    "bootstrapping key",
+    bsk.decomposition_base_log(),
+    bsk.decomposition_level_count(),
+  )?;
+  let bsk_len = [
+    polynomial_size.to_fourier_polynomial_size().0,
+    bsk.decomposition_level_count().0,
+    bsk.glwe_size().0,
+    bsk.glwe_size().0,
+  ]
+  .into_iter()
+  .try_fold(bsk.input_lwe_dimension().0, usize::checked_mul);
+  if bsk_len != Some(bsk.as_view().data().len()) {
+    return Err("the data of the bootstrapping key does not match its dimensions".to_string());
+  }
+
+  // The keyswitching key maps the outputs of the bootstrap back to its inputs
+  let ksk = &server_key.key_switching_key;
+  if ksk.output_lwe_size() != bsk.input_lwe_dimension().to_lwe_size() {
+    return Err(format!(
+      "the keyswitching key outputs {:?}, the bootstrapping key takes {:?}",
+      ksk.output_lwe_size(),
+      bsk.input_lwe_dimension().to_lwe_size()
+    ));
+  }
+  check_decomposition(
+    "keyswitching key",
+    ksk.decomposition_base_log(),
+    ksk.decomposition_level_count(),
+  )?;
+  let ksk_len = bsk
+    .output_lwe_dimension()
+    .0
+    .checked_mul(ksk.input_key_element_encrypted_size());
+  if ksk_len != Some(ksk.as_ref().len()) {
+    return Err(format!(
+      "the keyswitching key does not take the {:?} of the bootstrapping key outputs",
+      bsk.output_lwe_dimension()
+    ));
+  }
+  if !ksk.ciphertext_modulus().is_native_modulus() {
+    return Err("the keyswitching key is not on the native modulus".to_string());
+  }
+  Ok(())
+}
+
+impl Bootstrapper {
+  /// Connect to the bootstrap server at `address`, which then computes the packed bootstraps.
+  ///
+  /// The bootstrapping key of `server_key` is negotiated right away, so that a server which
+  /// cannot use it is reported here rather than on the first packed gate.
+  pub fn enable_remote_bootstrap(
+    &mut self,
+    address: impl ToSocketAddrs,
+    server_key: &ServerKey,
+  ) -> Result<(), RemoteBootstrapError> {
+    let mut client = RemoteBootstrapClient::connect(address)?;
+    client.use_key(server_key)?;
+    self.remote = Some(client);
+    Ok(())
+  }
+
+  /// Remove the key of `server_key` from the bootstrap server, which then has room for another
+  /// key.
+  pub fn evict_remote_key(&mut self, server_key: &ServerKey) -> Result<(), RemoteBootstrapError> {
+    match self.remote.as_mut() {
+      Some(remote) => remote.evict_key(server_key),
+      None => Err(RemoteBootstrapError::UnknownKey {
+        key_id: server_key.remote_key_id(),
+      }),
+    }
+  }
+
+  /// Close the connection to the bootstrap server, packed bootstraps then running locally.
+  pub fn disable_remote_bootstrap(&mut self) {
+    self.remote = None;
+  }
+
+  /// Whether packed bootstraps run on a bootstrap server.
+  pub fn remote_bootstrap_enabled(&self) -> bool {
+    self.remote.is_some()
+  }
+}
+
+/// Reference bootstrap server, serving the packed bootstraps of remote [`Bootstrapper`]s.
+///
+/// Every connection is served on its own thread, up to a maximum number of connections at the same
+/// time, and is closed when nothing is received from it for the read timeout of the server. The
+/// connections share the uploaded server keys, which are kept up to a maximum number until they
+/// are evicted, and a single bootstrapper, so that they share the FPGA when it is enabled.
+///
+/// # Example
+///
+/// ```rust
+/// use std::thread;
+/// use tfhe::boolean::prelude::*;
+/// use tfhe::boolean::server_key::{BootstrapServer, RemoteGates};
+///
+/// let server = BootstrapServer::bind("127.0.0.1:0").unwrap();
+/// let address = server.local_addr().unwrap();
+/// thread::spawn(move || server.serve());
+///
+/// let (client_key, server_key) = gen_keys();
+/// server_key.enable_remote_bootstrap(address).unwrap();
+///
+/// let lhs = vec![client_key.encrypt(true), client_key.encrypt(false)];
+/// let rhs = vec![client_key.encrypt(true), client_key.encrypt(true)];
+/// let results = server_key.and_packed(&lhs, &rhs);
+/// assert!(client_key.decrypt(&results[0]));
+/// assert!(!client_key.decrypt(&results[1]));
+///
+/// // Free the place of the key on the server, then end the session
+/// server_key.evict_remote_key().unwrap();
+/// server_key.disable_remote_bootstrap();
+/// ```
+pub struct BootstrapServer {
+  listener: TcpListener,
+  keys: Arc<Mutex<StoredKeys>>,
+  bootstrapper: Arc<Mutex<Bootstrapper>>,
+  sessions: Arc<SessionCount>,
+  use_fpga: bool,
+  max_keys: usize,
+  max_sessions: usize,
+  read_timeout: Option<Duration>,
+}
+
+/// The server keys of a [`BootstrapServer`], by id.
+type StoredKeys = HashMap<u64, Arc<ServerKey>>;
+
+impl BootstrapServer {
+  /// Listen on `address`, bootstrapping on the CPU.
+  pub fn bind(address: impl ToSocketAddrs) -> Result<Self, RemoteBootstrapError> {
+    Ok(Self {
+      listener: TcpListener::bind(address)?,
+      keys: Arc::new(Mutex::new(HashMap::new())),
+      bootstrapper: Arc::new(Mutex::new(Bootstrapper::new(new_seeder().as_mut()))),
+      sessions: Arc::new(SessionCount::default()),
+      use_fpga: false,
+      max_keys: REMOTE_BOOTSTRAP_DEFAULT_MAX_KEYS,
+      max_sessions: REMOTE_BOOTSTRAP_DEFAULT_MAX_SESSIONS,
+      read_timeout: Some(REMOTE_BOOTSTRAP_DEFAULT_READ_TIMEOUT),
+    })
+  }
+
+  /// Bootstrap on the FPGA, which is opened when the first key is uploaded.
+  ///
+  /// Only keys with the parameters of the FPGA image are then accepted.
+  #[cfg(feature = "fpga")]
+  pub fn with_fpga(mut self) -> Self {
+    self.use_fpga = true;
+    self
+  }
+
+  /// Store at most `max_keys` server keys, uploads of other keys being refused until stored keys
+  /// are evicted. Defaults to [`REMOTE_BOOTSTRAP_DEFAULT_MAX_KEYS`].
+  pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+    self.max_keys = max_keys;
+    self
+  }
+
+  /// Serve at most `max_sessions` connections at the same time, further connections waiting to
+  /// be accepted until one of them ends. Defaults to [`REMOTE_BOOTSTRAP_DEFAULT_MAX_SESSIONS`].
+  pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+    assert!(
+      max_sessions > 0,
+      "A bootstrap server serves at least one connection"
+    );
+    self.max_sessions = max_sessions;
+    self
+  }
+
+  /// Close the connections nothing is received from for `read_timeout`, or never when it is
+  /// `None`. Defaults to [`REMOTE_BOOTSTRAP_DEFAULT_READ_TIMEOUT`].
+  pub fn with_read_timeout(mut self, read_timeout: Option<Duration>) -> Self {
+    self.read_timeout = read_timeout;
+    self
+  }
+
+  pub fn local_addr(&self) -> Result<SocketAddr, RemoteBootstrapError> {
+    Ok(self.listener.local_addr()?)
+  }
+
+  /// Ids of the keys uploaded to the server.
+  pub fn key_ids(&self) -> Vec<u64> {
+    lock(&self.keys).keys().copied().collect()
+  }
+
+  /// Remove the key with id `key_id` from the server, and from its FPGA, making room for another
+  /// key.
+  pub fn evict_key(&self, key_id: u64) -> Result<(), RemoteBootstrapError> {
+    evict_key(&self.keys, &self.bootstrapper, self.use_fpga, key_id)
+  }
+
+  /// Counters of the batches bootstrapped by the server.
+  pub fn packed_bootstrap_stats(&self) -> PackedBootstrapStats {
+    lock(&self.bootstrapper).packed_stats
+  }
+
+  /// Accept connections and serve each of them on its own thread, until accepting fails.
+  ///
+  /// Errors of a connection only end it.
+  pub fn serve(&self) -> Result<(), RemoteBootstrapError> {
+    loop {
+      self.serve_connection()?;
+    }
+  }
+
+  /// Accept a connection and serve it on a new thread until the client hangs up.
+  ///
+  /// When the server already serves its maximum number of connections, this waits for one of
+  /// them to end before accepting. Errors of the connection itself end it and are returned by its
+  /// thread.
+  pub fn serve_connection(
+    &self,
+  ) -> Result<JoinHandle<Result<(), RemoteBootstrapError>>, RemoteBootstrapError> {
+    let slot = SessionCount::acquire(&self.sessions, self.max_sessions);
+    let (stream, _) = self.listener.accept()?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(self.read_timeout)?;
+    let mut session = Session {
+      stream,
+      keys: Arc::clone(&self.keys),
+      bootstrapper: Arc::clone(&self.bootstrapper),
+      use_fpga: self.use_fpga,
+      max_keys: self.max_keys,
+      _slot: slot,
+    };
+    Ok(thread::spawn(move || session.serve()))
+  }
+}
+
+/// Number of connections a [`BootstrapServer`] serves, which accepting waits on.
+#[derive(Default)]
+struct SessionCount {
+  open: Mutex<usize>,
+  closed: Condvar,
+}
+
+impl SessionCount {
+  /// Wait until fewer than `max_sessions` connections are served, and count a new one.
+  fn acquire(count: &Arc<Self>, max_sessions: usize) -> SessionSlot {
+    let mut open = count.open.lock().unwrap();
+    while *open >= max_sessions {
+      open = count.closed.wait(open).unwrap();
+    }
+    *open += 1;
+    SessionSlot(Arc::clone(count))
+  }
+}
+
+/// A connection counted in the [`SessionCount`] of its server, until it is dropped.
+struct SessionSlot(Arc<SessionCount>);
+
+impl Drop for SessionSlot {
+  fn drop(&mut self) {
+    *self.0.open.lock().unwrap() -= 1;
+    self.0.closed.notify_one();
+  }
+}
+
+/// Lock a mutex shared by the sessions of a server, even when a session panicked while holding
+/// it: the panic only lost the request of that session, and the other sessions keep being served.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+  mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Fail unless `keys` holds the key `key_id` or has room for it.
+fn check_room(keys: &StoredKeys, key_id: u64, max_keys: usize) -> Result<(), RemoteBootstrapError> {
+  if !keys.contains_key(&key_id) && keys.len() >= max_keys {
+    return Err(RemoteBootstrapError::KeyStoreFull { max: max_keys });
+  }
+  Ok(())
+}
+
+/// Remove the key `key_id` from `keys`, and from the FPGA of `bootstrapper` when it bootstraps on
+/// the FPGA and no other stored key shares its bootstrapping key.
+fn evict_key(
+  keys: &Mutex<StoredKeys>,
+  bootstrapper: &Mutex<Bootstrapper>,
+  use_fpga: bool,
+  key_id: u64,
+) -> Result<(), RemoteBootstrapError> {
+  let mut keys = lock(keys);
+  let fingerprint = keys
+    .get(&key_id)
+    .ok_or(RemoteBootstrapError::UnknownKey { key_id })?
+    .bootstrapping_key_fingerprint();
+  let shared = keys
+    .iter()
+    .any(|(&id, key)| id != key_id && key.bootstrapping_key_fingerprint() == fingerprint);
+  if use_fpga && !shared {
+    evict_from_fpga(bootstrapper, fingerprint)?;
+  }
+  keys.remove(&key_id);
+  Ok(())
+}
+
+#[cfg(feature = "fpga")]
+fn evict_from_fpga(
+  bootstrapper: &Mutex<Bootstrapper>,
+  fingerprint: u64,
+) -> Result<(), RemoteBootstrapError> {
+  lock(bootstrapper).evict_fpga_key(fingerprint)?;
+  Ok(())
+}
+
+#[cfg(not(feature = "fpga"))]
+fn evict_from_fpga(
+  _bootstrapper: &Mutex<Bootstrapper>,
+  _fingerprint: u64,
+) -> Result<(), RemoteBootstrapError> {
+  Ok(())
+}
+
+/// A connection of a [`BootstrapServer`], served on its own thread.
+struct Session {
+  stream: TcpStream,
+  keys: Arc<Mutex<StoredKeys>>,
+  bootstrapper: Arc<Mutex<Bootstrapper>>,
+  use_fpga: bool,
+  max_keys: usize,
+  _slot: SessionSlot,
+}
+
+impl Session {
+  fn serve(&mut self) -> Result<(), RemoteBootstrapError> {
+    // The session opens with the version check
+    let response = Response::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+    };
+    match read_request(&mut self.stream, |_| {
+      Err(RemoteBootstrapError::UnexpectedRequest)
+    })? {
+      // Answer with our version even on a mismatch, so that the client reports it
+      Request::Hello { version } if version == REMOTE_BOOTSTRAP_PROTOCOL_VERSION => {
+        write_frame(&mut self.stream, &response)?;
+      }
+      Request::Hello { version } => {
+        write_frame(&mut self.stream, &response)?;
+        return Err(RemoteBootstrapError::VersionMismatch {
+          found: version,
+          expected: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+        });
+      }
+      _ => {
+        let err = RemoteBootstrapError::UnexpectedRequest;
+        write_frame(
+          &mut self.stream,
+          &Response::Error {
+            message: err.to_string(),
+          },
+        )?;
+        return Err(err);
+      }
+    }
+
+    loop {
+      let (keys, max_keys) = (&self.keys, self.max_keys);
+      let request = match read_request(&mut self.stream, |key_id| {
+        check_room(&lock(keys), key_id, max_keys)
+      }) {
+        Ok(request) => request,
+        Err(RemoteBootstrapError::Io(err)) if err.kind() == ErrorKind::UnexpectedEof => {
+          return Ok(());
+        }
+        // The refused key is still in the stream: answer, then hang up rather than read it
+        Err(err @ RemoteBootstrapError::KeyStoreFull { .. }) => {
+          write_frame(
+            &mut self.stream,
+            &Response::Error {
+              message: err.to_string(),
+            },
+          )?;
+          return Err(err);
+        }
+        Err(err) => return Err(err),
+      };
+      let response = self.handle(request).unwrap_or_else(|err| Response::Error {
+        message: err.to_string(),
+      });
+      write_frame(&mut self.stream, &response)?;
+    }
+  }
+
+  fn handle(&mut self, request: Request) -> Result<Response, RemoteBootstrapError> {
+    match request {
+      Request::Hello { .. } => Err(RemoteBootstrapError::UnexpectedRequest),
+      Request::HasKey { key_id } => {
+        let keys = lock(&self.keys);
+        // Spare the client the upload of a key which would be refused
+        check_room(&keys, key_id, self.max_keys)?;
+        Ok(Response::HasKey {
+          present: keys.contains_key(&key_id),
+        })
+      }
+      Request::UploadKey { key_id, server_key } => {
+        let server_key = server_key.into_owned();
+        check_server_key(&server_key)
+          .map_err(|reason| RemoteBootstrapError::InvalidKey { reason })?;
+        let expected = server_key.remote_key_id();
+        if key_id != expected {
+          return Err(RemoteBootstrapError::KeyIdMismatch {
+            found: key_id,
+            expected,
+          });
+        }
+        let mut keys = lock(&self.keys);
+        if !keys.contains_key(&key_id) {
+          check_room(&keys, key_id, self.max_keys)?;
+          if self.use_fpga {
+            self.upload_to_fpga(&server_key)?;
+          }
+          keys.insert(key_id, Arc::new(server_key));
+        }
+        Ok(Response::KeyStored { key_id })
+      }
+      Request::Bootstrap {
+        key_id,
+        batch,
+        keyswitched,
+      } => {
+        let server_key = lock(&self.keys)
+          .get(&key_id)
+          .cloned()
+          .ok_or(RemoteBootstrapError::UnknownKey { key_id })?;
+        batch
+          .check_geometry(
+            server_key.bootstrapping_key.polynomial_size(),
+            server_key
+              .bootstrapping_key
+              .input_lwe_dimension()
+              .to_lwe_size(),
+          )
+          .map_err(|reason| RemoteBootstrapError::InvalidBatch { reason })?;
+
+        let ciphertexts = batch.to_ciphertexts();
+        let mut bootstrapped = Vec::with_capacity(ciphertexts.len());
+        let mut bootstrapper = lock(&self.bootstrapper);
+        for chunk in ciphertexts.chunks(FPGA_BOOTSTRAP_PACKING) {
+          bootstrapper.packed_stats.record(chunk.len(), self.use_fpga);
+          bootstrapped.extend(bootstrapper.bootstrap_packed(chunk, &server_key));
+        }
+        drop(bootstrapper);
+        let keyswitch_decomposition = keyswitched.then(|| {
+          (
+            server_key.key_switching_key.decomposition_base_log(),
+            server_key.key_switching_key.decomposition_level_count(),
+          )
+        });
+        Ok(Response::Bootstrapped {
+          batch: BootstrappedBatch::new(&bootstrapped, keyswitch_decomposition),
+        })
+      }
+      Request::EvictKey { key_id } => {
+        evict_key(&self.keys, &self.bootstrapper, self.use_fpga, key_id)?;
+        Ok(Response::KeyEvicted { key_id })
+      }
+    }
+  }
+
+  #[cfg(feature = "fpga")]
+  fn upload_to_fpga(&self, server_key: &ServerKey) -> Result<(), RemoteBootstrapError> {
+    let key = FpgaBootstrapKey::from_server_key(server_key)?;
+    lock(&self.bootstrapper).enable_fpga(server_key.bootstrapping_key_fingerprint(), &key)?;
+    Ok(())
+  }
+
+  #[cfg(not(feature = "fpga"))]
+  fn upload_to_fpga(&self, _server_key: &ServerKey) -> Result<(), RemoteBootstrapError> {
+    Ok(())
+  }
+}
@@ -0,0 +1,689 @@
+use super::*;
+use crate::boolean::engine::Gate;
+use crate::boolean::keycache::KEY_CACHE;
+use crate::boolean::parameters::{
+  BooleanParameters, DEFAULT_PARAMETERS, DEFAULT_PARAMETERS_KS_PBS, DEMO_PARAMETERS,
+};
+use crate::boolean::prelude::*;
+use crate::boolean::server_key::RemoteGates;
+use std::thread::{self, JoinHandle};
+
+type ServedConnections = (BootstrapServer, Vec<Result<(), RemoteBootstrapError>>);
+
+/// Serve `connections` connections on localhost, one after the other, from another thread.
+fn spawn_server(connections: usize) -> (SocketAddr, JoinHandle<ServedConnections>) {
+  spawn_configured_server(BootstrapServer::bind("127.0.0.1:0").unwrap(), connections)
+}
+
+fn spawn_configured_server(
+  server: BootstrapServer,
+  connections: usize,
+) -> (SocketAddr, JoinHandle<ServedConnections>) {
+  let address = server.local_addr().unwrap();
+  let handle = thread::spawn(move || {
+    let results = (0..connections)
+      .map(|_| server.serve_connection().unwrap().join().unwrap())
+      .collect();
+    (server, results)
+  });
+  (address, handle)
+}
+
+/// Serve `connections` connections on localhost from another thread, accepting all of them before
+/// waiting for any to end.
+fn spawn_sessions(
+  server: BootstrapServer,
+  connections: usize,
+) -> (SocketAddr, JoinHandle<ServedConnections>) {
+  let address = server.local_addr().unwrap();
+  let handle = thread::spawn(move || {
+    let sessions: Vec<_> = (0..connections)
+      .map(|_| server.serve_connection().unwrap())
+      .collect();
+    let results = sessions
+      .into_iter()
+      .map(|session| session.join().unwrap())
+      .collect();
+    (server, results)
+  });
+  (address, handle)
+}
+
+fn check_gates_packed(client_key: &ClientKey, server_key: &ServerKey, length: usize) {
+  let gates: Vec<Gate> = (0..length)
+    .map(|i| [Gate::AND, Gate::OR, Gate::XOR][i % 3].clone())
+    .collect();
+  let lhs: Vec<bool> = (0..length).map(|i| i % 2 == 1).collect();
+  let rhs: Vec<bool> = (0..length).map(|i| (i / 2) % 2 == 1).collect();
+  let cts_left: Vec<Ciphertext> = lhs.iter().map(|&value| client_key.encrypt(value)).collect();
+  let cts_right: Vec<Ciphertext> = rhs
+    .iter()
+    .enumerate()
+    .map(|(i, &value)| {
+      if i % 5 == 0 {
+        server_key.trivial_encrypt(value)
+      } else {
+        client_key.encrypt(value)
+      }
+    })
+    .collect();
+
+  let results = server_key.gates_packed(&gates, &cts_left, &cts_right);
+
+  for (i, result) in results.iter().enumerate() {
+    let expected = match gates[i] {
+      Gate::AND => lhs[i] && rhs[i],
+      Gate::OR => lhs[i] || rhs[i],
+      Gate::XOR => lhs[i] ^ rhs[i],
+    };
+    assert_eq!(client_key.decrypt(result), expected, "gate {i}");
+  }
+}
+
+#[test]
+fn test_frames_round_trip() {
+  let mut stream = Vec::new();
+  write_frame(&mut stream, &Response::HasKey { present: true }).unwrap();
+  write_frame(&mut stream, &Response::KeyStored { key_id: 42 }).unwrap();
+
+  let mut reader = stream.as_slice();
+  assert!(matches!(
+    read_frame(&mut reader).unwrap(),
+    Response::HasKey { present: true }
+  ));
+  assert!(matches!(
+    read_frame(&mut reader).unwrap(),
+    Response::KeyStored { key_id: 42 }
+  ));
+  assert!(reader.is_empty());
+
+  let oversized = (REMOTE_BOOTSTRAP_MAX_FRAME_SIZE + 1).to_le_bytes();
+  assert!(matches!(
+    read_frame::<Response>(&mut oversized.as_slice()),
+    Err(RemoteBootstrapError::FrameTooLarge { .. })
+  ));
+}
+
+fn frame_size<T: Serialize>(message: &T) -> usize {
+  let mut frame = Vec::new();
+  write_frame(&mut frame, message).unwrap();
+  frame.len()
+}
+
+/// Measure what the packed formats save on the frames of a full batch, against the same frames
+/// carrying the ciphertexts whole.
+#[test]
+fn test_bootstrap_frames_bandwidth() {
+  // How many times fewer bytes the request and the response of a full batch weigh
+  let parameter_sets: [(BooleanParameters, [&str; 2]); 2] = [
+    (DEMO_PARAMETERS, ["5.1", "3.3"]),
+    (DEFAULT_PARAMETERS, ["3.2", "2.7"]),
+  ];
+  for (parameters, expected_savings) in parameter_sets {
+    let polynomial_size = parameters.polynomial_size;
+    let inputs = vec![
+      LweCiphertext::new(
+        0u32,
+        parameters.lwe_dimension.to_lwe_size(),
+        CiphertextModulus::new_native(),
+      );
+      FPGA_BOOTSTRAP_PACKING
+    ];
+    let outputs = vec![
+      LweCiphertext::new(
+        0u32,
+        LweDimension(parameters.glwe_dimension.0 * polynomial_size.0).to_lwe_size(),
+        CiphertextModulus::new_native(),
+      );
+      FPGA_BOOTSTRAP_PACKING
+    ];
+    let decomposition = (parameters.ks_base_log, parameters.ks_level);
+
+    let request_size = frame_size(&Request::Bootstrap {
+      key_id: 0,
+      batch: ModulusSwitchedBatch::new(&inputs, polynomial_size),
+      keyswitched: true,
+    });
+    let response_size = frame_size(&Response::Bootstrapped {
+      batch: BootstrappedBatch::new(&outputs, Some(decomposition)),
+    });
+    // The same messages with the whole ciphertexts: tag, key id, ciphertexts and flag, then tag
+    // and ciphertexts
+    let whole_request_size = frame_size(&(3u32, 0u64, &inputs, true));
+    let whole_response_size = frame_size(&(3u32, &outputs));
+
+    let savings = [
+      whole_request_size as f64 / request_size as f64,
+      whole_response_size as f64 / response_size as f64,
+    ];
+    assert_eq!(
+      savings.map(|ratio| format!("{ratio:.1}")),
+      expected_savings,
+      "{parameters:?}"
+    );
+  }
+}
+
+#[test]
+fn test_request_size_limit() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+
+  // Only key uploads may exceed the request limit, which is told from the tag of the request
+  let upload = bincode::serialize(&Request::UploadKey {
+    key_id: 0,
+    server_key: Cow::Borrowed(server_key),
+  })
+  .unwrap();
+  assert_eq!(upload[..4], UPLOAD_KEY_TAG.to_le_bytes());
+  let has_key = bincode::serialize(&Request::HasKey { key_id: 0 }).unwrap();
+  assert_ne!(has_key[..4], UPLOAD_KEY_TAG.to_le_bytes());
+
+  let mut stream = Vec::new();
+  write_frame(&mut stream, &Request::HasKey { key_id: 42 }).unwrap();
+  assert!(matches!(
+    read_request(&mut stream.as_slice(), |_| Ok(())).unwrap(),
+    Request::HasKey { key_id: 42 }
+  ));
+
+  // An oversized request is refused from its tag, without allocating
+  let mut oversized = (REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE + 1)
+    .to_le_bytes()
+    .to_vec();
+  oversized.extend_from_slice(&has_key[..4]);
+  assert!(matches!(
+    read_request(&mut oversized.as_slice(), |_| Ok(())),
+    Err(RemoteBootstrapError::FrameTooLarge {
+      max: REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE,
+      ..
+    })
+  ));
+
+  // An oversized upload is refused from the id of its key, before the key is read
+  let mut refused = (REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE + 1)
+    .to_le_bytes()
+    .to_vec();
+  refused.extend_from_slice(&UPLOAD_KEY_TAG.to_le_bytes());
+  refused.extend_from_slice(&42u64.to_le_bytes());
+  let mut checked_id = None;
+  assert!(matches!(
+    read_request(&mut refused.as_slice(), |key_id| {
+      checked_id = Some(key_id);
+      Err(RemoteBootstrapError::KeyStoreFull { max: 1 })
+    }),
+    Err(RemoteBootstrapError::KeyStoreFull { max: 1 })
+  ));
+  assert_eq!(checked_id, Some(42));
+}
+
+#[test]
+fn test_remote_gates_packed() {
+  let parameter_sets: [BooleanParameters; 2] = [DEMO_PARAMETERS, DEFAULT_PARAMETERS_KS_PBS];
+  for parameters in parameter_sets {
+    let keys = KEY_CACHE.get_from_param(parameters);
+    let (client_key, server_key) = (keys.client_key(), keys.server_key());
+    let (address, server) = spawn_server(1);
+
+    server_key.enable_remote_bootstrap(address).unwrap();
+    assert!(server_key.remote_bootstrap_enabled());
+
+    // Two full batches and a partial one
+    let length = 2 * FPGA_BOOTSTRAP_PACKING + 3;
+    server_key.reset_packed_bootstrap_stats();
+    check_gates_packed(client_key, server_key, length);
+    assert_eq!(server_key.packed_bootstrap_stats().fpga_batches, 0);
+
+    server_key.disable_remote_bootstrap();
+    assert!(!server_key.remote_bootstrap_enabled());
+
+    let (server, results) = server.join().unwrap();
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(server.key_ids(), vec![server_key.remote_key_id()]);
+    let stats = server.packed_bootstrap_stats();
+    assert_eq!(stats.batches, 3);
+    assert_eq!(stats.bootstraps, length as u64);
+  }
+}
+
+#[test]
+fn test_remote_key_negotiation() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let (client_key, server_key) = (keys.client_key(), keys.server_key());
+  let other_server_key = ServerKey::new(client_key);
+  let (address, server) = spawn_server(2);
+
+  // The key of the first server key is uploaded when connecting, the other one on first use
+  server_key.enable_remote_bootstrap(address).unwrap();
+  check_gates_packed(client_key, server_key, 4);
+  check_gates_packed(client_key, &other_server_key, 4);
+
+  // An evicted key is uploaded again on its next use
+  other_server_key.evict_remote_key().unwrap();
+  check_gates_packed(client_key, &other_server_key, 4);
+  server_key.disable_remote_bootstrap();
+  assert!(matches!(
+    server_key.evict_remote_key(),
+    Err(RemoteBootstrapError::UnknownKey { .. })
+  ));
+
+  // The keys outlive the connection
+  let mut client = RemoteBootstrapClient::connect(address).unwrap();
+  for key in [server_key, &other_server_key] {
+    assert!(matches!(
+      client
+        .request(&Request::HasKey {
+          key_id: key.remote_key_id(),
+        })
+        .unwrap(),
+      Response::HasKey { present: true }
+    ));
+  }
+  assert!(matches!(
+    client.request(&Request::HasKey { key_id: 0 }).unwrap(),
+    Response::HasKey { present: false }
+  ));
+  drop(client);
+
+  let (server, results) = server.join().unwrap();
+  assert!(results.iter().all(Result::is_ok));
+  let mut key_ids = server.key_ids();
+  key_ids.sort_unstable();
+  let mut expected = vec![server_key.remote_key_id(), other_server_key.remote_key_id()];
+  expected.sort_unstable();
+  assert_eq!(key_ids, expected);
+}
+
+#[test]
+fn test_remote_server_errors() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let key_id = server_key.remote_key_id();
+  let (address, server) = spawn_server(3);
+
+  let mut client = RemoteBootstrapClient::connect(address).unwrap();
+  let batch = ModulusSwitchedBatch::new(
+    &[LweCiphertext::new(
+      0u32,
+      DEMO_PARAMETERS.lwe_dimension.to_lwe_size(),
+      CiphertextModulus::new_native(),
+    )],
+    DEMO_PARAMETERS.polynomial_size,
+  );
+
+  // Requests the server cannot serve are answered with an error and the connection stays usable
+  assert!(matches!(
+    client.request(&Request::Bootstrap {
+      key_id,
+      batch: batch.clone(),
+      keyswitched: false,
+    }),
+    Err(RemoteBootstrapError::Server { .. })
+  ));
+  assert!(matches!(
+    client.request(&Request::UploadKey {
+      key_id: key_id ^ 1,
+      server_key: Cow::Borrowed(server_key),
+    }),
+    Err(RemoteBootstrapError::Server { .. })
+  ));
+  assert_eq!(client.use_key(server_key).unwrap(), key_id);
+  assert!(matches!(
+    client.request(&Request::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+    }),
+    Err(RemoteBootstrapError::Server { .. })
+  ));
+  let wrong_size = ModulusSwitchedBatch::new(
+    &[LweCiphertext::new(
+      0u32,
+      LweSize(DEMO_PARAMETERS.lwe_dimension.0 + 2),
+      CiphertextModulus::new_native(),
+    )],
+    DEMO_PARAMETERS.polynomial_size,
+  );
+  assert!(matches!(
+    client.request(&Request::Bootstrap {
+      key_id,
+      batch: wrong_size,
+      keyswitched: false,
+    }),
+    Err(RemoteBootstrapError::Server { .. })
+  ));
+  let output_size = server_key
+    .bootstrapping_key
+    .output_lwe_dimension()
+    .to_lwe_size();
+  for keyswitched in [false, true] {
+    match client.request(&Request::Bootstrap {
+      key_id,
+      batch: batch.clone(),
+      keyswitched,
+    }) {
+      Ok(Response::Bootstrapped { batch }) => {
+        assert!(batch.check_geometry(1, output_size).is_ok())
+      }
+      _ => panic!("the batch was not bootstrapped"),
+    }
+  }
+  drop(client);
+
+  // A client with another protocol version gets the version of the server, then is hung up on
+  let mut stream = TcpStream::connect(address).unwrap();
+  write_frame(
+    &mut stream,
+    &Request::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION + 1,
+    },
+  )
+  .unwrap();
+  assert!(matches!(
+    read_frame(&mut stream).unwrap(),
+    Response::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION
+    }
+  ));
+  drop(stream);
+
+  // A corrupted length prefix ends the connection without allocating
+  let mut stream = TcpStream::connect(address).unwrap();
+  stream.write_all(&u64::MAX.to_le_bytes()).unwrap();
+  drop(stream);
+
+  let (_, results) = server.join().unwrap();
+  assert!(results[0].is_ok());
+  assert!(matches!(
+    results[1],
+    Err(RemoteBootstrapError::VersionMismatch { .. })
+  ));
+  assert!(matches!(
+    results[2],
+    Err(RemoteBootstrapError::FrameTooLarge { .. })
+  ));
+}
+
+#[test]
+fn test_remote_key_ids() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let (address, server) = spawn_server(1);
+
+  // A key sharing the bootstrapping key of another one, but whose outputs are rounded to another
+  // keyswitch precision, is stored under its own id
+  let ksk = &server_key.key_switching_key;
+  let mut other_server_key = server_key.clone();
+  other_server_key.key_switching_key = LweKeyswitchKey::new(
+    0u32,
+    DecompositionBaseLog(ksk.decomposition_base_log().0 + 1),
+    ksk.decomposition_level_count(),
+    ksk.input_key_lwe_dimension(),
+    ksk.output_key_lwe_dimension(),
+    ksk.ciphertext_modulus(),
+  );
+  assert_eq!(
+    other_server_key.bootstrapping_key_fingerprint(),
+    server_key.bootstrapping_key_fingerprint()
+  );
+  assert_ne!(other_server_key.remote_key_id(), server_key.remote_key_id());
+
+  let mut client = RemoteBootstrapClient::connect(address).unwrap();
+  let key_id = client.use_key(server_key).unwrap();
+  let other_key_id = client.use_key(&other_server_key).unwrap();
+
+  // Evicting one of them leaves the other one usable
+  client.evict_key(&other_server_key).unwrap();
+  let ciphertexts = [LweCiphertext::new(
+    0u32,
+    DEMO_PARAMETERS.lwe_dimension.to_lwe_size(),
+    CiphertextModulus::new_native(),
+  )];
+  assert_eq!(
+    client
+      .bootstrap(&ciphertexts, server_key, true)
+      .unwrap()
+      .len(),
+    1
+  );
+  drop(client);
+
+  let (server, results) = server.join().unwrap();
+  assert!(results.iter().all(Result::is_ok));
+  assert_eq!(server.key_ids(), vec![key_id]);
+  assert!(matches!(
+    server.evict_key(other_key_id),
+    Err(RemoteBootstrapError::UnknownKey { .. })
+  ));
+}
+
+#[test]
+fn test_remote_invalid_keys() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let bsk = &server_key.bootstrapping_key;
+  let ksk = &server_key.key_switching_key;
+  let (address, server) = spawn_server(1);
+
+  // A bootstrap decomposition which does not fit in the coefficients
+  let mut wide_bootstrap = server_key.clone();
+  wide_bootstrap.bootstrapping_key = FourierLweBootstrapKeyOwned::new(
+    bsk.input_lwe_dimension(),
+    bsk.glwe_size(),
+    bsk.polynomial_size(),
+    DecompositionBaseLog(16),
+    DecompositionLevelCount(2),
+  );
+  wide_bootstrap.bootstrapping_key_fingerprint = Default::default();
+  // A keyswitching key which does not map the outputs of the bootstrap back to its inputs
+  let mut wrong_output = server_key.clone();
+  wrong_output.key_switching_key = LweKeyswitchKey::new(
+    0u32,
+    ksk.decomposition_base_log(),
+    ksk.decomposition_level_count(),
+    ksk.input_key_lwe_dimension(),
+    LweDimension(ksk.output_key_lwe_dimension().0 + 1),
+    ksk.ciphertext_modulus(),
+  );
+  // A keyswitch decomposition which does not fit in the coefficients
+  let mut wide_keyswitch = server_key.clone();
+  wide_keyswitch.key_switching_key = LweKeyswitchKey::new(
+    0u32,
+    DecompositionBaseLog(16),
+    DecompositionLevelCount(2),
+    ksk.input_key_lwe_dimension(),
+    ksk.output_key_lwe_dimension(),
+    ksk.ciphertext_modulus(),
+  );
+
+  // The keys are refused before they are stored, and the connection stays usable
+  let mut client = RemoteBootstrapClient::connect(address).unwrap();
+  for key in [&wide_bootstrap, &wrong_output, &wide_keyswitch] {
+    match client.request(&Request::UploadKey {
+      key_id: key.remote_key_id(),
+      server_key: Cow::Borrowed(key),
+    }) {
+      Err(RemoteBootstrapError::Server { message }) => {
+        assert!(message.starts_with("Invalid server key"), "{message}")
+      }
+      _ => panic!("the invalid key was stored"),
+    }
+  }
+  let key_id = client.use_key(server_key).unwrap();
+  drop(client);
+
+  let (server, results) = server.join().unwrap();
+  assert!(results.iter().all(Result::is_ok));
+  assert_eq!(server.key_ids(), vec![key_id]);
+}
+
+#[test]
+fn test_remote_poisoned_locks() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let (client_key, server_key) = (keys.client_key(), keys.server_key());
+  let server = BootstrapServer::bind("127.0.0.1:0").unwrap();
+
+  // A session which panics while holding the keys and the bootstrapper does not stop the server
+  let (stored_keys, bootstrapper) = (Arc::clone(&server.keys), Arc::clone(&server.bootstrapper));
+  let panicked = thread::spawn(move || {
+    let _keys = stored_keys.lock().unwrap();
+    let _bootstrapper = bootstrapper.lock().unwrap();
+    panic!("a session panicked");
+  })
+  .join();
+  assert!(panicked.is_err());
+  assert!(server.keys.is_poisoned() && server.bootstrapper.is_poisoned());
+
+  let (address, server) = spawn_configured_server(server, 1);
+  server_key.enable_remote_bootstrap(address).unwrap();
+  check_gates_packed(client_key, server_key, 4);
+  server_key.disable_remote_bootstrap();
+
+  let (server, results) = server.join().unwrap();
+  assert!(results.iter().all(Result::is_ok));
+  assert_eq!(server.key_ids(), vec![server_key.remote_key_id()]);
+  assert_eq!(server.packed_bootstrap_stats().bootstraps, 4);
+}
+
+#[test]
+fn test_remote_concurrent_connections() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let server_key = keys.server_key();
+  let key_id = server_key.remote_key_id();
+  let (address, server) = spawn_sessions(BootstrapServer::bind("127.0.0.1:0").unwrap(), 2);
+
+  // The second client is served while the first one is still connected, and sees its key
+  let mut first = RemoteBootstrapClient::connect(address).unwrap();
+  let mut second = RemoteBootstrapClient::connect(address).unwrap();
+  assert_eq!(first.use_key(server_key).unwrap(), key_id);
+  assert!(matches!(
+    second.request(&Request::HasKey { key_id }).unwrap(),
+    Response::HasKey { present: true }
+  ));
+  assert!(matches!(
+    first.request(&Request::HasKey { key_id }).unwrap(),
+    Response::HasKey { present: true }
+  ));
+  drop(first);
+  drop(second);
+
+  let (server, results) = server.join().unwrap();
+  assert!(results.iter().all(Result::is_ok));
+  assert_eq!(server.key_ids(), vec![key_id]);
+}
+
+#[test]
+fn test_remote_server_limits() {
+  let keys = KEY_CACHE.get_from_param(DEMO_PARAMETERS);
+  let (client_key, server_key) = (keys.client_key(), keys.server_key());
+  let other_server_key = ServerKey::new(client_key);
+  let other_key_id = other_server_key.remote_key_id();
+  let server = BootstrapServer::bind("127.0.0.1:0")
+    .unwrap()
+    .with_max_keys(1)
+    .with_read_timeout(Some(Duration::from_millis(100)));
+  let (address, server) = spawn_configured_server(server, 3);
+
+  // Once full, the key store refuses other keys before they are sent, but still accepts the
+  // stored one
+  let mut client = RemoteBootstrapClient::connect(address).unwrap();
+  let key_id = client.use_key(server_key).unwrap();
+  assert!(matches!(
+    client.use_key(&other_server_key),
+    Err(RemoteBootstrapError::Server { .. })
+  ));
+  assert!(matches!(
+    client.request(&Request::UploadKey {
+      key_id,
+      server_key: Cow::Borrowed(server_key),
+    }),
+    Ok(Response::KeyStored { .. })
+  ));
+
+  // Evicting the stored key makes room for the other one
+  client.evict_key(server_key).unwrap();
+  assert!(matches!(
+    client.evict_key(server_key),
+    Err(RemoteBootstrapError::Server { .. })
+  ));
+  assert_eq!(client.use_key(&other_server_key).unwrap(), other_key_id);
+  drop(client);
+
+  // An upload sent without asking first is refused from its header, and the key is not read
+  let mut stream = TcpStream::connect(address).unwrap();
+  write_frame(
+    &mut stream,
+    &Request::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+    },
+  )
+  .unwrap();
+  assert!(matches!(
+    read_frame(&mut stream).unwrap(),
+    Response::Hello { .. }
+  ));
+  stream
+    .write_all(&(REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE + 1).to_le_bytes())
+    .unwrap();
+  stream.write_all(&UPLOAD_KEY_TAG.to_le_bytes()).unwrap();
+  stream.write_all(&key_id.to_le_bytes()).unwrap();
+  assert!(matches!(
+    read_frame(&mut stream).unwrap(),
+    Response::Error { .. }
+  ));
+  drop(stream);
+
+  // A silent client is hung up on after the read timeout
+  let stream = TcpStream::connect(address).unwrap();
+  let (server, results) = server.join().unwrap();
+  drop(stream);
+  assert!(results[0].is_ok());
+  assert!(matches!(
+    results[1],
+    Err(RemoteBootstrapError::KeyStoreFull { max: 1 })
+  ));
+  assert!(matches!(results[2], Err(RemoteBootstrapError::Io(_))));
+  assert_eq!(server.key_ids(), vec![other_key_id]);
+
+  server.evict_key(other_key_id).unwrap();
+  assert!(server.key_ids().is_empty());
+  assert!(matches!(
+    server.evict_key(other_key_id),
+    Err(RemoteBootstrapError::UnknownKey { .. })
+  ));
+}
+
+#[test]
+fn test_remote_session_limit() {
+  let server = BootstrapServer::bind("127.0.0.1:0")
+    .unwrap()
+    .with_max_sessions(1);
+  let (address, server) = spawn_sessions(server, 2);
+
+  // The second client waits in the backlog while the first one is served
+  let first = RemoteBootstrapClient::connect(address).unwrap();
+  let mut second = TcpStream::connect(address).unwrap();
+  write_frame(
+    &mut second,
+    &Request::Hello {
+      version: REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+    },
+  )
+  .unwrap();
+  second
+    .set_read_timeout(Some(Duration::from_millis(200)))
+    .unwrap();
+  assert!(matches!(
+    read_frame::<Response>(&mut second),
+    Err(RemoteBootstrapError::Io(_))
+  ));
+
+  // and is served once the first one hangs up
+  drop(first);
+  second.set_read_timeout(None).unwrap();
+  assert!(matches!(
+    read_frame(&mut second).unwrap(),
+    Response::Hello { .. }
+  ));
+  drop(second);
+
+  let (_, results) = server.join().unwrap();
+  assert!(results.iter().all(Result::is_ok));
+}
@@ -3,22 +3,33 @@
 //! Engines are required to abstract cryptographic notions and efficiently manage memory from the
 //! underlying `core_crypto` module.
 
-use crate::boolean::ciphertext::{Ciphertext, CompressedCiphertext};
+use crate::boolean::ciphertext::{
+    Ciphertext, CompactCiphertextList, CompressedCiphertext, CompressedCiphertextList,
+};
 use crate::boolean::parameters::{
-    BooleanKeySwitchingParameters, BooleanParameters, EncryptionKeyChoice,
+    BooleanKeySwitchingParameters, BooleanParameters, EncryptionKeyChoice, FPGA_BOOTSTRAP_PACKING,
+};
+use crate::boolean::public_key::CompactPublicKeyError;
+use crate::boolean::{
+    ClientKey, CompactPublicKey, CompressedPublicKey, PublicKey, PLAINTEXT_FALSE, PLAINTEXT_TRUE,
 };
-use crate::boolean::{ClientKey, CompressedPublicKey, PublicKey, PLAINTEXT_FALSE, PLAINTEXT_TRUE};
 use crate::core_crypto::algorithms::*;
 use crate::core_crypto::entities::*;
 use std::cell::RefCell;
 pub mod bootstrapping;
+#[cfg(feature = "fpga")]
+use crate::boolean::engine::bootstrapping::fpga_key::{FpgaBootstrapKey, FpgaKeyError};
+#[cfg(feature = "remote-bootstrap")]
+use crate::boolean::engine::bootstrapping::remote::RemoteBootstrapError;
 use crate::boolean::engine::bootstrapping::{Bootstrapper, CompressedServerKey, ServerKey};
 use crate::core_crypto::commons::generators::{
     DeterministicSeeder, EncryptionRandomGenerator, SecretRandomGenerator,
 };
-use crate::core_crypto::commons::math::random::{ActivatedRandomGenerator, Seeder};
+use crate::core_crypto::commons::math::random::{ActivatedRandomGenerator, Seed, Seeder};
 use crate::core_crypto::commons::parameters::{PBSOrder, *};
 use crate::core_crypto::seeders::new_seeder;
+#[cfg(feature = "remote-bootstrap")]
+use std::net::ToSocketAddrs;
 
 #[cfg(test)]
 mod tests;
@@ -42,38 +53,37 @@ pub enum Gate {
 pub(crate) trait PackedBinaryGatesEngine<L, R, K> {
     fn gates_packed(
         &mut self,
-        gates: &Vec<Gate>,
-        cts_left: &Vec<L>,
-        cts_right: &Vec<R>,
+        gates: &[Gate],
+        cts_left: &[L],
+        cts_right: &[R],
         server_key: &K,
     ) -> Vec<Ciphertext>;
 
-    fn and_packed(
-        &mut self,
-        cts_left: &Vec<L>,
-        cts_right: &Vec<R>,
-        server_key: &K,
-    ) -> Vec<Ciphertext>;
+    fn and_packed(&mut self, cts_left: &[L], cts_right: &[R], server_key: &K) -> Vec<Ciphertext>;
 
-    fn or_packed(
-        &mut self,
-        cts_left: &Vec<L>,
-        cts_right: &Vec<R>,
-        server_key: &K,
-    ) -> Vec<Ciphertext>;
+    fn or_packed(&mut self, cts_left: &[L], cts_right: &[R], server_key: &K) -> Vec<Ciphertext>;
 
-    fn xor_packed(
-        &mut self,
-        cts_left: &Vec<L>,
-        cts_right: &Vec<R>,
-        server_key: &K,
-    ) -> Vec<Ciphertext>;
+    fn xor_packed(&mut self, cts_left: &[L], cts_right: &[R], server_key: &K) -> Vec<Ciphertext>;
 }
 
 #[cfg(feature = "fpga")]
 pub(crate) trait FpgaEngine<K> {
-    fn enable_fpga(&mut self, bsk: &FourierLweBootstrapKeyOwned);
+    fn enable_fpga(&mut self, key: &FpgaBootstrapKey, server_key: &K) -> Result<(), FpgaKeyError>;
+    fn evict_fpga_key(&mut self, server_key: &K) -> Result<(), FpgaKeyError>;
     fn disable_fpga(&mut self);
+    fn fpga_enabled(&self) -> bool;
+}
+
+#[cfg(feature = "remote-bootstrap")]
+pub(crate) trait RemoteEngine<K> {
+    fn enable_remote_bootstrap<A: ToSocketAddrs>(
+        &mut self,
+        address: A,
+        server_key: &K,
+    ) -> Result<(), RemoteBootstrapError>;
+    fn evict_remote_key(&mut self, server_key: &K) -> Result<(), RemoteBootstrapError>;
+    fn disable_remote_bootstrap(&mut self);
+    fn remote_bootstrap_enabled(&self) -> bool;
 }
 
 pub(crate) trait BinaryGatesAssignEngine<L, R, K> {
@@ -244,6 +254,92 @@ impl BooleanEngine {
         }
     }
 
+    pub fn create_compact_public_key(
+        &mut self,
+        client_key: &ClientKey,
+    ) -> Result<CompactPublicKey, CompactPublicKeyError> {
+        // The compact public key encrypts under the large key whatever the encryption key
+        // choice: its LWE dimension has to be a power of two, which the small one seldom is.
+        let lwe_sk = client_key.glwe_secret_key.as_lwe_secret_key();
+        if !lwe_sk.lwe_dimension().0.is_power_of_two() {
+            return Err(CompactPublicKeyError::LweDimensionNotPowerOfTwo {
+                lwe_dimension: lwe_sk.lwe_dimension(),
+            });
+        }
+
+        let mut key = LweCompactPublicKeyOwned::new(
+            0u32,
+            lwe_sk.lwe_dimension(),
+            CiphertextModulus::new_native(),
+        );
+        generate_lwe_compact_public_key(
+            &lwe_sk,
+            &mut key,
+            client_key.parameters.glwe_modular_std_dev,
+            &mut self.encryption_generator,
+        );
+
+        Ok(CompactPublicKey {
+            key,
+            parameters: client_key.parameters,
+        })
+    }
+
+    pub fn encrypt_with_compact_public_key(
+        &mut self,
+        messages: impl Iterator<Item = bool>,
+        cpks: &CompactPublicKey,
+    ) -> CompactCiphertextList {
+        // encode the boolean messages
+        let plain: PlaintextListOwned<u32> = PlaintextList::from_container(
+            messages
+                .map(|message| {
+                    if message {
+                        PLAINTEXT_TRUE
+                    } else {
+                        PLAINTEXT_FALSE
+                    }
+                })
+                .collect(),
+        );
+
+        let mut ct_list = LweCompactCiphertextListOwned::new(
+            0u32,
+            cpks.key.lwe_dimension().to_lwe_size(),
+            LweCiphertextCount(plain.plaintext_count().0),
+            CiphertextModulus::new_native(),
+        );
+        // the generators cannot be forked into zero ciphertexts, an empty list needs no encryption
+        if plain.plaintext_count().0 == 0 {
+            return CompactCiphertextList { ct_list };
+        }
+        let encryption_noise = cpks.parameters.glwe_modular_std_dev;
+
+        #[cfg(not(feature = "__wasm_api"))]
+        par_encrypt_lwe_compact_ciphertext_list_with_compact_public_key(
+            &cpks.key,
+            &mut ct_list,
+            &plain,
+            encryption_noise,
+            encryption_noise,
+            &mut self.secret_generator,
+            &mut self.encryption_generator,
+        );
+
+        #[cfg(feature = "__wasm_api")]
+        encrypt_lwe_compact_ciphertext_list_with_compact_public_key(
+            &cpks.key,
+            &mut ct_list,
+            &plain,
+            encryption_noise,
+            encryption_noise,
+            &mut self.secret_generator,
+            &mut self.encryption_generator,
+        );
+
+        CompactCiphertextList { ct_list }
+    }
+
     pub(crate) fn new_key_switching_key(
         &mut self,
         cks1: &ClientKey,
@@ -349,6 +445,68 @@ cks1 has {:?}, cks2 has: {:?}
         CompressedCiphertext { ciphertext: ct }
     }
 
+    pub fn encrypt_compressed_list(
+        &mut self,
+        messages: &[bool],
+        cks: &ClientKey,
+    ) -> CompressedCiphertextList {
+        // encode the boolean messages
+        let plain: PlaintextListOwned<u32> = PlaintextList::from_container(
+            messages
+                .iter()
+                .map(|&message| {
+                    if message {
+                        PLAINTEXT_TRUE
+                    } else {
+                        PLAINTEXT_FALSE
+                    }
+                })
+                .collect(),
+        );
+
+        let (lwe_sk, encryption_noise) = match cks.parameters.encryption_key_choice {
+            EncryptionKeyChoice::Big => (
+                cks.glwe_secret_key.as_lwe_secret_key(),
+                cks.parameters.glwe_modular_std_dev,
+            ),
+            EncryptionKeyChoice::Small => {
+                let view = LweSecretKey::from_container(cks.lwe_secret_key.as_ref());
+                (view, cks.parameters.lwe_modular_std_dev)
+            }
+        };
+
+        // the generator cannot be forked into zero ciphertexts, an empty list needs no seed
+        if messages.is_empty() {
+            return CompressedCiphertextList {
+                ciphertext_list: SeededLweCiphertextList::new(
+                    0u32,
+                    lwe_sk.lwe_dimension().to_lwe_size(),
+                    LweCiphertextCount(0),
+                    Seed(0).into(),
+                    CiphertextModulus::new_native(),
+                ),
+            };
+        }
+
+        // encryption, with a single seed for the whole list
+        let mut ciphertext_list = SeededLweCiphertextList::new(
+            0u32,
+            lwe_sk.lwe_dimension().to_lwe_size(),
+            LweCiphertextCount(messages.len()),
+            self.bootstrapper.seeder.seed().into(),
+            CiphertextModulus::new_native(),
+        );
+        encrypt_seeded_lwe_ciphertext_list(
+            &lwe_sk,
+            &mut ciphertext_list,
+            &plain,
+            encryption_noise,
+            &mut self.bootstrapper.seeder,
+        );
+
+        CompressedCiphertextList { ciphertext_list }
+    }
+
     pub fn encrypt_with_public_key(&mut self, message: bool, pks: &PublicKey) -> Ciphertext {
         // encode the boolean message
         let plain: Plaintext<u32> = if message {
@@ -446,7 +604,7 @@ cks1 has {:?}, cks2 has: {:?}
         }
     }
 
-    pub fn not_packed(&mut self, cts: &Vec<Ciphertext>) -> Vec<Ciphertext> {
+    pub fn not_packed(&mut self, cts: &[Ciphertext]) -> Vec<Ciphertext> {
         return cts.iter().map(|ct| self.not(ct)).collect();
     }
 }
@@ -664,82 +822,115 @@ impl BooleanEngine {
 use itertools::izip;
 
 impl PackedBinaryGatesEngine<Ciphertext, Ciphertext, ServerKey> for BooleanEngine {
-
     fn gates_packed(
         &mut self,
-        gates: &Vec<Gate>,
-        cts_left: &Vec<Ciphertext>,
-        cts_right: &Vec<Ciphertext>,
+        gates: &[Gate],
+        cts_left: &[Ciphertext],
+        cts_right: &[Ciphertext],
         server_key: &ServerKey,
     ) -> Vec<Ciphertext> {
-        assert!(gates.len() == cts_left.len());
-        // assert!(gates.len() == cts_right.len());
+        assert_eq!(
+            cts_left.len(),
+            gates.len(),
+            "The left operands and the gates have different lengths"
+        );
+        assert_eq!(
+            cts_right.len(),
+            gates.len(),
+            "The right operands and the gates have different lengths"
+        );
 
+        // the outputs known without bootstrap, and the linear combinations to bootstrap for the
+        // others, in the order of the gates
+        let mut outputs = Vec::<Option<Ciphertext>>::with_capacity(gates.len());
         let mut buffers_lwe_before_pbs = Vec::<LweCiphertext<Vec<u32>>>::new();
 
         for (ct_left, ct_right, gate) in izip!(cts_left, cts_right, gates) {
-            match (ct_left, ct_right) {
-                (Ciphertext::Encrypted(ct_left_ct), Ciphertext::Encrypted(ct_right_ct)) => {
-                    let mut buffer_lwe_before_pbs = LweCiphertext::new(
-                        0u32,
-                        server_key
-                            .bootstrapping_key
-                            .input_lwe_dimension()
-                            .to_lwe_size(),
-                        ct_left_ct.ciphertext_modulus(),
-                    );
-
-                    match gate {
-                        Gate::AND => {
-                            // compute the linear combination for AND: ct_left + ct_right + (0,...,0,-1/8)
-                            // ct_left + ct_right
-                            lwe_ciphertext_add(&mut buffer_lwe_before_pbs, ct_left_ct, ct_right_ct);
-                            let cst = Plaintext(PLAINTEXT_FALSE);
-                            // - 1/8
-                            lwe_ciphertext_plaintext_add_assign(&mut buffer_lwe_before_pbs, cst);
-                        }
-                        Gate::OR => {
-                            // Compute the linear combination for OR: ct_left + ct_right + (0,...,0,+1/8)
-                            // ct_left + ct_right
-                            lwe_ciphertext_add(&mut buffer_lwe_before_pbs, ct_left_ct, ct_right_ct);
-                            let cst = Plaintext(PLAINTEXT_TRUE);
-                            // + 1/8
-                            lwe_ciphertext_plaintext_add_assign(&mut buffer_lwe_before_pbs, cst);
-                        }
-                        Gate::XOR => {
-                            // Compute the linear combination for XOR: 2*(ct_left + ct_right) +
-                            // (0,...,0,1/4) ct_left + ct_right
-                            lwe_ciphertext_add(&mut buffer_lwe_before_pbs, ct_left_ct, ct_right_ct);
-                            let cst_add = Plaintext(PLAINTEXT_TRUE);
-                            // + 1/8
-                            lwe_ciphertext_plaintext_add_assign(
-                                &mut buffer_lwe_before_pbs,
-                                cst_add,
-                            );
-                            let cst_mul = Cleartext(2u32);
-                            //* 2
-                            lwe_ciphertext_cleartext_mul_assign(
-                                &mut buffer_lwe_before_pbs,
-                                cst_mul,
-                            );
-                        }
-                    }
+            if let (Ciphertext::Trivial(message_left), Ciphertext::Trivial(message_right)) =
+                (ct_left, ct_right)
+            {
+                outputs.push(Some(Ciphertext::Trivial(match gate {
+                    Gate::AND => *message_left && *message_right,
+                    Gate::OR => *message_left || *message_right,
+                    Gate::XOR => *message_left ^ *message_right,
+                })));
+                continue;
+            }
 
-                    buffers_lwe_before_pbs.push(buffer_lwe_before_pbs);
+            // a trivial operand is bootstrapped with the encrypted one, as a noiseless ciphertext
+            // under the key of the PBS order
+            let ct_left_ct = self.convert_into_lwe_ciphertext_32(ct_left, server_key);
+            let ct_right_ct = self.convert_into_lwe_ciphertext_32(ct_right, server_key);
+
+            let mut buffer_lwe_before_pbs =
+                LweCiphertext::new(0u32, ct_left_ct.lwe_size(), ct_left_ct.ciphertext_modulus());
+
+            match gate {
+                Gate::AND => {
+                    // compute the linear combination for AND: ct_left + ct_right + (0,...,0,-1/8)
+                    // ct_left + ct_right
+                    lwe_ciphertext_add(&mut buffer_lwe_before_pbs, &ct_left_ct, &ct_right_ct);
+                    let cst = Plaintext(PLAINTEXT_FALSE);
+                    // - 1/8
+                    lwe_ciphertext_plaintext_add_assign(&mut buffer_lwe_before_pbs, cst);
+                }
+                Gate::OR => {
+                    // Compute the linear combination for OR: ct_left + ct_right + (0,...,0,+1/8)
+                    // ct_left + ct_right
+                    lwe_ciphertext_add(&mut buffer_lwe_before_pbs, &ct_left_ct, &ct_right_ct);
+                    let cst = Plaintext(PLAINTEXT_TRUE);
+                    // + 1/8
+                    lwe_ciphertext_plaintext_add_assign(&mut buffer_lwe_before_pbs, cst);
+                }
+                Gate::XOR => {
+                    // Compute the linear combination for XOR: 2*(ct_left + ct_right) +
+                    // (0,...,0,1/4) ct_left + ct_right
+                    lwe_ciphertext_add(&mut buffer_lwe_before_pbs, &ct_left_ct, &ct_right_ct);
+                    let cst_add = Plaintext(PLAINTEXT_TRUE);
+                    // + 1/8
+                    lwe_ciphertext_plaintext_add_assign(&mut buffer_lwe_before_pbs, cst_add);
+                    let cst_mul = Cleartext(2u32);
+                    //* 2
+                    lwe_ciphertext_cleartext_mul_assign(&mut buffer_lwe_before_pbs, cst_mul);
                 }
-                _ => todo!(),
             }
+
+            outputs.push(None);
+            buffers_lwe_before_pbs.push(buffer_lwe_before_pbs);
         }
 
-        return self
-            .bootstrapper
-            .bootstrap_and_keyswitch_packed(&mut buffers_lwe_before_pbs, server_key);
+        // a batch of trivial operands needs no bootstrap at all
+        if buffers_lwe_before_pbs.is_empty() {
+            return outputs.into_iter().map(Option::unwrap).collect();
+        }
+
+        // one packed bootstrap per batch of at most FPGA_BOOTSTRAP_PACKING linear combinations
+        let mut bootstrapped = Vec::with_capacity(buffers_lwe_before_pbs.len());
+        let mut buffers_lwe_before_pbs = buffers_lwe_before_pbs.into_iter();
+        loop {
+            let mut batch: Vec<_> = buffers_lwe_before_pbs
+                .by_ref()
+                .take(FPGA_BOOTSTRAP_PACKING)
+                .collect();
+            if batch.is_empty() {
+                break;
+            }
+            bootstrapped.extend(
+                self.bootstrapper
+                    .bootstrap_and_keyswitch_packed(&mut batch, server_key),
+            );
+        }
+        let mut bootstrapped = bootstrapped.into_iter();
+        outputs
+            .into_iter()
+            .map(|output| output.unwrap_or_else(|| bootstrapped.next().unwrap()))
+            .collect()
     }
 
     fn and_packed(
         &mut self,
-        cts_left: &Vec<Ciphertext>,
-        cts_right: &Vec<Ciphertext>,
+        cts_left: &[Ciphertext],
+        cts_right: &[Ciphertext],
         server_key: &ServerKey,
     ) -> Vec<Ciphertext> {
         return self.gates_packed(
@@ -752,8 +943,8 @@ impl PackedBinaryGatesEngine<Ciphertext, Ciphertext, ServerKey> for BooleanEngin
 
     fn or_packed(
         &mut self,
-        cts_left: &Vec<Ciphertext>,
-        cts_right: &Vec<Ciphertext>,
+        cts_left: &[Ciphertext],
+        cts_right: &[Ciphertext],
         server_key: &ServerKey,
     ) -> Vec<Ciphertext> {
         return self.gates_packed(
@@ -766,8 +957,8 @@ impl PackedBinaryGatesEngine<Ciphertext, Ciphertext, ServerKey> for BooleanEngin
 
     fn xor_packed(
         &mut self,
-        cts_left: &Vec<Ciphertext>,
-        cts_right: &Vec<Ciphertext>,
+        cts_left: &[Ciphertext],
+        cts_right: &[Ciphertext],
         server_key: &ServerKey,
     ) -> Vec<Ciphertext> {
         return self.gates_packed(
@@ -781,13 +972,51 @@ impl PackedBinaryGatesEngine<Ciphertext, Ciphertext, ServerKey> for BooleanEngin
 
 #[cfg(feature = "fpga")]
 impl FpgaEngine<ServerKey> for BooleanEngine {
-    fn enable_fpga(&mut self, bsk: &FourierLweBootstrapKeyOwned) {
-        self.bootstrapper.enable_fpga(bsk);
+    fn enable_fpga(
+        &mut self,
+        key: &FpgaBootstrapKey,
+        server_key: &ServerKey,
+    ) -> Result<(), FpgaKeyError> {
+        self.bootstrapper
+            .enable_fpga(server_key.bootstrapping_key_fingerprint(), key)
+    }
+
+    fn evict_fpga_key(&mut self, server_key: &ServerKey) -> Result<(), FpgaKeyError> {
+        self.bootstrapper
+            .evict_fpga_key(server_key.bootstrapping_key_fingerprint())
     }
 
     fn disable_fpga(&mut self) {
         self.bootstrapper.disable_fpga();
     }
+
+    fn fpga_enabled(&self) -> bool {
+        self.bootstrapper.fpga_enabled()
+    }
+}
+
+#[cfg(feature = "remote-bootstrap")]
+impl RemoteEngine<ServerKey> for BooleanEngine {
+    fn enable_remote_bootstrap<A: ToSocketAddrs>(
+        &mut self,
+        address: A,
+        server_key: &ServerKey,
+    ) -> Result<(), RemoteBootstrapError> {
+        self.bootstrapper
+            .enable_remote_bootstrap(address, server_key)
+    }
+
+    fn evict_remote_key(&mut self, server_key: &ServerKey) -> Result<(), RemoteBootstrapError> {
+        self.bootstrapper.evict_remote_key(server_key)
+    }
+
+    fn disable_remote_bootstrap(&mut self) {
+        self.bootstrapper.disable_remote_bootstrap();
+    }
+
+    fn remote_bootstrap_enabled(&self) -> bool {
+        self.bootstrapper.remote_bootstrap_enabled()
+    }
 }
 
 impl BinaryGatesEngine<&Ciphertext, &Ciphertext, ServerKey> for BooleanEngine {
@@ -0,0 +1,155 @@
+use crate::boolean::client_key::ClientKey;
+use crate::boolean::parameters::BooleanParameters;
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+use crate::boolean::server_key::FpgaBootstrapKey;
+use crate::boolean::server_key::ServerKey;
+use crate::keycache::*;
+use lazy_static::*;
+
+impl From<BooleanParameters> for (ClientKey, ServerKey) {
+    fn from(param: BooleanParameters) -> Self {
+        let cks = ClientKey::new(&param);
+        let sks = ServerKey::new(&cks);
+        (cks, sks)
+    }
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+impl From<BooleanParameters> for FpgaBootstrapKey {
+    fn from(param: BooleanParameters) -> Self {
+        // use with_key to avoid doing a temporary cloning
+        KEY_CACHE.inner.with_key(param, |keys| {
+            FpgaBootstrapKey::from_server_key(&keys.1).unwrap()
+        })
+    }
+}
+
+pub struct Keycache {
+    inner: ImplKeyCache<BooleanParameters, (ClientKey, ServerKey), FileStorage>,
+}
+
+impl Default for Keycache {
+    fn default() -> Self {
+        Self {
+            inner: ImplKeyCache::new(FileStorage::new(
+                "../keys/boolean/client_server".to_string(),
+            )),
+        }
+    }
+}
+
+pub struct SharedKey {
+    inner: GenericSharedKey<(ClientKey, ServerKey)>,
+}
+
+impl SharedKey {
+    pub fn client_key(&self) -> &ClientKey {
+        &self.inner.0
+    }
+    pub fn server_key(&self) -> &ServerKey {
+        &self.inner.1
+    }
+}
+
+impl Keycache {
+    pub fn get_from_param(&self, param: BooleanParameters) -> SharedKey {
+        SharedKey {
+            inner: self.inner.get(param),
+        }
+    }
+
+    pub fn clear_in_memory_cache(&self) {
+        self.inner.clear_in_memory_cache();
+    }
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+/// The files of the FPGA memory images, which only hold an image if it was computed from the
+/// server key of [KEY_CACHE], so that the images of a regenerated key cache are computed again.
+pub struct FpgaImageStorage {
+    files: FileStorage,
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+impl FpgaImageStorage {
+    pub fn new(prefix: String) -> Self {
+        Self {
+            files: FileStorage::new(prefix),
+        }
+    }
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+impl PersistentStorage<BooleanParameters, FpgaBootstrapKey> for FpgaImageStorage {
+    fn load(&self, param: BooleanParameters) -> Option<FpgaBootstrapKey> {
+        let image: FpgaBootstrapKey = self.files.load(param)?;
+        let expected = KEY_CACHE
+            .inner
+            .with_key(param, |keys| keys.1.bootstrapping_key_fingerprint());
+        (image.source_fingerprint() == Some(expected)).then_some(image)
+    }
+
+    fn store(&self, param: BooleanParameters, key: &FpgaBootstrapKey) {
+        self.files.store(param, key);
+    }
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+/// The KeyCache struct for the FPGA memory images.
+///
+/// Images are derived from the server keys of [KEY_CACHE] so both caches stay consistent.
+/// You should not create an instance yourself, but rather use the global variable defined:
+/// [KEY_CACHE_FPGA]
+pub struct KeycacheFpga {
+    inner: ImplKeyCache<BooleanParameters, FpgaBootstrapKey, FpgaImageStorage>,
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+impl Default for KeycacheFpga {
+    fn default() -> Self {
+        Self {
+            inner: ImplKeyCache::new(FpgaImageStorage::new("../keys/boolean/fpga".to_string())),
+        }
+    }
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+pub struct SharedFpgaKey {
+    inner: GenericSharedKey<(ClientKey, ServerKey)>,
+    fpga: GenericSharedKey<FpgaBootstrapKey>,
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+impl SharedFpgaKey {
+    pub fn client_key(&self) -> &ClientKey {
+        &self.inner.0
+    }
+    pub fn server_key(&self) -> &ServerKey {
+        &self.inner.1
+    }
+    pub fn fpga_key(&self) -> &FpgaBootstrapKey {
+        &self.fpga
+    }
+}
+
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+impl KeycacheFpga {
+    pub fn get_from_param(&self, param: BooleanParameters) -> SharedFpgaKey {
+        let key = KEY_CACHE.get_from_param(param);
+        let fpga = self.inner.get(param);
+        SharedFpgaKey {
+            inner: key.inner,
+            fpga,
+        }
+    }
+
+    pub fn clear_in_memory_cache(&self) {
+        self.inner.clear_in_memory_cache();
+    }
+}
+
+lazy_static! {
+    pub static ref KEY_CACHE: Keycache = Default::default();
+    #[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+    pub static ref KEY_CACHE_FPGA: KeycacheFpga = Default::default();
+}
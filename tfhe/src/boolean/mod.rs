@@ -54,7 +54,7 @@
 
 use crate::boolean::client_key::ClientKey;
 use crate::boolean::parameters::DEFAULT_PARAMETERS;
-use crate::boolean::public_key::{CompressedPublicKey, PublicKey};
+use crate::boolean::public_key::{CompactPublicKey, CompressedPublicKey, PublicKey};
 use crate::boolean::server_key::ServerKey;
 #[cfg(test)]
 use rand::Rng;
@@ -63,6 +63,8 @@ pub mod ciphertext;
 pub mod client_key;
 pub mod engine;
 pub mod key_switching_key;
+#[cfg(any(test, feature = "internal-keycache"))]
+pub mod keycache;
 pub mod parameters;
 pub mod prelude;
 pub mod public_key;
@@ -203,8 +203,16 @@ impl NamedParam for BooleanParameters {
     fn name(&self) -> &'static str {
         if *self == DEFAULT_PARAMETERS {
             "DEFAULT_PARAMETERS"
+        } else if *self == DEFAULT_PARAMETERS_KS_PBS {
+            "DEFAULT_PARAMETERS_KS_PBS"
+        } else if *self == PARAMETERS_ERROR_PROB_2_POW_MINUS_165 {
+            "PARAMETERS_ERROR_PROB_2_POW_MINUS_165"
+        } else if *self == PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS {
+            "PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS"
         } else if *self == TFHE_LIB_PARAMETERS {
             "TFHE_LIB_PARAMETERS"
+        } else if *self == DEMO_PARAMETERS {
+            "DEMO_PARAMETERS"
         } else {
             panic!("Unknown parameters, missing name implementation")
         }
@@ -245,4 +253,36 @@ pub const BSK_SIZE: usize = (DEMO_PARAMETERS.glwe_dimension.0 + 1)
     * BSK_DEPTH
     * 2
     * std::mem::size_of::<u64>();
-    
\ No newline at end of file
+
+/// Number of bootstrapping keys that can be resident in the FPGA memory at the same time.
+pub const FPGA_KEY_SLOTS: usize = 8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The keys of every parameter set are cached under its name.
+    #[test]
+    fn test_boolean_parameters_are_named() {
+        let names = [
+            DEFAULT_PARAMETERS,
+            DEFAULT_PARAMETERS_KS_PBS,
+            PARAMETERS_ERROR_PROB_2_POW_MINUS_165,
+            PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS,
+            TFHE_LIB_PARAMETERS,
+            DEMO_PARAMETERS,
+        ]
+        .map(|parameters| parameters.name());
+        assert_eq!(
+            names,
+            [
+                "DEFAULT_PARAMETERS",
+                "DEFAULT_PARAMETERS_KS_PBS",
+                "PARAMETERS_ERROR_PROB_2_POW_MINUS_165",
+                "PARAMETERS_ERROR_PROB_2_POW_MINUS_165_KS_PBS",
+                "TFHE_LIB_PARAMETERS",
+                "DEMO_PARAMETERS",
+            ]
+        );
+    }
+}
@@ -3,10 +3,16 @@
 //! The TFHE-rs preludes include convenient imports.
 //! Having `tfhe::boolean::prelude::*;` should be enough to start using the lib.
 
-pub use super::ciphertext::{Ciphertext, CompressedCiphertext};
+pub use super::ciphertext::{
+    Ciphertext, CompactCiphertextList, CompressedCiphertext, CompressedCiphertextList,
+};
 pub use super::client_key::ClientKey;
 pub use super::gen_keys;
 pub use super::key_switching_key::KeySwitchingKey;
 pub use super::parameters::*;
-pub use super::public_key::{CompressedPublicKey, PublicKey};
-pub use super::server_key::{BinaryBooleanGates, PackedBinaryBooleanGates, ServerKey};
+pub use super::public_key::{
+    CompactPublicKey, CompactPublicKeyError, CompressedPublicKey, PublicKey,
+};
+pub use super::server_key::{
+    BinaryBooleanGates, PackedBinaryBooleanGates, PackedBootstrapStats, ServerKey,
+};
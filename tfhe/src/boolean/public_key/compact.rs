@@ -0,0 +1,318 @@
+//! Module with the definition of the CompactPublicKey.
+
+use crate::boolean::ciphertext::CompactCiphertextList;
+use crate::boolean::client_key::ClientKey;
+use crate::boolean::engine::{BooleanEngine, WithThreadLocalEngine};
+use crate::boolean::parameters::BooleanParameters;
+use crate::core_crypto::commons::parameters::LweDimension;
+use crate::core_crypto::entities::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Error returned when a [`CompactPublicKey`] cannot be generated for the parameters of a client
+/// key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactPublicKeyError {
+    /// The LWE dimension of the large key, `k * N`, is not a power of two.
+    LweDimensionNotPowerOfTwo { lwe_dimension: LweDimension },
+}
+
+impl Display for CompactPublicKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompactPublicKeyError::LweDimensionNotPowerOfTwo { lwe_dimension } => {
+                write!(
+                    f,
+                    "Incompatible parameters, the large key has {lwe_dimension:?}, which is not a \
+                    power of two"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompactPublicKeyError {}
+
+/// A structure containing a compact public key.
+///
+/// It is much smaller than a [`PublicKey`](super::PublicKey), and encrypts lists of messages into
+/// a [`CompactCiphertextList`] sharing a single mask. The key encrypts under the large key of the
+/// client key, whatever its encryption key choice, so the lists have to be expanded with the
+/// server key before evaluating a Boolean circuit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompactPublicKey {
+    pub(crate) key: LweCompactPublicKeyOwned<u32>,
+    pub parameters: BooleanParameters,
+}
+
+impl CompactPublicKey {
+    /// Generate a new compact public key.
+    ///
+    /// Returns an error if the LWE dimension of the large key, `k * N`, is not a power of two.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() {
+    /// use tfhe::boolean::prelude::*;
+    ///
+    /// // Generate the client key and the server key:
+    /// let (cks, sks) = gen_keys();
+    ///
+    /// let cpks = CompactPublicKey::new(&cks).unwrap();
+    /// # }
+    /// ```
+    pub fn new(client_key: &ClientKey) -> Result<CompactPublicKey, CompactPublicKeyError> {
+        BooleanEngine::with_thread_local_mut(|engine| engine.create_compact_public_key(client_key))
+    }
+
+    /// Encrypt a list of Boolean messages using the compact public key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() {
+    /// use tfhe::boolean::prelude::*;
+    ///
+    /// // Generate the client key and the server key:
+    /// let (cks, sks) = gen_keys();
+    ///
+    /// let cpks = CompactPublicKey::new(&cks).unwrap();
+    ///
+    /// // Encryption of a list of messages:
+    /// let messages = [true, false, true];
+    /// let compact_list = cpks.encrypt_slice(&messages);
+    ///
+    /// // Expansion and decryption:
+    /// let cts = compact_list.expand(&sks).unwrap();
+    /// for (ct, message) in cts.iter().zip(messages) {
+    ///     assert_eq!(cks.decrypt(ct), message);
+    /// }
+    /// # }
+    /// ```
+    pub fn encrypt_slice(&self, messages: &[bool]) -> CompactCiphertextList {
+        self.encrypt_iter(messages.iter().copied())
+    }
+
+    /// Encrypt the Boolean messages of an iterator using the compact public key.
+    pub fn encrypt_iter(&self, messages: impl Iterator<Item = bool>) -> CompactCiphertextList {
+        BooleanEngine::with_thread_local_mut(|engine| {
+            engine.encrypt_with_compact_public_key(messages, self)
+        })
+    }
+
+    pub fn size_elements(&self) -> usize {
+        self.key.size_elements()
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        self.key.size_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LweDimension;
+    use crate::boolean::ciphertext::CompactListExpandError;
+    use crate::boolean::parameters::GlweDimension;
+    use crate::boolean::prelude::{
+        BinaryBooleanGates, BooleanParameters, ClientKey, CompactCiphertextList, CompactPublicKey,
+        CompactPublicKeyError, PublicKey, ServerKey, DEFAULT_PARAMETERS, DEFAULT_PARAMETERS_KS_PBS,
+        DEMO_PARAMETERS, PARAMETERS_ERROR_PROB_2_POW_MINUS_165, TFHE_LIB_PARAMETERS,
+    };
+    use crate::boolean::{random_boolean, PLAINTEXT_FALSE, PLAINTEXT_TRUE};
+    use crate::core_crypto::algorithms::{
+        decrypt_lwe_ciphertext, expand_lwe_compact_ciphertext_list,
+    };
+    use crate::core_crypto::commons::traits::ContiguousEntityContainer;
+    use crate::core_crypto::entities::LweCiphertextList;
+    const NB_TEST: usize = 32;
+    const NB_NOISE_KEYS: usize = 16;
+    const NB_NOISE_SAMPLES: usize = 4096;
+
+    #[test]
+    fn test_compact_public_key_default_parameters() {
+        test_compact_public_key(DEFAULT_PARAMETERS);
+    }
+
+    #[test]
+    fn test_compact_public_key_default_parameters_ks_pbs() {
+        test_compact_public_key(DEFAULT_PARAMETERS_KS_PBS);
+    }
+
+    #[test]
+    fn test_compact_public_key_tfhe_lib_parameters() {
+        test_compact_public_key(TFHE_LIB_PARAMETERS);
+    }
+
+    #[test]
+    fn test_compact_public_key_error_prob_2_pow_minus_165_parameters() {
+        test_compact_public_key(PARAMETERS_ERROR_PROB_2_POW_MINUS_165);
+    }
+
+    fn test_compact_public_key(parameters: BooleanParameters) {
+        let cks = ClientKey::new(&parameters);
+        let sks = ServerKey::new(&cks);
+        let cpks = CompactPublicKey::new(&cks).unwrap();
+
+        let messages: Vec<bool> = (0..2 * NB_TEST).map(|_| random_boolean()).collect();
+        let compact_list = cpks.encrypt_slice(&messages);
+        assert_eq!(compact_list.len(), messages.len());
+
+        // The list goes through serialization, as it would on an upload
+        let serialized = bincode::serialize(&compact_list).unwrap();
+        let compact_list: CompactCiphertextList = bincode::deserialize(&serialized).unwrap();
+        let cts = compact_list.expand(&sks).unwrap();
+        assert_eq!(cts.len(), messages.len());
+
+        for (ct, bits) in cts.chunks_exact(2).zip(messages.chunks_exact(2)) {
+            let (b1, b2) = (bits[0], bits[1]);
+            let expected_result = !(b1 && b2);
+
+            let ct_res = sks.nand(&ct[0], &ct[1]);
+
+            let dec_ct1 = cks.decrypt(&ct[0]);
+            let dec_ct2 = cks.decrypt(&ct[1]);
+            let dec_nand = cks.decrypt(&ct_res);
+
+            assert_eq!(dec_ct1, b1);
+            assert_eq!(dec_ct2, b2);
+            assert_eq!(dec_nand, expected_result);
+        }
+
+        let empty_list = cpks.encrypt_iter(std::iter::empty());
+        assert!(empty_list.is_empty());
+        assert!(empty_list.expand(&sks).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compact_public_key_noise_default_parameters() {
+        test_compact_public_key_noise(DEFAULT_PARAMETERS);
+    }
+
+    #[test]
+    fn test_compact_public_key_noise_default_parameters_ks_pbs() {
+        test_compact_public_key_noise(DEFAULT_PARAMETERS_KS_PBS);
+    }
+
+    #[test]
+    fn test_compact_public_key_noise_tfhe_lib_parameters() {
+        test_compact_public_key_noise(TFHE_LIB_PARAMETERS);
+    }
+
+    #[test]
+    fn test_compact_public_key_noise_error_prob_2_pow_minus_165_parameters() {
+        test_compact_public_key_noise(PARAMETERS_ERROR_PROB_2_POW_MINUS_165);
+    }
+
+    #[test]
+    fn test_compact_public_key_noise_demo_parameters() {
+        test_compact_public_key_noise(DEMO_PARAMETERS);
+    }
+
+    /// Measure the noise of the expanded ciphertexts under the large key, before the first
+    /// keyswitch, and check it against its predicted variance and the decryption margin.
+    fn test_compact_public_key_noise(parameters: BooleanParameters) {
+        // The ciphertexts of a list share their randomness, and those of a key its noise: the
+        // samples are spread over several keys for their variance to settle.
+        let mut noises = Vec::with_capacity(NB_NOISE_KEYS * NB_NOISE_SAMPLES);
+        let mut lwe_dimension = LweDimension(0);
+        for _ in 0..NB_NOISE_KEYS {
+            let cks = ClientKey::new(&parameters);
+            let cpks = CompactPublicKey::new(&cks).unwrap();
+            let lwe_sk = cks.glwe_secret_key.as_lwe_secret_key();
+            lwe_dimension = lwe_sk.lwe_dimension();
+
+            let messages: Vec<bool> = (0..NB_NOISE_SAMPLES).map(|_| random_boolean()).collect();
+            let compact_list = cpks.encrypt_slice(&messages);
+            let mut cts = LweCiphertextList::new(
+                0u32,
+                compact_list.ct_list.lwe_size(),
+                compact_list.ct_list.lwe_ciphertext_count(),
+                compact_list.ct_list.ciphertext_modulus(),
+            );
+            expand_lwe_compact_ciphertext_list(&mut cts, &compact_list.ct_list);
+
+            noises.extend(cts.iter().zip(messages.iter()).map(|(ct, &message)| {
+                let plaintext = if message {
+                    PLAINTEXT_TRUE
+                } else {
+                    PLAINTEXT_FALSE
+                };
+                decrypt_lwe_ciphertext(&lwe_sk, &ct)
+                    .0
+                    .wrapping_sub(plaintext) as i32 as f64
+            }));
+        }
+        let variance = noises.iter().map(|noise| noise * noise).sum::<f64>() / noises.len() as f64;
+
+        // n + 1 times the variance of the GLWE noise rounded to the torus, for n = k * N: the noise
+        // of the key times the binary randomness and the mask noise times the binary secret key
+        // make n / 2 each, the body noise one more.
+        let std_dev = parameters.glwe_modular_std_dev.0 * 2f64.powi(32);
+        let expected_variance = (lwe_dimension.0 as f64 + 1.) * (std_dev * std_dev + 1. / 12.);
+        assert!(
+            variance <= 1.5 * expected_variance,
+            "noise variance {variance}, expected at most {expected_variance}"
+        );
+
+        // The messages sit q / 8 away from the decryption boundaries: at more than 2^10 standard
+        // deviations, the probability of a wrong decryption is below 2^-700000.
+        let margin = 2f64.powi(29);
+        assert!(margin > 1024. * expected_variance.sqrt());
+        assert!(noises.iter().all(|noise| noise.abs() < margin / 1024.));
+    }
+
+    #[test]
+    fn test_compact_public_key_unsupported_parameters() {
+        // k * N = 3 * 128 is not a power of two
+        let parameters = BooleanParameters {
+            glwe_dimension: GlweDimension(3),
+            ..DEMO_PARAMETERS
+        };
+        let cks = ClientKey::new(&parameters);
+        assert_eq!(
+            CompactPublicKey::new(&cks),
+            Err(CompactPublicKeyError::LweDimensionNotPowerOfTwo {
+                lwe_dimension: LweDimension(384)
+            })
+        );
+    }
+
+    #[test]
+    fn test_compact_list_expand_with_other_parameters() {
+        let cks = ClientKey::new(&DEFAULT_PARAMETERS);
+        let cpks = CompactPublicKey::new(&cks).unwrap();
+        let other_cks = ClientKey::new(&PARAMETERS_ERROR_PROB_2_POW_MINUS_165);
+        let other_sks = ServerKey::new(&other_cks);
+
+        let compact_list = cpks.encrypt_slice(&[true, false]);
+        assert!(matches!(
+            compact_list.expand(&other_sks),
+            Err(CompactListExpandError::LweSizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_compact_public_key_sizes() {
+        let cks = ClientKey::new(&DEFAULT_PARAMETERS);
+        let cpks = CompactPublicKey::new(&cks).unwrap();
+        let pks = PublicKey::new(&cks);
+
+        let compact_key_size = bincode::serialized_size(&cpks).unwrap();
+        let key_size = bincode::serialized_size(&pks).unwrap();
+        assert!(compact_key_size * 100 < key_size);
+
+        // A mask for the whole list, and a body per message
+        let messages = [true; NB_TEST];
+        let list_size = bincode::serialized_size(&cpks.encrypt_slice(&messages)).unwrap();
+        let ciphertexts_size = bincode::serialized_size(
+            &messages
+                .iter()
+                .map(|&message| pks.encrypt(message))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert!(list_size * 10 < ciphertexts_size);
+    }
+}
@@ -1,5 +1,7 @@
+mod compact;
 mod compressed;
 mod standard;
 
+pub use compact::{CompactPublicKey, CompactPublicKeyError};
 pub use compressed::CompressedPublicKey;
 pub use standard::PublicKey;
@@ -10,13 +10,32 @@ mod tests;
 
 use crate::boolean::ciphertext::Ciphertext;
 use crate::boolean::client_key::ClientKey;
-pub use crate::boolean::engine::bootstrapping::{CompressedServerKey, ServerKey};
+#[cfg(any(feature = "fpga", feature = "remote-bootstrap"))]
+pub use crate::boolean::engine::bootstrapping::fpga_key::{
+    FpgaBootstrapKey, FpgaKeyError, FpgaKeyGeometry, FpgaKeyHeader, FpgaKeyWidths,
+    FPGA_BOOTSTRAP_KEY_VERSION,
+};
+#[cfg(feature = "remote-bootstrap")]
+pub use crate::boolean::engine::bootstrapping::remote::{
+    BootstrapServer, RemoteBootstrapError, REMOTE_BOOTSTRAP_DEFAULT_MAX_KEYS,
+    REMOTE_BOOTSTRAP_DEFAULT_MAX_SESSIONS, REMOTE_BOOTSTRAP_DEFAULT_READ_TIMEOUT,
+    REMOTE_BOOTSTRAP_MAX_FRAME_SIZE, REMOTE_BOOTSTRAP_MAX_REQUEST_SIZE,
+    REMOTE_BOOTSTRAP_PROTOCOL_VERSION,
+};
+pub use crate::boolean::engine::bootstrapping::{
+    CompressedServerKey, PackedBootstrapStats, ServerKey,
+};
 use crate::boolean::engine::{
-    BinaryGatesAssignEngine, BinaryGatesEngine, PackedBinaryGatesEngine, BooleanEngine, WithThreadLocalEngine,
+    BinaryGatesAssignEngine, BinaryGatesEngine, BooleanEngine, PackedBinaryGatesEngine,
+    WithThreadLocalEngine,
 };
 
 #[cfg(feature = "fpga")]
 use crate::boolean::engine::FpgaEngine;
+#[cfg(feature = "remote-bootstrap")]
+use crate::boolean::engine::RemoteEngine;
+#[cfg(feature = "remote-bootstrap")]
+use std::net::ToSocketAddrs;
 
 use super::engine::Gate;
 
@@ -39,21 +58,59 @@ pub trait BinaryBooleanGatesAssign<L, R> {
 }
 
 pub trait PackedBinaryBooleanGates<L, R> {
-    fn gates_packed(
-        &self,
-        gates: &Vec<Gate>,
-        cts_left: &Vec<L>,
-        cts_right: &Vec<R>,
-    ) -> Vec<Ciphertext>;
-    fn and_packed(&self, cts_left: &Vec<L>, cts_right: &Vec<R>) -> Vec<Ciphertext>;
-    fn or_packed(&self, cts_left: &Vec<L>, cts_right: &Vec<R>) -> Vec<Ciphertext>;
-    fn xor_packed(&self, cts_left: &Vec<L>, cts_right: &Vec<R>) -> Vec<Ciphertext>;
+    /// Element-wise gates, whose bootstraps are packed in batches of at most
+    /// [`FPGA_BOOTSTRAP_PACKING`](crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING).
+    ///
+    /// Gates with two trivial operands are computed without bootstrap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operands and the gates have different lengths. The same holds for the
+    /// operands of `and_packed`, `or_packed` and `xor_packed`.
+    fn gates_packed(&self, gates: &[Gate], cts_left: &[L], cts_right: &[R]) -> Vec<Ciphertext>;
+    fn and_packed(&self, cts_left: &[L], cts_right: &[R]) -> Vec<Ciphertext>;
+    fn or_packed(&self, cts_left: &[L], cts_right: &[R]) -> Vec<Ciphertext>;
+    fn xor_packed(&self, cts_left: &[L], cts_right: &[R]) -> Vec<Ciphertext>;
 }
 
 #[cfg(feature = "fpga")]
 pub trait FpgaGates {
-    fn enable_fpga(&self);
+    /// Upload the bootstrapping key of `self` and route packed gates of this thread to the FPGA.
+    ///
+    /// Several server keys can be resident on the card at the same time, up to
+    /// [`FPGA_KEY_SLOTS`](crate::boolean::parameters::FPGA_KEY_SLOTS); packed gates always run
+    /// with the key of the server key they are called on.
+    fn enable_fpga(&self) -> Result<(), FpgaKeyError>;
+    /// Same as [`FpgaGates::enable_fpga`], using a memory image computed ahead of time.
+    ///
+    /// The image must have been computed from the bootstrapping key of `self`, otherwise
+    /// [`FpgaKeyError::SourceFingerprintMismatch`] is returned.
+    fn enable_fpga_with_key(&self, key: &FpgaBootstrapKey) -> Result<(), FpgaKeyError>;
+    /// Free the slot holding the bootstrapping key of `self` on the card.
+    fn evict_fpga_key(&self) -> Result<(), FpgaKeyError>;
     fn disable_fpga(&self);
+    /// Whether packed gates of this thread run on the FPGA.
+    fn fpga_enabled(&self) -> bool;
+}
+
+#[cfg(feature = "remote-bootstrap")]
+pub trait RemoteGates {
+    /// Connect this thread to the [`BootstrapServer`] at `address`, which then computes the
+    /// bootstraps of its packed gates.
+    ///
+    /// The server gets the bootstrapping key of `self` unless it already holds it; packed gates
+    /// called with another server key upload its key on first use. Remote bootstrapping takes
+    /// precedence over the FPGA of this thread.
+    fn enable_remote_bootstrap<A: ToSocketAddrs>(
+        &self,
+        address: A,
+    ) -> Result<(), RemoteBootstrapError>;
+    /// Remove the bootstrapping key of `self` from the server, making room for another key.
+    fn evict_remote_key(&self) -> Result<(), RemoteBootstrapError>;
+    /// Close the connection to the server, packed gates of this thread then running locally.
+    fn disable_remote_bootstrap(&self);
+    /// Whether packed gates of this thread run on a bootstrap server.
+    fn remote_bootstrap_enabled(&self) -> bool;
 }
 
 trait DefaultImplementation {
@@ -72,30 +129,25 @@ impl<Lhs, Rhs> PackedBinaryBooleanGates<Lhs, Rhs> for ServerKey
 where
     <ServerKey as DefaultImplementation>::Engine: PackedBinaryGatesEngine<Lhs, Rhs, ServerKey>,
 {
-    fn gates_packed(
-        &self,
-        gates: &Vec<Gate>,
-        cts_left: &Vec<Lhs>,
-        cts_right: &Vec<Rhs>,
-    ) -> Vec<Ciphertext> {
+    fn gates_packed(&self, gates: &[Gate], cts_left: &[Lhs], cts_right: &[Rhs]) -> Vec<Ciphertext> {
         <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
             engine.gates_packed(gates, cts_left, cts_right, self)
         })
     }
 
-    fn and_packed(&self, cts_left: &Vec<Lhs>, cts_right: &Vec<Rhs>) -> Vec<Ciphertext> {
+    fn and_packed(&self, cts_left: &[Lhs], cts_right: &[Rhs]) -> Vec<Ciphertext> {
         <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
             engine.and_packed(cts_left, cts_right, self)
         })
     }
 
-    fn or_packed(&self, cts_left: &Vec<Lhs>, cts_right: &Vec<Rhs>) -> Vec<Ciphertext> {
+    fn or_packed(&self, cts_left: &[Lhs], cts_right: &[Rhs]) -> Vec<Ciphertext> {
         <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
             engine.or_packed(cts_left, cts_right, self)
         })
     }
 
-    fn xor_packed(&self, cts_left: &Vec<Lhs>, cts_right: &Vec<Rhs>) -> Vec<Ciphertext> {
+    fn xor_packed(&self, cts_left: &[Lhs], cts_right: &[Rhs]) -> Vec<Ciphertext> {
         <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
             engine.xor_packed(cts_left, cts_right, self)
         })
@@ -107,22 +159,68 @@ impl FpgaGates for ServerKey
 where
     <ServerKey as DefaultImplementation>::Engine: FpgaEngine<ServerKey>,
 {
-    fn enable_fpga(
-        &self
-    ) {
-        let bsk = &self.bootstrapping_key;
+    fn enable_fpga(&self) -> Result<(), FpgaKeyError> {
+        let key = FpgaBootstrapKey::from_server_key(self)?;
+        self.enable_fpga_with_key(&key)
+    }
+
+    fn enable_fpga_with_key(&self, key: &FpgaBootstrapKey) -> Result<(), FpgaKeyError> {
+        key.check_source(self)?;
+        <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
+            engine.enable_fpga(key, self)
+        })
+    }
+
+    fn evict_fpga_key(&self) -> Result<(), FpgaKeyError> {
         <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
-            engine.enable_fpga(bsk);
+            engine.evict_fpga_key(self)
         })
     }
 
-    fn disable_fpga(
-        &self
-    ) {
+    fn disable_fpga(&self) {
         <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
             engine.disable_fpga();
         })
     }
+
+    fn fpga_enabled(&self) -> bool {
+        <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
+            engine.fpga_enabled()
+        })
+    }
+}
+
+#[cfg(feature = "remote-bootstrap")]
+impl RemoteGates for ServerKey
+where
+    <ServerKey as DefaultImplementation>::Engine: RemoteEngine<ServerKey>,
+{
+    fn enable_remote_bootstrap<A: ToSocketAddrs>(
+        &self,
+        address: A,
+    ) -> Result<(), RemoteBootstrapError> {
+        <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
+            engine.enable_remote_bootstrap(address, self)
+        })
+    }
+
+    fn evict_remote_key(&self) -> Result<(), RemoteBootstrapError> {
+        <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
+            engine.evict_remote_key(self)
+        })
+    }
+
+    fn disable_remote_bootstrap(&self) {
+        <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
+            engine.disable_remote_bootstrap();
+        })
+    }
+
+    fn remote_bootstrap_enabled(&self) -> bool {
+        <ServerKey as DefaultImplementation>::Engine::with_thread_local_mut(|engine| {
+            engine.remote_bootstrap_enabled()
+        })
+    }
 }
 
 impl<Lhs, Rhs> BinaryBooleanGates<Lhs, Rhs> for ServerKey
@@ -224,10 +322,22 @@ impl ServerKey {
         BooleanEngine::with_thread_local_mut(|engine| engine.not_assign(ct))
     }
 
-    pub fn not_packed(&self, cts: &Vec<Ciphertext>) -> Vec<Ciphertext> {
+    pub fn not_packed(&self, cts: &[Ciphertext]) -> Vec<Ciphertext> {
         BooleanEngine::with_thread_local_mut(|engine| engine.not_packed(cts))
     }
 
+    /// Counters of the packed bootstraps computed by this thread, with any server key, since the
+    /// last call to [`ServerKey::reset_packed_bootstrap_stats`].
+    pub fn packed_bootstrap_stats(&self) -> PackedBootstrapStats {
+        BooleanEngine::with_thread_local_mut(|engine| engine.bootstrapper.packed_stats)
+    }
+
+    pub fn reset_packed_bootstrap_stats(&self) {
+        BooleanEngine::with_thread_local_mut(|engine| {
+            engine.bootstrapper.packed_stats = PackedBootstrapStats::default();
+        })
+    }
+
     pub fn mux(
         &self,
         ct_condition: &Ciphertext,
@@ -928,3 +928,127 @@ fn test_deep_circuit(parameters: BooleanParameters) {
         assert_eq!(*boolean, dec);
     }
 }
+
+#[test]
+fn test_packed_bootstrap_stats() {
+    use crate::boolean::parameters::{DEMO_PARAMETERS, FPGA_BOOTSTRAP_PACKING};
+    use crate::boolean::server_key::{PackedBinaryBooleanGates, PackedBootstrapStats};
+
+    let cks = ClientKey::new(&DEMO_PARAMETERS);
+    let sks = ServerKey::new(&cks);
+
+    let full: Vec<Ciphertext> = (0..FPGA_BOOTSTRAP_PACKING)
+        .map(|_| cks.encrypt(random_boolean()))
+        .collect();
+    let partial = full[..5].to_vec();
+
+    sks.reset_packed_bootstrap_stats();
+    let _ = sks.and_packed(&full, &full);
+    let _ = sks.xor_packed(&partial, &partial);
+    // not is linear, it does not bootstrap
+    let _ = sks.not_packed(&partial);
+
+    let stats = sks.packed_bootstrap_stats();
+    assert_eq!(
+        stats,
+        PackedBootstrapStats {
+            batches: 2,
+            bootstraps: FPGA_BOOTSTRAP_PACKING as u64 + 5,
+            fpga_batches: 0,
+        }
+    );
+    let expected = (FPGA_BOOTSTRAP_PACKING + 5) as f64 / (2 * FPGA_BOOTSTRAP_PACKING) as f64;
+    assert!((stats.batch_utilisation() - expected).abs() < 1e-12);
+
+    sks.reset_packed_bootstrap_stats();
+    assert_eq!(
+        sks.packed_bootstrap_stats(),
+        PackedBootstrapStats::default()
+    );
+}
+
+#[test]
+fn test_packed_gates_trivial_operands_and_pbs_orders() {
+    use crate::boolean::engine::Gate;
+    use crate::boolean::parameters::{
+        EncryptionKeyChoice, DEMO_PARAMETERS, FPGA_BOOTSTRAP_PACKING,
+    };
+    use crate::boolean::server_key::PackedBinaryBooleanGates;
+
+    for encryption_key_choice in [EncryptionKeyChoice::Small, EncryptionKeyChoice::Big] {
+        let cks = ClientKey::new(&BooleanParameters {
+            encryption_key_choice,
+            ..DEMO_PARAMETERS
+        });
+        let sks = ServerKey::new(&cks);
+
+        for _ in 0..NB_TEST / 8 {
+            // a partial batch mixing encrypted and trivial operands
+            let len = 1 + random_integer() as usize % FPGA_BOOTSTRAP_PACKING;
+            let gates: Vec<Gate> = (0..len)
+                .map(|_| match random_integer() % 3 {
+                    0 => Gate::AND,
+                    1 => Gate::OR,
+                    _ => Gate::XOR,
+                })
+                .collect();
+            let lhs: Vec<bool> = (0..len).map(|_| random_boolean()).collect();
+            let rhs: Vec<bool> = (0..len).map(|_| random_boolean()).collect();
+            let ct_lhs: Vec<_> = lhs
+                .iter()
+                .map(|&b| random_enum_encryption(&cks, &sks, b))
+                .collect();
+            let ct_rhs: Vec<_> = rhs
+                .iter()
+                .map(|&b| random_enum_encryption(&cks, &sks, b))
+                .collect();
+
+            let ct_res = sks.gates_packed(&gates, &ct_lhs, &ct_rhs);
+            assert_eq!(ct_res.len(), len);
+            for (i, ct) in ct_res.iter().enumerate() {
+                let expected = match gates[i] {
+                    Gate::AND => lhs[i] && rhs[i],
+                    Gate::OR => lhs[i] || rhs[i],
+                    Gate::XOR => lhs[i] ^ rhs[i],
+                };
+                assert_eq!(cks.decrypt(ct), expected);
+                // the results of two trivial operands are trivial
+                if let (Ciphertext::Trivial(_), Ciphertext::Trivial(_)) = (&ct_lhs[i], &ct_rhs[i]) {
+                    assert!(matches!(ct, Ciphertext::Trivial(_)));
+                }
+            }
+        }
+
+        // a batch of trivial operands is not bootstrapped
+        let trivial = vec![sks.trivial_encrypt(true); 3];
+        sks.reset_packed_bootstrap_stats();
+        let ct_res = sks.xor_packed(&trivial, &trivial);
+        assert!(ct_res.iter().all(|ct| !cks.decrypt(ct)));
+        assert_eq!(sks.packed_bootstrap_stats().batches, 0);
+    }
+}
+
+#[test]
+#[should_panic(expected = "The right operands and the gates have different lengths")]
+fn test_packed_gates_short_right_operands() {
+    use crate::boolean::engine::Gate;
+    use crate::boolean::parameters::DEMO_PARAMETERS;
+    use crate::boolean::server_key::PackedBinaryBooleanGates;
+
+    let cks = ClientKey::new(&DEMO_PARAMETERS);
+    let sks = ServerKey::new(&cks);
+    let cts: Vec<_> = (0..3).map(|_| cks.encrypt(random_boolean())).collect();
+    let _ = sks.gates_packed(&[Gate::AND, Gate::OR, Gate::XOR], &cts, &cts[..2]);
+}
+
+#[test]
+#[should_panic(expected = "The right operands and the gates have different lengths")]
+fn test_and_packed_short_right_operands() {
+    use crate::boolean::parameters::DEMO_PARAMETERS;
+    use crate::boolean::server_key::PackedBinaryBooleanGates;
+
+    let cks = ClientKey::new(&DEMO_PARAMETERS);
+    let sks = ServerKey::new(&cks);
+    let cts: Vec<_> = (0..3).map(|_| cks.encrypt(random_boolean())).collect();
+    let _ = sks.and_packed(&cts, &cts[..2]);
+}
@@ -3,7 +3,13 @@ use crate::c_api::utils::*;
 use std::os::raw::c_int;
 
 use crate::boolean;
-use crate::boolean::server_key::{BinaryBooleanGates, BinaryBooleanGatesAssign};
+use crate::boolean::engine::Gate;
+use crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING;
+#[cfg(feature = "fpga")]
+use crate::boolean::server_key::FpgaGates;
+use crate::boolean::server_key::{
+    BinaryBooleanGates, BinaryBooleanGatesAssign, PackedBinaryBooleanGates,
+};
 
 use super::BooleanCiphertext;
 
@@ -686,3 +692,205 @@ pub unsafe extern "C" fn boolean_decompress_server_key(
         *result = Box::into_raw(heap_allocated_public_key);
     })
 }
+
+/// Identifiers of the gates of [`boolean_server_key_gates_packed`].
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum BooleanGate {
+    BooleanGateAnd = 0,
+    BooleanGateOr = 1,
+    BooleanGateXor = 2,
+}
+
+fn gate_from_id(gate_id: u32) -> Result<Gate, String> {
+    match gate_id {
+        id if id == BooleanGate::BooleanGateAnd as u32 => Ok(Gate::AND),
+        id if id == BooleanGate::BooleanGateOr as u32 => Ok(Gate::OR),
+        id if id == BooleanGate::BooleanGateXor as u32 => Ok(Gate::XOR),
+        id => Err(format!("invalid gate id: {id}")),
+    }
+}
+
+unsafe fn ciphertexts_from_array(
+    cts: *const *const BooleanCiphertext,
+    len: usize,
+) -> Vec<boolean::ciphertext::Ciphertext> {
+    check_ptr_is_non_null_and_aligned(cts).unwrap();
+    std::slice::from_raw_parts(cts, len)
+        .iter()
+        .map(|&ct| get_ref_checked(ct).unwrap().0.clone())
+        .collect()
+}
+
+/// Compute `gate_ids[i]` on `cts_left[i]` and `cts_right[i]` for every `i` below `len`, and
+/// write the resulting ciphertexts in the `len` slots of `results`.
+///
+/// The bootstraps are packed in batches of at most [`boolean_fpga_bootstrap_packing`] gates,
+/// computed by the FPGA when it is enabled for the calling thread.
+#[no_mangle]
+pub unsafe extern "C" fn boolean_server_key_gates_packed(
+    server_key: *const BooleanServerKey,
+    gate_ids: *const u32,
+    cts_left: *const *const BooleanCiphertext,
+    cts_right: *const *const BooleanCiphertext,
+    len: usize,
+    results: *mut *mut BooleanCiphertext,
+) -> c_int {
+    catch_panic(|| {
+        check_ptr_is_non_null_and_aligned(results).unwrap();
+        let results = std::slice::from_raw_parts_mut(results, len);
+
+        // First fill the results with null ptrs so that if we fail and the return code is not
+        // checked, then any access to the results will segfault (mimics malloc on failure)
+        results.fill(std::ptr::null_mut());
+
+        let server_key = get_ref_checked(server_key).unwrap();
+        check_ptr_is_non_null_and_aligned(gate_ids).unwrap();
+        let gates = std::slice::from_raw_parts(gate_ids, len)
+            .iter()
+            .map(|&gate_id| gate_from_id(gate_id))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let cts_left = ciphertexts_from_array(cts_left, len);
+        let cts_right = ciphertexts_from_array(cts_right, len);
+
+        let outputs = server_key.0.gates_packed(&gates, &cts_left, &cts_right);
+
+        for (result, output) in results.iter_mut().zip(outputs) {
+            *result = Box::into_raw(Box::new(BooleanCiphertext(output)));
+        }
+    })
+}
+
+/// Write in `result` the number of bootstraps in a full batch of
+/// [`boolean_server_key_gates_packed`], the capacity of the FPGA.
+#[no_mangle]
+pub unsafe extern "C" fn boolean_fpga_bootstrap_packing(result: *mut usize) -> c_int {
+    catch_panic(|| {
+        check_ptr_is_non_null_and_aligned(result).unwrap();
+
+        *result = FPGA_BOOTSTRAP_PACKING;
+    })
+}
+
+/// Counters of the packed bootstraps computed by the calling thread.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct BooleanPackedBootstrapStats {
+    pub batches: u64,
+    pub bootstraps: u64,
+    pub fpga_batches: u64,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn boolean_server_key_packed_bootstrap_stats(
+    server_key: *const BooleanServerKey,
+    result: *mut BooleanPackedBootstrapStats,
+) -> c_int {
+    catch_panic(|| {
+        check_ptr_is_non_null_and_aligned(result).unwrap();
+
+        let server_key = get_ref_checked(server_key).unwrap();
+        let stats = server_key.0.packed_bootstrap_stats();
+
+        *result = BooleanPackedBootstrapStats {
+            batches: stats.batches,
+            bootstraps: stats.bootstraps,
+            fpga_batches: stats.fpga_batches,
+        };
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn boolean_server_key_reset_packed_bootstrap_stats(
+    server_key: *const BooleanServerKey,
+) -> c_int {
+    catch_panic(|| {
+        let server_key = get_ref_checked(server_key).unwrap();
+
+        server_key.0.reset_packed_bootstrap_stats();
+    })
+}
+
+/// Codes returned by [`boolean_server_key_enable_fpga`] when the FPGA cannot be enabled, in
+/// addition to 1 for invalid arguments.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub enum BooleanFpgaError {
+    /// The library was built without the `fpga` feature.
+    BooleanFpgaErrorNotSupported = 2,
+    /// The FPGA_IMAGE or FPGA_INDEX environment variable is missing or invalid.
+    BooleanFpgaErrorDeviceNotConfigured = 3,
+    /// An XRT call failed while opening or driving the device.
+    BooleanFpgaErrorDeviceFailure = 4,
+    /// The parameters of the key do not match the FPGA image, or the key was computed from
+    /// another server key.
+    BooleanFpgaErrorIncompatibleKey = 5,
+    /// All the key slots of the device are in use.
+    BooleanFpgaErrorKeySlotsFull = 6,
+}
+
+#[cfg(feature = "fpga")]
+impl From<boolean::server_key::FpgaKeyError> for BooleanFpgaError {
+    fn from(error: boolean::server_key::FpgaKeyError) -> Self {
+        use boolean::server_key::FpgaKeyError;
+        match error {
+            FpgaKeyError::DeviceNotConfigured { .. } => Self::BooleanFpgaErrorDeviceNotConfigured,
+            FpgaKeyError::DeviceFailure { .. } | FpgaKeyError::KeyNotResident { .. } => {
+                Self::BooleanFpgaErrorDeviceFailure
+            }
+            FpgaKeyError::KeySlotsFull { .. } => Self::BooleanFpgaErrorKeySlotsFull,
+            FpgaKeyError::UnsupportedVersion { .. }
+            | FpgaKeyError::GeometryMismatch { .. }
+            | FpgaKeyError::WidthsMismatch { .. }
+            | FpgaKeyError::FingerprintMismatch { .. }
+            | FpgaKeyError::SourceFingerprintMismatch { .. }
+            | FpgaKeyError::InvalidImageSize { .. } => Self::BooleanFpgaErrorIncompatibleKey,
+        }
+    }
+}
+
+/// Upload the bootstrapping key of `server_key` and bootstrap the packed gates of the calling
+/// thread on the FPGA.
+///
+/// Returns 0 on success, 1 for invalid arguments, and a [`BooleanFpgaError`] when the FPGA cannot
+/// be enabled, in which case the packed gates keep running on the CPU.
+#[no_mangle]
+pub unsafe extern "C" fn boolean_server_key_enable_fpga(
+    server_key: *const BooleanServerKey,
+) -> c_int {
+    let mut code = 0;
+    let panicked = catch_panic(|| {
+        let server_key = get_ref_checked(server_key).unwrap();
+
+        #[cfg(feature = "fpga")]
+        if let Err(error) = server_key.0.enable_fpga() {
+            code = BooleanFpgaError::from(error) as c_int;
+        }
+        #[cfg(not(feature = "fpga"))]
+        {
+            let _ = server_key;
+            code = BooleanFpgaError::BooleanFpgaErrorNotSupported as c_int;
+        }
+    });
+    if panicked != 0 {
+        panicked
+    } else {
+        code
+    }
+}
+
+/// Release the FPGA of the calling thread, the packed gates then run on the CPU.
+#[no_mangle]
+pub unsafe extern "C" fn boolean_server_key_disable_fpga(
+    server_key: *const BooleanServerKey,
+) -> c_int {
+    catch_panic(|| {
+        let server_key = get_ref_checked(server_key).unwrap();
+
+        #[cfg(feature = "fpga")]
+        server_key.0.disable_fpga();
+        #[cfg(not(feature = "fpga"))]
+        let _ = server_key;
+    })
+}
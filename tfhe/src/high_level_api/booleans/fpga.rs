@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::boolean::engine::{BooleanEngine, WithThreadLocalEngine};
+use crate::high_level_api::ServerKey;
+
+/// How the [FheBool] and [FheBoolVec] operations use the FPGA accelerator.
+///
+/// The accelerator is the card `FPGA_INDEX` loaded with the image `FPGA_IMAGE`, read from the
+/// environment of the thread calling [set_server_key].
+///
+/// The config is a choice of the server, which is not serialized with the keys: it is set on the
+/// server key returned by [generate_keys] from the
+/// [enable_fpga_bool](crate::high_level_api::ConfigBuilder::enable_fpga_bool) config, or with
+/// [ServerKey::with_fpga_bool] on a key the server received.
+///
+/// # Warning
+///
+/// The accelerator only bootstraps with the insecure [DEMO_PARAMETERS], whose LWE dimension is
+/// 16, and [enable_fpga_bool](crate::high_level_api::ConfigBuilder::enable_fpga_bool) switches the
+/// [FheBool] parameters to them. Do not encrypt anything confidential with such a config.
+///
+/// [FheBool]: crate::high_level_api::FheBool
+/// [FheBoolVec]: crate::high_level_api::FheBoolVec
+/// [set_server_key]: crate::high_level_api::set_server_key
+/// [generate_keys]: crate::high_level_api::generate_keys
+/// [DEMO_PARAMETERS]: crate::boolean::parameters::DEMO_PARAMETERS
+#[cfg_attr(all(doc, not(doctest)), cfg(feature = "fpga"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpgaConfig {
+    /// Compute on the CPU when the accelerator cannot be opened or cannot bootstrap with the
+    /// key, instead of panicking in [set_server_key].
+    ///
+    /// [set_server_key]: crate::high_level_api::set_server_key
+    pub fallback_to_cpu: bool,
+}
+
+impl FpgaConfig {
+    /// A config with which [set_server_key] panics when the accelerator cannot be used.
+    ///
+    /// [set_server_key]: crate::high_level_api::set_server_key
+    pub fn required() -> Self {
+        Self {
+            fallback_to_cpu: false,
+        }
+    }
+}
+
+impl Default for FpgaConfig {
+    fn default() -> Self {
+        Self {
+            fallback_to_cpu: true,
+        }
+    }
+}
+
+impl ServerKey {
+    /// Make [set_server_key] route the [FheBool] operations to the FPGA accelerator, as `fpga`
+    /// asks.
+    ///
+    /// A deserialized or decompressed key computes on the CPU until this is called.
+    ///
+    /// [FheBool]: crate::high_level_api::FheBool
+    /// [set_server_key]: crate::high_level_api::set_server_key
+    pub fn with_fpga_bool(mut self, fpga: FpgaConfig) -> Self {
+        self.fpga_bool = Some(fpga);
+        self
+    }
+
+    /// Route the bootstraps of this thread to the FPGA if the key has an [FpgaConfig], otherwise
+    /// disable the FPGA of the thread, which must not keep computing with the key previously set.
+    pub(crate) fn activate_fpga_bool(&self) {
+        match (self.boolean_key.bool_key.as_ref(), self.fpga_bool) {
+            (Some(key), Some(fpga)) => key.activate_fpga(fpga),
+            _ => BooleanEngine::with_thread_local_mut(|engine| engine.bootstrapper.disable_fpga()),
+        }
+    }
+}
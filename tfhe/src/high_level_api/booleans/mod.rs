@@ -1,11 +1,15 @@
+#[cfg(feature = "fpga")]
+pub use fpga::FpgaConfig;
 pub(crate) use keys::{
     BooleanClientKey, BooleanCompressedPublicKey, BooleanCompressedServerKey, BooleanConfig,
     BooleanPublicKey, BooleanServerKey,
 };
 pub use parameters::FheBoolParameters;
-pub use types::{CompressedFheBool, FheBool};
+pub use types::{CompressedFheBool, FheBool, FheBoolVec};
 
 mod client_key;
+#[cfg(feature = "fpga")]
+mod fpga;
 mod keys;
 mod public_key;
 mod server_key;
@@ -1,6 +1,13 @@
 use super::client_key::FheBoolClientKey;
 use super::types::FheBool;
-use crate::boolean::server_key::{BinaryBooleanGates, CompressedServerKey, ServerKey};
+#[cfg(feature = "fpga")]
+use super::FpgaConfig;
+use crate::boolean::engine::Gate;
+#[cfg(feature = "fpga")]
+use crate::boolean::server_key::FpgaGates;
+use crate::boolean::server_key::{
+    BinaryBooleanGates, CompressedServerKey, PackedBinaryBooleanGates, ServerKey,
+};
 
 #[cfg_attr(all(doc, not(doctest)), cfg(feature = "boolean"))]
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -15,17 +22,64 @@ impl FheBoolServerKey {
         }
     }
 
+    /// Route the bootstraps of this thread to the FPGA, as `fpga` asks.
+    ///
+    /// When the accelerator cannot be used, the FPGA of the thread is disabled and the
+    /// operations run on the CPU, unless the config requires the accelerator.
+    #[cfg(feature = "fpga")]
+    pub(in crate::high_level_api::booleans) fn activate_fpga(&self, fpga: FpgaConfig) {
+        if let Err(err) = self.key.enable_fpga() {
+            assert!(
+                fpga.fallback_to_cpu,
+                "The FPGA cannot compute the FheBool operations: {err}"
+            );
+            self.key.disable_fpga();
+        }
+    }
+
+    /// Compute `gate` as a packed batch of one when the FPGA of this thread is enabled, so that
+    /// the operators of [FheBool] run on the accelerator like the ones of
+    /// [FheBoolVec](crate::high_level_api::FheBoolVec).
+    #[cfg(feature = "fpga")]
+    fn gate_on_fpga(&self, gate: Gate, lhs: &FheBool, rhs: &FheBool) -> Option<FheBool> {
+        if !self.key.fpga_enabled() {
+            return None;
+        }
+        let ciphertext = self
+            .key
+            .gates_packed(
+                &[gate],
+                std::slice::from_ref(&lhs.ciphertext),
+                std::slice::from_ref(&rhs.ciphertext),
+            )
+            .pop()
+            .unwrap();
+        Some(FheBool::new(ciphertext))
+    }
+
     pub(in crate::high_level_api::booleans) fn and(&self, lhs: &FheBool, rhs: &FheBool) -> FheBool {
+        #[cfg(feature = "fpga")]
+        if let Some(result) = self.gate_on_fpga(Gate::AND, lhs, rhs) {
+            return result;
+        }
         let ciphertext = self.key.and(&lhs.ciphertext, &rhs.ciphertext);
         FheBool::new(ciphertext)
     }
 
     pub(in crate::high_level_api::booleans) fn or(&self, lhs: &FheBool, rhs: &FheBool) -> FheBool {
+        #[cfg(feature = "fpga")]
+        if let Some(result) = self.gate_on_fpga(Gate::OR, lhs, rhs) {
+            return result;
+        }
         let ciphertext = self.key.or(&lhs.ciphertext, &rhs.ciphertext);
         FheBool::new(ciphertext)
     }
 
     pub(in crate::high_level_api::booleans) fn xor(&self, lhs: &FheBool, rhs: &FheBool) -> FheBool {
+        #[cfg(feature = "fpga")]
+        if let Some(result) = self.gate_on_fpga(Gate::XOR, lhs, rhs) {
+            return result;
+        }
         let ciphertext = self.key.xor(&lhs.ciphertext, &rhs.ciphertext);
         FheBool::new(ciphertext)
     }
@@ -35,6 +89,11 @@ impl FheBoolServerKey {
         lhs: &FheBool,
         rhs: &FheBool,
     ) -> FheBool {
+        // The negation of a ciphertext does not bootstrap
+        #[cfg(feature = "fpga")]
+        if let Some(result) = self.gate_on_fpga(Gate::XOR, lhs, rhs) {
+            return self.not(&result);
+        }
         let ciphertext = self.key.xnor(&lhs.ciphertext, &rhs.ciphertext);
         FheBool::new(ciphertext)
     }
@@ -44,10 +103,49 @@ impl FheBoolServerKey {
         lhs: &FheBool,
         rhs: &FheBool,
     ) -> FheBool {
+        // The negation of a ciphertext does not bootstrap
+        #[cfg(feature = "fpga")]
+        if let Some(result) = self.gate_on_fpga(Gate::AND, lhs, rhs) {
+            return self.not(&result);
+        }
         let ciphertext = self.key.nand(&lhs.ciphertext, &rhs.ciphertext);
         FheBool::new(ciphertext)
     }
 
+    /// Element-wise gates, bootstrapped in packed batches of at most
+    /// [FPGA_BOOTSTRAP_PACKING] gates.
+    ///
+    /// [FPGA_BOOTSTRAP_PACKING]: crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING
+    pub(in crate::high_level_api::booleans) fn gates_packed(
+        &self,
+        gates: &[Gate],
+        lhs: &[FheBool],
+        rhs: &[FheBool],
+    ) -> Vec<FheBool> {
+        assert_eq!(
+            lhs.len(),
+            gates.len(),
+            "The left operands and the gates have different lengths"
+        );
+        assert_eq!(
+            rhs.len(),
+            gates.len(),
+            "The right operands and the gates have different lengths"
+        );
+
+        let ciphertexts = |operands: &[FheBool]| -> Vec<_> {
+            operands
+                .iter()
+                .map(|operand| operand.ciphertext.clone())
+                .collect()
+        };
+        self.key
+            .gates_packed(gates, &ciphertexts(lhs), &ciphertexts(rhs))
+            .into_iter()
+            .map(FheBool::new)
+            .collect()
+    }
+
     pub(in crate::high_level_api::booleans) fn not(&self, lhs: &FheBool) -> FheBool {
         let ciphertext = self.key.not(&lhs.ciphertext);
         FheBool::new(ciphertext)
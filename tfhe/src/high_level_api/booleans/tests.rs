@@ -5,11 +5,19 @@
 #![allow(clippy::bool_assert_comparison)]
 use std::ops::{BitAnd, BitOr, BitXor, Not};
 
+#[cfg(feature = "fpga")]
+use crate::boolean::parameters::DEMO_PARAMETERS;
+use crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING;
+#[cfg(feature = "fpga")]
+use crate::boolean::server_key::FpgaGates;
+use crate::high_level_api::global_state::with_internal_keys;
 use crate::high_level_api::prelude::*;
 use crate::high_level_api::{
     generate_keys, set_server_key, ClientKey, CompressedFheBool, ConfigBuilder, FheBool,
-    FheBoolParameters,
+    FheBoolParameters, FheBoolVec,
 };
+#[cfg(feature = "fpga")]
+use crate::high_level_api::{CompressedServerKey, FpgaConfig, ServerKey};
 use crate::CompressedPublicKey;
 
 fn setup_static_default() -> ClientKey {
@@ -227,3 +235,211 @@ fn test_decompressed_public_key_encrypt() {
     let clear: bool = a.decrypt(&client_key);
     assert_eq!(clear, true);
 }
+
+#[test]
+fn test_fhe_bool_vec_operations() {
+    let keys = setup_static_default();
+
+    // two full batches and a partial one
+    let len = 2 * FPGA_BOOTSTRAP_PACKING + 3;
+    let clear_a: Vec<bool> = (0..len).map(|i| i % 2 == 0).collect();
+    let clear_b: Vec<bool> = (0..len).map(|i| i % 3 == 0).collect();
+    let clear_c: Vec<bool> = (0..len).map(|i| i % 5 < 2).collect();
+    let a = FheBoolVec::encrypt(clear_a.as_slice(), &keys);
+    let b = FheBoolVec::encrypt(clear_b.as_slice(), &keys);
+    let c = FheBoolVec::encrypt(clear_c.as_slice(), &keys);
+
+    let expected = |f: fn(bool, bool, bool) -> bool| -> Vec<bool> {
+        (0..len)
+            .map(|i| f(clear_a[i], clear_b[i], clear_c[i]))
+            .collect()
+    };
+
+    let stats = with_internal_keys(|server_keys| {
+        let server_key = &server_keys.boolean_key.bool_key.as_ref().unwrap().key;
+        server_key.reset_packed_bootstrap_stats();
+        let result = &a & &b;
+        let stats = server_key.packed_bootstrap_stats();
+        assert_eq!(result.decrypt(&keys), expected(|a, b, _| a & b));
+        stats
+    });
+    assert_eq!(stats.batches, 3);
+    assert_eq!(stats.bootstraps, len as u64);
+
+    assert_eq!((&a | &b).decrypt(&keys), expected(|a, b, _| a | b));
+    assert_eq!((&a ^ &b).decrypt(&keys), expected(|a, b, _| a ^ b));
+    assert_eq!((!&a).decrypt(&keys), expected(|a, _, _| !a));
+    assert_eq!(
+        c.mux(&a, &b).decrypt(&keys),
+        expected(|a, b, c| if c { a } else { b })
+    );
+
+    // a trivial operand is bootstrapped with the encrypted one
+    let trivial_b = FheBoolVec::encrypt_trivial(clear_b.as_slice());
+    assert_eq!((&a ^ &trivial_b).decrypt(&keys), expected(|a, b, _| a ^ b));
+
+    // elements with two trivial operands are computed without bootstrap
+    let trivial_a = FheBoolVec::encrypt_trivial(clear_a.as_slice());
+    let stats = with_internal_keys(|server_keys| {
+        let server_key = &server_keys.boolean_key.bool_key.as_ref().unwrap().key;
+        server_key.reset_packed_bootstrap_stats();
+        let result = &trivial_a ^ &trivial_b;
+        assert_eq!(result.decrypt(&keys), expected(|a, b, _| a ^ b));
+        server_key.packed_bootstrap_stats()
+    });
+    assert_eq!(stats.batches, 0);
+    assert_eq!(stats.bootstraps, 0);
+}
+
+#[test]
+#[should_panic(expected = "The FheBoolVec operands have different lengths")]
+fn test_fhe_bool_vec_lengths() {
+    let keys = setup_static_default();
+
+    let a = FheBoolVec::encrypt([true, false].as_slice(), &keys);
+    let b = FheBoolVec::encrypt([true].as_slice(), &keys);
+    let _ = a & b;
+}
+
+#[cfg(feature = "fpga")]
+fn setup_fpga_default_parameters(fpga: FpgaConfig) -> ClientKey {
+    // The accelerator cannot bootstrap with the default parameters
+    let config = ConfigBuilder::all_disabled()
+        .enable_fpga_bool(fpga)
+        .enable_default_bool()
+        .build();
+
+    let (my_keys, server_keys) = generate_keys(config);
+
+    set_server_key(server_keys);
+    my_keys
+}
+
+#[cfg(feature = "fpga")]
+#[test]
+fn test_fpga_config() {
+    let config = ConfigBuilder::all_disabled()
+        .enable_fpga_bool(FpgaConfig::required())
+        .build();
+    assert_eq!(config.fpga_bool, Some(FpgaConfig::required()));
+    assert_eq!(
+        config.boolean_config.bool_params,
+        Some(FheBoolParameters::from_static(&DEMO_PARAMETERS))
+    );
+
+    // The accelerator is a choice of the server, which does not travel with the keys
+    let (client_key, server_key) = generate_keys(config);
+    assert_eq!(server_key.fpga_bool, Some(FpgaConfig::required()));
+    let serialized = bincode::serialize(&server_key).unwrap();
+    let server_key: ServerKey = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(server_key.fpga_bool, None);
+    let server_key = CompressedServerKey::new(&client_key).decompress();
+    assert_eq!(server_key.fpga_bool, None);
+    let server_key = server_key.with_fpga_bool(FpgaConfig::default());
+    assert_eq!(server_key.fpga_bool, Some(FpgaConfig::default()));
+
+    let config = ConfigBuilder::all_disabled()
+        .enable_fpga_bool(FpgaConfig::default())
+        .disable_bool()
+        .build();
+    assert_eq!(config.fpga_bool, None);
+}
+
+#[cfg(feature = "fpga")]
+#[test]
+fn test_fpga_fallback_to_cpu() {
+    let keys = setup_fpga_default_parameters(FpgaConfig::default());
+    assert!(!with_internal_keys(|server_keys| {
+        server_keys
+            .boolean_key
+            .bool_key
+            .as_ref()
+            .unwrap()
+            .key
+            .fpga_enabled()
+    }));
+
+    let ttrue = FheBool::encrypt(true, &keys);
+    let ffalse = FheBool::encrypt(false, &keys);
+
+    xor_truth_table(&ttrue, &ffalse, &keys);
+    and_truth_table(&ttrue, &ffalse, &keys);
+    or_truth_table(&ttrue, &ffalse, &keys);
+    assert_eq!(ttrue.nand(&ttrue).decrypt(&keys), false);
+}
+
+#[cfg(feature = "fpga")]
+#[test]
+#[should_panic(expected = "The FPGA cannot compute the FheBool operations")]
+fn test_fpga_required() {
+    setup_fpga_default_parameters(FpgaConfig::required());
+}
+
+#[cfg(feature = "__xrt_mock")]
+fn fpga_enabled() -> bool {
+    with_internal_keys(|server_keys| {
+        server_keys
+            .boolean_key
+            .bool_key
+            .as_ref()
+            .map_or(false, |key| key.key.fpga_enabled())
+    })
+}
+
+#[cfg(feature = "__xrt_mock")]
+#[test]
+fn test_fpga_runs_fhe_bool_operations() {
+    let _card = xrt_mock::lock();
+    std::env::set_var("FPGA_IMAGE", "accel.xclbin");
+    std::env::set_var("FPGA_INDEX", "0");
+    let config = ConfigBuilder::all_disabled()
+        .enable_fpga_bool(FpgaConfig::required())
+        .build();
+    let (keys, server_keys) = generate_keys(config);
+    set_server_key(server_keys);
+    assert!(fpga_enabled());
+
+    let a = FheBool::encrypt(true, &keys);
+    let b = FheBool::encrypt(false, &keys);
+    let lhs = FheBoolVec::encrypt([true, true, false].as_slice(), &keys);
+    let rhs = FheBoolVec::encrypt([true, false, false].as_slice(), &keys);
+
+    // The fake card does not compute: only where the bootstraps run is checked
+    let (scalar_stats, packed_stats) = with_internal_keys(|server_keys| {
+        let server_key = &server_keys.boolean_key.bool_key.as_ref().unwrap().key;
+        server_key.reset_packed_bootstrap_stats();
+        let _ = [&a & &b, &a | &b, &a ^ &b, a.nand(&b)];
+        let scalar_stats = server_key.packed_bootstrap_stats();
+        server_key.reset_packed_bootstrap_stats();
+        let _ = &lhs & &rhs;
+        let packed_stats = server_key.packed_bootstrap_stats();
+        server_key.disable_fpga();
+        (scalar_stats, packed_stats)
+    });
+
+    // A single operation is a batch of one
+    assert_eq!(scalar_stats.bootstraps, 4);
+    assert_eq!(scalar_stats.fpga_batches, 4);
+
+    assert_eq!(packed_stats.bootstraps, 3);
+    assert_eq!(packed_stats.fpga_batches, 1);
+}
+
+#[cfg(feature = "__xrt_mock")]
+#[test]
+fn test_set_server_key_without_fpga_disables_it() {
+    let _card = xrt_mock::lock();
+    std::env::set_var("FPGA_IMAGE", "accel.xclbin");
+    std::env::set_var("FPGA_INDEX", "0");
+    let config = ConfigBuilder::all_disabled()
+        .enable_fpga_bool(FpgaConfig::required())
+        .build();
+    let (client_key, server_keys) = generate_keys(config);
+    set_server_key(server_keys);
+    assert!(fpga_enabled());
+
+    // The same key received by another server, which does not choose the accelerator
+    set_server_key(client_key.generate_server_key());
+    assert!(!fpga_enabled());
+    assert_eq!(xrt_mock::open_handles(), Vec::<&str>::new());
+}
@@ -17,7 +17,7 @@ use crate::high_level_api::traits::{
 use super::static_::{FheBoolClientKey, FheBoolPublicKey, FheBoolServerKey};
 
 #[derive(Default, Copy, Clone, Serialize, Deserialize)]
-struct FheBoolId;
+pub(super) struct FheBoolId;
 
 impl_with_global_key!(
     for FheBoolId {
@@ -1,4 +1,6 @@
 pub use base::{CompressedFheBool, FheBool};
+pub use vec::FheBoolVec;
 
 mod base;
 pub(in crate::high_level_api) mod static_;
+mod vec;
@@ -0,0 +1,216 @@
+use std::borrow::Borrow;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use serde::{Deserialize, Serialize};
+
+use crate::boolean::engine::Gate;
+use crate::high_level_api::global_state::WithGlobalKey;
+use crate::high_level_api::keys::ClientKey;
+use crate::high_level_api::traits::{
+    FheDecrypt, FheTrivialEncrypt, FheTryEncrypt, FheTryTrivialEncrypt,
+};
+
+use super::base::{FheBool, FheBoolId};
+
+/// A vector of [FheBool], whose element-wise operations are bootstrapped in packed batches.
+///
+/// The binary operations and [FheBoolVec::mux] group the bootstraps of the elements in batches
+/// of [FPGA_BOOTSTRAP_PACKING], each bootstrapped by a single call to the accelerator when the
+/// config enables it with [enable_fpga_bool].
+///
+/// [FPGA_BOOTSTRAP_PACKING]: crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING
+/// [enable_fpga_bool]: crate::high_level_api::ConfigBuilder::enable_fpga_bool
+///
+/// # Example
+/// ```rust
+/// use tfhe::prelude::*;
+/// use tfhe::{generate_keys, set_server_key, ConfigBuilder, FheBoolVec};
+///
+/// let config = ConfigBuilder::all_disabled().enable_default_bool().build();
+/// let (client_key, server_key) = generate_keys(config);
+///
+/// let a = FheBoolVec::encrypt([true, true, false].as_slice(), &client_key);
+/// let b = FheBoolVec::encrypt([true, false, false].as_slice(), &client_key);
+///
+/// set_server_key(server_key);
+///
+/// let fhe_result = &a & &b;
+///
+/// let clear_result: Vec<bool> = fhe_result.decrypt(&client_key);
+/// assert_eq!(clear_result, vec![true, false, false]);
+/// ```
+#[cfg_attr(all(doc, not(doctest)), cfg(feature = "boolean"))]
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FheBoolVec {
+    elements: Vec<FheBool>,
+}
+
+impl FheBoolVec {
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[FheBool] {
+        &self.elements
+    }
+
+    pub fn into_vec(self) -> Vec<FheBool> {
+        self.elements
+    }
+
+    /// Element-wise selection: `then_result` where `self` is true, `else_result` elsewhere.
+    ///
+    /// Computed with two batches of bootstraps per [FPGA_BOOTSTRAP_PACKING] elements.
+    ///
+    /// [FPGA_BOOTSTRAP_PACKING]: crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING
+    pub fn mux(&self, then_result: &Self, else_result: &Self) -> Self {
+        self.assert_same_len(then_result);
+        self.assert_same_len(else_result);
+        let len = self.len();
+
+        FheBoolId.with_unwrapped_global(|key| {
+            // (condition & then) and (!condition & else) in the same batches
+            let not_conditions: Vec<FheBool> = self.elements.iter().map(|c| key.not(c)).collect();
+            let conditions = [self.elements.as_slice(), not_conditions.as_slice()].concat();
+            let results = [then_result.as_slice(), else_result.as_slice()].concat();
+            let products = key.gates_packed(&vec![Gate::AND; 2 * len], &conditions, &results);
+
+            let (then_products, else_products) = products.split_at(len);
+            key.gates_packed(&vec![Gate::OR; len], then_products, else_products)
+                .into()
+        })
+    }
+
+    // the same gate on every pair of elements
+    fn gates(&self, gate: Gate, rhs: &Self) -> Self {
+        self.assert_same_len(rhs);
+        let gates = vec![gate; self.len()];
+        FheBoolId
+            .with_unwrapped_global(|key| key.gates_packed(&gates, &self.elements, &rhs.elements))
+            .into()
+    }
+
+    fn assert_same_len(&self, other: &Self) {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "The FheBoolVec operands have different lengths"
+        );
+    }
+}
+
+impl From<Vec<FheBool>> for FheBoolVec {
+    fn from(elements: Vec<FheBool>) -> Self {
+        Self { elements }
+    }
+}
+
+impl From<FheBoolVec> for Vec<FheBool> {
+    fn from(value: FheBoolVec) -> Self {
+        value.elements
+    }
+}
+
+impl FromIterator<FheBool> for FheBoolVec {
+    fn from_iter<I: IntoIterator<Item = FheBool>>(iter: I) -> Self {
+        Self {
+            elements: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for FheBoolVec {
+    type Item = FheBool;
+    type IntoIter = std::vec::IntoIter<FheBool>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+impl FheTryEncrypt<&[bool], ClientKey> for FheBoolVec {
+    type Error = crate::high_level_api::errors::Error;
+
+    fn try_encrypt(values: &[bool], key: &ClientKey) -> Result<Self, Self::Error> {
+        values
+            .iter()
+            .map(|&value| FheBool::try_encrypt(value, key))
+            .collect()
+    }
+}
+
+impl FheTryTrivialEncrypt<&[bool]> for FheBoolVec {
+    type Error = crate::high_level_api::errors::Error;
+
+    fn try_encrypt_trivial(values: &[bool]) -> Result<Self, Self::Error> {
+        values
+            .iter()
+            .map(|&value| FheBool::try_encrypt_trivial(value))
+            .collect()
+    }
+}
+
+impl FheTrivialEncrypt<&[bool]> for FheBoolVec {
+    #[track_caller]
+    fn encrypt_trivial(values: &[bool]) -> Self {
+        Self::try_encrypt_trivial(values).unwrap()
+    }
+}
+
+impl FheDecrypt<Vec<bool>> for FheBoolVec {
+    #[track_caller]
+    fn decrypt(&self, key: &ClientKey) -> Vec<bool> {
+        self.elements
+            .iter()
+            .map(|element| element.decrypt(key))
+            .collect()
+    }
+}
+
+macro_rules! fhe_bool_vec_impl_operation(
+    ($trait_name:ident($trait_method:ident) => $gate:ident) => {
+        impl<B> $trait_name<B> for FheBoolVec
+        where B: Borrow<FheBoolVec>,
+        {
+            type Output = FheBoolVec;
+
+            fn $trait_method(self, rhs: B) -> Self::Output {
+                <&Self as $trait_name<B>>::$trait_method(&self, rhs)
+            }
+        }
+
+        impl<B> $trait_name<B> for &FheBoolVec
+        where B: Borrow<FheBoolVec>,
+        {
+            type Output = FheBoolVec;
+
+            fn $trait_method(self, rhs: B) -> Self::Output {
+                self.gates(Gate::$gate, rhs.borrow())
+            }
+        }
+    };
+);
+
+fhe_bool_vec_impl_operation!(BitAnd(bitand) => AND);
+fhe_bool_vec_impl_operation!(BitOr(bitor) => OR);
+fhe_bool_vec_impl_operation!(BitXor(bitxor) => XOR);
+
+impl Not for FheBoolVec {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        !&self
+    }
+}
+
+impl Not for &FheBoolVec {
+    type Output = FheBoolVec;
+
+    fn not(self) -> Self::Output {
+        FheBoolId.with_unwrapped_global(|key| self.elements.iter().map(|e| key.not(e)).collect())
+    }
+}
@@ -1,3 +1,7 @@
+#[cfg(all(feature = "boolean", feature = "fpga"))]
+use crate::boolean::parameters::DEMO_PARAMETERS;
+#[cfg(all(feature = "boolean", feature = "fpga"))]
+use crate::high_level_api::booleans::FpgaConfig;
 #[cfg(feature = "boolean")]
 use crate::high_level_api::booleans::{BooleanConfig, FheBoolParameters};
 #[cfg(feature = "integer")]
@@ -10,6 +14,8 @@ use crate::high_level_api::shortints::ShortIntConfig;
 pub struct Config {
     #[cfg(feature = "boolean")]
     pub(crate) boolean_config: BooleanConfig,
+    #[cfg(all(feature = "boolean", feature = "fpga"))]
+    pub(crate) fpga_bool: Option<FpgaConfig>,
     #[cfg(feature = "shortint")]
     pub(crate) shortint_config: ShortIntConfig,
     #[cfg(feature = "integer")]
@@ -49,6 +55,8 @@ impl ConfigBuilder {
             config: Config {
                 #[cfg(feature = "boolean")]
                 boolean_config: BooleanConfig::all_default(),
+                #[cfg(all(feature = "boolean", feature = "fpga"))]
+                fpga_bool: None,
                 #[cfg(feature = "shortint")]
                 shortint_config: ShortIntConfig::all_default(),
                 #[cfg(feature = "integer")]
@@ -63,6 +71,8 @@ impl ConfigBuilder {
             config: Config {
                 #[cfg(feature = "boolean")]
                 boolean_config: BooleanConfig::all_none(),
+                #[cfg(all(feature = "boolean", feature = "fpga"))]
+                fpga_bool: None,
                 #[cfg(feature = "shortint")]
                 shortint_config: ShortIntConfig::all_none(),
                 #[cfg(feature = "integer")]
@@ -83,9 +93,42 @@ impl ConfigBuilder {
         self
     }
 
+    /// Enable [FheBool] with the parameters of the FPGA accelerator, whose operations, including
+    /// the ones of [FheBoolVec], then run on the accelerator once the server key returned by
+    /// [generate_keys] is set with [set_server_key].
+    ///
+    /// # Warning
+    ///
+    /// This replaces the [FheBool] parameters with [DEMO_PARAMETERS], the only ones the
+    /// accelerator bootstraps with. Their LWE dimension is 16: they are for demonstrations and
+    /// tests only, and the ciphertexts they encrypt are **not secure**.
+    ///
+    /// The accelerator is not part of the keys: a server that deserializes the server key
+    /// chooses it with [ServerKey::with_fpga_bool].
+    ///
+    /// Setting other parameters afterwards keeps the config, but the accelerator then cannot
+    /// bootstrap with the key: see [FpgaConfig::fallback_to_cpu].
+    ///
+    /// [FheBool]: crate::high_level_api::FheBool
+    /// [FheBoolVec]: crate::high_level_api::FheBoolVec
+    /// [generate_keys]: crate::high_level_api::generate_keys
+    /// [set_server_key]: crate::high_level_api::set_server_key
+    /// [ServerKey::with_fpga_bool]: crate::high_level_api::ServerKey::with_fpga_bool
+    #[cfg(all(feature = "boolean", feature = "fpga"))]
+    pub fn enable_fpga_bool(mut self, fpga: FpgaConfig) -> Self {
+        self.config.boolean_config.bool_params =
+            Some(FheBoolParameters::from_static(&DEMO_PARAMETERS));
+        self.config.fpga_bool = Some(fpga);
+        self
+    }
+
     #[cfg(feature = "boolean")]
     pub fn disable_bool(mut self) -> Self {
         self.config.boolean_config.bool_params = None;
+        #[cfg(feature = "fpga")]
+        {
+            self.config.fpga_bool = None;
+        }
         self
     }
 
@@ -39,8 +39,7 @@ thread_local! {
 ///
 /// ```
 /// use std::thread;
-/// use tfhe;
-/// use tfhe::ConfigBuilder;
+/// use tfhe::{self, ConfigBuilder};
 ///
 /// # let config = tfhe::ConfigBuilder::all_disabled().build();
 /// let (client_key, server_key) = tfhe::generate_keys(config);
@@ -59,7 +58,13 @@ thread_local! {
 /// th2.join();
 /// th1.join();
 /// ```
+///
+/// With the `fpga` feature, a key with an [FpgaConfig](crate::high_level_api::FpgaConfig) also
+/// opens the accelerator for the thread and uploads its bootstrapping key. Setting a key without
+/// it closes the accelerator of the thread.
 pub fn set_server_key(keys: ServerKey) {
+    #[cfg(all(feature = "boolean", feature = "fpga"))]
+    keys.activate_fpga_bool();
     INTERNAL_KEYS.with(|internal_keys| internal_keys.replace_with(|_old| keys));
 }
 
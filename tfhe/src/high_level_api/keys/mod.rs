@@ -26,8 +26,17 @@ pub use server::{CompressedServerKey, ServerKey};
 /// # }
 /// ```
 pub fn generate_keys<C: Into<Config>>(config: C) -> (ClientKey, ServerKey) {
+    let config: Config = config.into();
+    #[cfg(all(feature = "boolean", feature = "fpga"))]
+    let fpga_bool = config.fpga_bool;
+
     let client_kc = ClientKey::generate(config);
-    let server_kc = client_kc.generate_server_key();
+    #[allow(unused_mut)]
+    let mut server_kc = client_kc.generate_server_key();
+    #[cfg(all(feature = "boolean", feature = "fpga"))]
+    {
+        server_kc.fpga_bool = fpga_bool;
+    }
 
     (client_kc, server_kc)
 }
@@ -1,3 +1,5 @@
+#[cfg(all(feature = "boolean", feature = "fpga"))]
+use crate::high_level_api::booleans::FpgaConfig;
 #[cfg(feature = "boolean")]
 use crate::high_level_api::booleans::{BooleanCompressedServerKey, BooleanServerKey};
 #[cfg(feature = "integer")]
@@ -24,6 +26,10 @@ use super::ClientKey;
 pub struct ServerKey {
     #[cfg(feature = "boolean")]
     pub(crate) boolean_key: Arc<BooleanServerKey>,
+    /// How the server computes the FheBool operations: this is not part of the key, and is not
+    /// serialized.
+    #[cfg(all(feature = "boolean", feature = "fpga"))]
+    pub(crate) fpga_bool: Option<FpgaConfig>,
     #[cfg(feature = "shortint")]
     pub(crate) shortint_key: Arc<ShortIntServerKey>,
     #[cfg(feature = "integer")]
@@ -35,6 +41,8 @@ impl ServerKey {
         Self {
             #[cfg(feature = "boolean")]
             boolean_key: Arc::new(BooleanServerKey::new(&keys.boolean_key)),
+            #[cfg(all(feature = "boolean", feature = "fpga"))]
+            fpga_bool: None,
             #[cfg(feature = "shortint")]
             shortint_key: Arc::new(ShortIntServerKey::new(&keys.shortint_key)),
             #[cfg(feature = "integer")]
@@ -108,6 +116,8 @@ impl<'de> serde::Deserialize<'de> for ServerKey {
         DeserializableServerKey::deserialize(deserializer).map(|deserialized| Self {
             #[cfg(feature = "boolean")]
             boolean_key: Arc::new(deserialized.boolean_key),
+            #[cfg(all(feature = "boolean", feature = "fpga"))]
+            fpga_bool: None,
             #[cfg(feature = "shortint")]
             shortint_key: Arc::new(deserialized.shortint_key),
             #[cfg(feature = "integer")]
@@ -142,6 +152,8 @@ impl CompressedServerKey {
         ServerKey {
             #[cfg(feature = "boolean")]
             boolean_key: Arc::new(self.boolean_key.decompress()),
+            #[cfg(all(feature = "boolean", feature = "fpga"))]
+            fpga_bool: None,
             #[cfg(feature = "shortint")]
             shortint_key: Arc::new(self.shortint_key.decompress()),
             #[cfg(feature = "integer")]
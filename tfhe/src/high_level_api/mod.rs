@@ -33,7 +33,11 @@ pub use keys::{
 mod tests;
 
 #[cfg(feature = "boolean")]
-pub use crate::high_level_api::booleans::{CompressedFheBool, FheBool, FheBoolParameters};
+pub use crate::high_level_api::booleans::{
+    CompressedFheBool, FheBool, FheBoolParameters, FheBoolVec,
+};
+#[cfg(all(feature = "boolean", feature = "fpga"))]
+pub use crate::high_level_api::booleans::FpgaConfig;
 #[cfg(feature = "integer")]
 expand_pub_use_fhe_type!(
     pub use crate::high_level_api::integers{
@@ -0,0 +1,133 @@
+use crate::boolean::ciphertext::Ciphertext as BooleanCiphertext;
+use crate::boolean::client_key::ClientKey as BooleanClientKey;
+use crate::boolean::parameters::BooleanKeySwitchingParameters;
+use crate::integer::{ClientKey, RadixCiphertext, ServerKey};
+use crate::shortint::parameters::ShortintKeySwitchingParameters;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod test;
+
+/// A key converting vectors of boolean ciphertexts to radix ciphertexts, and back.
+///
+/// The bits are in little endian order: the first boolean ciphertext is the least significant
+/// bit of the first block, and each block holds as many bits as its message modulus.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BooleanKeySwitchingKey {
+    key: crate::shortint::key_switching_key::BooleanKeySwitchingKey,
+}
+
+impl BooleanKeySwitchingKey {
+    /// Generate a key converting the ciphertexts of `boolean_client_key` to the radix ciphertexts
+    /// of `key_pair` and back.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::boolean::parameters::{BooleanKeySwitchingParameters, DEFAULT_PARAMETERS};
+    /// use tfhe::integer::key_switching_key::BooleanKeySwitchingKey;
+    /// use tfhe::shortint::parameters::{
+    ///     ShortintKeySwitchingParameters, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
+    /// };
+    ///
+    /// let boolean_ck = tfhe::boolean::client_key::ClientKey::new(&DEFAULT_PARAMETERS);
+    /// let (ck, sk) = tfhe::integer::gen_keys_radix(PARAM_MESSAGE_2_CARRY_2_KS_PBS, 4);
+    ///
+    /// let ksk = BooleanKeySwitchingKey::new(
+    ///     &boolean_ck,
+    ///     (&ck, &sk),
+    ///     ShortintKeySwitchingParameters::new(
+    ///         ck.parameters().ks_base_log(),
+    ///         ck.parameters().ks_level(),
+    ///     ),
+    ///     BooleanKeySwitchingParameters::new(
+    ///         DEFAULT_PARAMETERS.ks_base_log,
+    ///         DEFAULT_PARAMETERS.ks_level,
+    ///     ),
+    /// );
+    ///
+    /// let msg = 0b1011_0110u8;
+    /// let bits: Vec<_> = (0..8).map(|i| boolean_ck.encrypt((msg >> i) & 1 == 1)).collect();
+    ///
+    /// let ct = ksk.cast_from_boolean(&bits);
+    /// let dec: u8 = ck.decrypt(&ct);
+    /// assert_eq!(dec, msg);
+    ///
+    /// let bits = ksk.cast_to_boolean(&ct);
+    /// let dec = bits
+    ///     .iter()
+    ///     .rev()
+    ///     .fold(0u8, |acc, bit| (acc << 1) | boolean_ck.decrypt(bit) as u8);
+    /// assert_eq!(dec, msg);
+    /// ```
+    pub fn new<ClientKeyType>(
+        boolean_client_key: &BooleanClientKey,
+        key_pair: (&ClientKeyType, &ServerKey),
+        to_integer_params: ShortintKeySwitchingParameters,
+        to_boolean_params: BooleanKeySwitchingParameters,
+    ) -> Self
+    where
+        ClientKeyType: AsRef<ClientKey>,
+    {
+        let client_key = &key_pair.0.as_ref().key;
+        if !client_key.parameters.message_modulus().0.is_power_of_two() {
+            panic!("Cannot convert boolean ciphertexts to blocks of a non power of 2 modulus");
+        }
+
+        Self {
+            key: crate::shortint::key_switching_key::BooleanKeySwitchingKey::new(
+                boolean_client_key,
+                (client_key, &key_pair.1.key),
+                to_integer_params,
+                to_boolean_params,
+            ),
+        }
+    }
+
+    fn bits_per_block(&self) -> usize {
+        self.key.server_key.message_modulus.0.ilog2() as usize
+    }
+
+    /// Convert boolean ciphertexts to a radix ciphertext with empty carries, of as many blocks as
+    /// needed to hold all the bits.
+    pub fn cast_from_boolean(&self, bits: &[BooleanCiphertext]) -> RadixCiphertext {
+        let blocks = bits
+            .par_chunks(self.bits_per_block())
+            .map(|block_bits| {
+                block_bits
+                    .iter()
+                    .enumerate()
+                    .map(|(shift, bit)| self.key.cast_from_boolean_shifted(bit, shift))
+                    // The bits are disjoint, their sum does not reach the carry
+                    .reduce(|mut block, bit| {
+                        self.key.server_key.unchecked_add_assign(&mut block, &bit);
+                        block
+                    })
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        RadixCiphertext::from(blocks)
+    }
+
+    /// Convert each bit of the message of a radix ciphertext to a boolean ciphertext.
+    ///
+    /// The carries of the ciphertext must be empty.
+    pub fn cast_to_boolean(&self, ct: &RadixCiphertext) -> Vec<BooleanCiphertext> {
+        assert!(
+            ct.block_carries_are_empty(),
+            "The carries must be propagated before the conversion to boolean ciphertexts"
+        );
+
+        let bits_per_block = self.bits_per_block();
+        ct.blocks
+            .par_iter()
+            .flat_map(|block| {
+                (0..bits_per_block)
+                    .into_par_iter()
+                    .map(|index| self.key.cast_bit_to_boolean(block, index))
+            })
+            .collect()
+    }
+}
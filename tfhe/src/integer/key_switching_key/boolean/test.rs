@@ -0,0 +1,116 @@
+use crate::boolean::keycache::KEY_CACHE as BOOLEAN_KEY_CACHE;
+use crate::boolean::parameters::{
+    BooleanKeySwitchingParameters, BooleanParameters, DEFAULT_PARAMETERS,
+    DEFAULT_PARAMETERS_KS_PBS, TFHE_LIB_PARAMETERS,
+};
+use crate::boolean::server_key::BinaryBooleanGates;
+use crate::integer::key_switching_key::BooleanKeySwitchingKey;
+use crate::integer::keycache::KEY_CACHE;
+use crate::integer::RadixClientKey;
+use crate::shortint::parameters::{
+    PBSParameters, ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS,
+    PARAM_MESSAGE_2_CARRY_2_KS_PBS, PARAM_MESSAGE_2_CARRY_2_PBS_KS,
+};
+use rand::Rng;
+
+const NB_TEST: usize = 4;
+
+fn boolean_to_radix_round_trip<P>(boolean_params: BooleanParameters, params: P)
+where
+    P: Into<PBSParameters>,
+{
+    let boolean_keys = BOOLEAN_KEY_CACHE.get_from_param(boolean_params);
+    let (boolean_cks, boolean_sks) = (boolean_keys.client_key(), boolean_keys.server_key());
+    let (cks, sks) = KEY_CACHE.get_from_params(params);
+
+    let ksk = BooleanKeySwitchingKey::new(
+        boolean_cks,
+        (&cks, &sks),
+        ShortintKeySwitchingParameters::new(
+            cks.parameters().ks_base_log(),
+            cks.parameters().ks_level(),
+        ),
+        BooleanKeySwitchingParameters::new(boolean_params.ks_base_log, boolean_params.ks_level),
+    );
+
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..NB_TEST {
+        let clear = rng.gen::<u8>();
+        let bits: Vec<_> = (0..u8::BITS)
+            .map(|index| boolean_cks.encrypt((clear >> index) & 1 == 1))
+            .collect();
+
+        let ct = ksk.cast_from_boolean(&bits);
+        assert!(ct.block_carries_are_empty());
+        let dec: u8 = cks.decrypt_radix(&ct);
+        assert_eq!(dec, clear);
+
+        // The bits come back after some integer arithmetic
+        let scalar = rng.gen::<u8>();
+        let sum = sks.scalar_add_parallelized(&ct, scalar);
+        let sum_bits = ksk.cast_to_boolean(&sum);
+        assert_eq!(sum_bits.len(), bits.len());
+        let expected = clear.wrapping_add(scalar);
+        for (index, bit) in sum_bits.iter().enumerate() {
+            assert_eq!(boolean_cks.decrypt(bit), (expected >> index) & 1 == 1);
+        }
+
+        // and are usable in gates
+        let and = boolean_sks.and(&sum_bits[0], &bits[0]);
+        assert_eq!(boolean_cks.decrypt(&and), expected & clear & 1 == 1);
+    }
+}
+
+#[test]
+fn test_boolean_to_radix_default_1_1() {
+    boolean_to_radix_round_trip(DEFAULT_PARAMETERS, PARAM_MESSAGE_1_CARRY_1_KS_PBS);
+}
+
+#[test]
+fn test_boolean_to_radix_default_2_2() {
+    boolean_to_radix_round_trip(DEFAULT_PARAMETERS, PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+}
+
+#[test]
+fn test_boolean_to_radix_default_ks_pbs_2_2_pbs_ks() {
+    boolean_to_radix_round_trip(DEFAULT_PARAMETERS_KS_PBS, PARAM_MESSAGE_2_CARRY_2_PBS_KS);
+}
+
+#[test]
+fn test_boolean_to_radix_tfhe_lib_2_2() {
+    boolean_to_radix_round_trip(TFHE_LIB_PARAMETERS, PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+}
+
+#[test]
+fn test_boolean_to_radix_partial_block() {
+    let boolean_keys = BOOLEAN_KEY_CACHE.get_from_param(DEFAULT_PARAMETERS);
+    let boolean_cks = boolean_keys.client_key();
+    let (cks, sks) = KEY_CACHE.get_from_params(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    let cks = RadixClientKey::from((cks, 2));
+
+    let ksk = BooleanKeySwitchingKey::new(
+        boolean_cks,
+        (&cks, &sks),
+        ShortintKeySwitchingParameters::new(
+            cks.parameters().ks_base_log(),
+            cks.parameters().ks_level(),
+        ),
+        BooleanKeySwitchingParameters::new(
+            DEFAULT_PARAMETERS.ks_base_log,
+            DEFAULT_PARAMETERS.ks_level,
+        ),
+    );
+
+    // 3 bits fill a block and a half
+    let bits: Vec<_> = [true, false, true]
+        .iter()
+        .map(|&bit| boolean_cks.encrypt(bit))
+        .collect();
+    let ct = ksk.cast_from_boolean(&bits);
+    assert_eq!(ct.blocks.len(), 2);
+    let dec: u64 = cks.decrypt(&ct);
+    assert_eq!(dec, 0b101);
+
+    assert!(ksk.cast_from_boolean(&[]).blocks.is_empty());
+}
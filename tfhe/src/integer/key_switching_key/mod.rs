@@ -6,9 +6,14 @@ use crate::shortint::parameters::ShortintKeySwitchingParameters;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "boolean")]
+mod boolean;
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "boolean")]
+pub use boolean::BooleanKeySwitchingKey;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct KeySwitchingKey {
     key: crate::shortint::KeySwitchingKey,
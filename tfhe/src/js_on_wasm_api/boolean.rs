@@ -2,6 +2,10 @@ pub use crate::core_crypto::commons::math::random::Seed;
 use bincode;
 use wasm_bindgen::prelude::*;
 
+use crate::boolean::ciphertext::Ciphertext;
+use crate::boolean::engine::Gate;
+use crate::boolean::parameters::FPGA_BOOTSTRAP_PACKING;
+use crate::boolean::server_key::{PackedBinaryBooleanGates, ServerKey};
 use crate::core_crypto::commons::generators::DeterministicSeeder;
 use crate::core_crypto::prelude::ActivatedRandomGenerator;
 
@@ -22,6 +26,16 @@ pub struct BooleanPublicKey(pub(crate) crate::boolean::public_key::PublicKey);
 #[wasm_bindgen]
 pub struct BooleanCompressedServerKey(pub(crate) crate::boolean::server_key::CompressedServerKey);
 
+#[wasm_bindgen]
+pub struct BooleanServerKey(pub(crate) crate::boolean::server_key::ServerKey);
+
+/// The operands and the results of the packed gates.
+///
+/// JS arrays of [BooleanCiphertext] cannot be borrowed by the wasm functions, so a batch is built
+/// in the wasm memory with [Boolean::ciphertext_array_push].
+#[wasm_bindgen]
+pub struct BooleanCiphertextArray(pub(crate) Vec<crate::boolean::ciphertext::Ciphertext>);
+
 #[wasm_bindgen]
 pub struct Boolean {}
 
@@ -293,4 +307,196 @@ impl Boolean {
             .map_err(|e| wasm_bindgen::JsError::new(format!("{e:?}").as_str()))
             .map(BooleanCompressedServerKey)
     }
+
+    #[wasm_bindgen]
+    pub fn new_server_key(client_key: &BooleanClientKey) -> BooleanServerKey {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        BooleanServerKey(ServerKey::new(&client_key.0))
+    }
+
+    #[wasm_bindgen]
+    pub fn decompress_server_key(
+        compressed_server_key: &BooleanCompressedServerKey,
+    ) -> BooleanServerKey {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        BooleanServerKey(compressed_server_key.0.clone().into())
+    }
+
+    #[wasm_bindgen]
+    pub fn serialize_server_key(server_key: &BooleanServerKey) -> Result<Vec<u8>, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        bincode::serialize(&server_key.0)
+            .map_err(|e| wasm_bindgen::JsError::new(format!("{e:?}").as_str()))
+    }
+
+    #[wasm_bindgen]
+    pub fn deserialize_server_key(buffer: &[u8]) -> Result<BooleanServerKey, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        bincode::deserialize(buffer)
+            .map_err(|e| wasm_bindgen::JsError::new(format!("{e:?}").as_str()))
+            .map(BooleanServerKey)
+    }
+
+    #[wasm_bindgen]
+    pub fn new_ciphertext_array() -> BooleanCiphertextArray {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        BooleanCiphertextArray(Vec::new())
+    }
+
+    #[wasm_bindgen]
+    pub fn ciphertext_array_push(array: &mut BooleanCiphertextArray, ct: &BooleanCiphertext) {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        array.0.push(ct.0.clone());
+    }
+
+    #[wasm_bindgen]
+    pub fn ciphertext_array_get(
+        array: &BooleanCiphertextArray,
+        index: usize,
+    ) -> Result<BooleanCiphertext, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        array
+            .0
+            .get(index)
+            .cloned()
+            .map(BooleanCiphertext)
+            .ok_or_else(|| {
+                JsError::new(&format!(
+                    "Index {index} out of a BooleanCiphertextArray of length {}",
+                    array.0.len()
+                ))
+            })
+    }
+
+    #[wasm_bindgen]
+    pub fn ciphertext_array_len(array: &BooleanCiphertextArray) -> usize {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        array.0.len()
+    }
+
+    /// The elements of the array, as a JS array of [BooleanCiphertext].
+    #[wasm_bindgen]
+    pub fn ciphertext_array_to_js(array: &BooleanCiphertextArray) -> Vec<JsValue> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        array
+            .0
+            .iter()
+            .cloned()
+            .map(BooleanCiphertext)
+            .map(JsValue::from)
+            .collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn and_packed(
+        server_key: &BooleanServerKey,
+        cts_left: &BooleanCiphertextArray,
+        cts_right: &BooleanCiphertextArray,
+    ) -> Result<BooleanCiphertextArray, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        gates_packed(&server_key.0, Gate::AND, cts_left, cts_right).map(BooleanCiphertextArray)
+    }
+
+    #[wasm_bindgen]
+    pub fn or_packed(
+        server_key: &BooleanServerKey,
+        cts_left: &BooleanCiphertextArray,
+        cts_right: &BooleanCiphertextArray,
+    ) -> Result<BooleanCiphertextArray, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        gates_packed(&server_key.0, Gate::OR, cts_left, cts_right).map(BooleanCiphertextArray)
+    }
+
+    #[wasm_bindgen]
+    pub fn xor_packed(
+        server_key: &BooleanServerKey,
+        cts_left: &BooleanCiphertextArray,
+        cts_right: &BooleanCiphertextArray,
+    ) -> Result<BooleanCiphertextArray, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        gates_packed(&server_key.0, Gate::XOR, cts_left, cts_right).map(BooleanCiphertextArray)
+    }
+
+    #[wasm_bindgen]
+    pub fn nand_packed(
+        server_key: &BooleanServerKey,
+        cts_left: &BooleanCiphertextArray,
+        cts_right: &BooleanCiphertextArray,
+    ) -> Result<BooleanCiphertextArray, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        gates_packed(&server_key.0, Gate::AND, cts_left, cts_right)
+            .map(|cts| BooleanCiphertextArray(not_all(&server_key.0, &cts)))
+    }
+
+    #[wasm_bindgen]
+    pub fn nor_packed(
+        server_key: &BooleanServerKey,
+        cts_left: &BooleanCiphertextArray,
+        cts_right: &BooleanCiphertextArray,
+    ) -> Result<BooleanCiphertextArray, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        gates_packed(&server_key.0, Gate::OR, cts_left, cts_right)
+            .map(|cts| BooleanCiphertextArray(not_all(&server_key.0, &cts)))
+    }
+
+    #[wasm_bindgen]
+    pub fn xnor_packed(
+        server_key: &BooleanServerKey,
+        cts_left: &BooleanCiphertextArray,
+        cts_right: &BooleanCiphertextArray,
+    ) -> Result<BooleanCiphertextArray, JsError> {
+        set_hook(Box::new(console_error_panic_hook::hook));
+        gates_packed(&server_key.0, Gate::XOR, cts_left, cts_right)
+            .map(|cts| BooleanCiphertextArray(not_all(&server_key.0, &cts)))
+    }
+}
+
+// `gate` on every pair of ciphertexts, in batches of FPGA_BOOTSTRAP_PACKING bootstraps, which run
+// on the threads of the pool with `parallel-wasm-api`
+fn gates_packed(
+    server_key: &ServerKey,
+    gate: Gate,
+    cts_left: &BooleanCiphertextArray,
+    cts_right: &BooleanCiphertextArray,
+) -> Result<Vec<Ciphertext>, JsError> {
+    if cts_left.0.len() != cts_right.0.len() {
+        return Err(JsError::new(&format!(
+            "The packed gates need operands of the same length, got {} and {}",
+            cts_left.0.len(),
+            cts_right.0.len()
+        )));
+    }
+
+    let gates = vec![gate; FPGA_BOOTSTRAP_PACKING];
+    let batch = |(cts_left, cts_right): (&[Ciphertext], &[Ciphertext])| {
+        server_key.gates_packed(&gates[..cts_left.len()], cts_left, cts_right)
+    };
+
+    // No parallelism allowed
+    #[cfg(not(feature = "parallel-wasm-api"))]
+    let batches: Vec<Vec<Ciphertext>> = cts_left
+        .0
+        .chunks(FPGA_BOOTSTRAP_PACKING)
+        .zip(cts_right.0.chunks(FPGA_BOOTSTRAP_PACKING))
+        .map(batch)
+        .collect();
+
+    // Parallelism allowed
+    #[cfg(feature = "parallel-wasm-api")]
+    let batches: Vec<Vec<Ciphertext>> = {
+        use rayon::prelude::*;
+        cts_left
+            .0
+            .par_chunks(FPGA_BOOTSTRAP_PACKING)
+            .zip(cts_right.0.par_chunks(FPGA_BOOTSTRAP_PACKING))
+            .map(batch)
+            .collect()
+    };
+
+    Ok(batches.into_iter().flatten().collect())
+}
+
+// the negation of every ciphertext, which needs no bootstrap
+fn not_all(server_key: &ServerKey, cts: &[Ciphertext]) -> Vec<Ciphertext> {
+    cts.iter().map(|ct| server_key.not(ct)).collect()
 }
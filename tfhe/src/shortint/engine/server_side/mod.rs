@@ -215,6 +215,61 @@ impl ShortintEngine {
         ))
     }
 
+    /// Key switching key from the encryption key of a boolean client key, lifted to `u64`, to the
+    /// encryption key of a shortint client key.
+    #[cfg(feature = "boolean")]
+    pub(crate) fn new_key_switching_key_from_boolean(
+        &mut self,
+        boolean_cks: &crate::boolean::client_key::ClientKey,
+        cks: &ClientKey,
+        params: ShortintKeySwitchingParameters,
+    ) -> EngineResult<LweKeyswitchKeyOwned<u64>> {
+        let (boolean_lwe_sk, _) = boolean_encryption_key(boolean_cks);
+        let pbs_order: PBSOrder = cks.parameters.encryption_key_choice().into();
+        let (output_lwe_sk, output_noise) = match pbs_order {
+            PBSOrder::KeyswitchBootstrap => (
+                &cks.large_lwe_secret_key,
+                cks.parameters.glwe_modular_std_dev(),
+            ),
+            PBSOrder::BootstrapKeyswitch => (
+                &cks.small_lwe_secret_key,
+                cks.parameters.lwe_modular_std_dev(),
+            ),
+        };
+
+        Ok(allocate_and_generate_new_lwe_keyswitch_key(
+            &boolean_lwe_sk,
+            output_lwe_sk,
+            params.ks_base_log,
+            params.ks_level,
+            output_noise,
+            cks.parameters.ciphertext_modulus(),
+            &mut self.encryption_generator,
+        ))
+    }
+
+    /// Key switching key from the small key of a shortint client key to the encryption key of a
+    /// boolean client key, lifted to `u64`.
+    #[cfg(feature = "boolean")]
+    pub(crate) fn new_key_switching_key_to_boolean(
+        &mut self,
+        cks: &ClientKey,
+        boolean_cks: &crate::boolean::client_key::ClientKey,
+        params: crate::boolean::parameters::BooleanKeySwitchingParameters,
+    ) -> EngineResult<LweKeyswitchKeyOwned<u64>> {
+        let (boolean_lwe_sk, boolean_noise) = boolean_encryption_key(boolean_cks);
+
+        Ok(allocate_and_generate_new_lwe_keyswitch_key(
+            &cks.small_lwe_secret_key,
+            &boolean_lwe_sk,
+            params.ks_base_log,
+            params.ks_level,
+            boolean_noise,
+            cks.parameters.ciphertext_modulus(),
+            &mut self.encryption_generator,
+        ))
+    }
+
     pub(crate) fn new_compressed_server_key(
         &mut self,
         cks: &ClientKey,
@@ -976,3 +1031,28 @@ impl ShortintEngine {
         Ok(())
     }
 }
+
+/// The key boolean ciphertexts are encrypted under, with its bits lifted to `u64`, and the noise
+/// of its encryptions.
+#[cfg(feature = "boolean")]
+fn boolean_encryption_key(
+    boolean_cks: &crate::boolean::client_key::ClientKey,
+) -> (
+    LweSecretKeyOwned<u64>,
+    crate::core_crypto::commons::dispersion::StandardDev,
+) {
+    use crate::core_crypto::commons::parameters::EncryptionKeyChoice;
+
+    let (lwe_sk, noise) = match boolean_cks.parameters.encryption_key_choice {
+        EncryptionKeyChoice::Big => (
+            boolean_cks.glwe_secret_key.as_lwe_secret_key(),
+            boolean_cks.parameters.glwe_modular_std_dev,
+        ),
+        EncryptionKeyChoice::Small => (
+            LweSecretKey::from_container(boolean_cks.lwe_secret_key.as_ref()),
+            boolean_cks.parameters.lwe_modular_std_dev,
+        ),
+    };
+    let lifted: Vec<u64> = lwe_sk.as_ref().iter().map(|&bit| bit as u64).collect();
+    (LweSecretKey::from_container(lifted), noise)
+}
@@ -0,0 +1,270 @@
+//! Conversions between the ciphertexts of the [`boolean`](crate::boolean) layer and shortint
+//! ciphertexts.
+//!
+//! Boolean ciphertexts encrypt `true` as `1/8` and `false` as `-1/8` of a torus discretized on
+//! 32 bits, shortint ciphertexts encrypt their message `m` as `m * Δ` on 64 bits. A
+//! [`BooleanKeySwitchingKey`] moves a bit from one encoding to the other with a key switch and a
+//! programmable bootstrap of the shortint server key:
+//!
+//! * to shortint, the boolean ciphertext is lifted to 64 bits, key switched to the shortint
+//!   encryption key and shifted by `1/4 - Δ/2`, so that `false` lands on `1/8 - Δ/2` and `true`
+//!   on `3/8 - Δ/2`, the centres of the two halves of the message space. A lookup table then
+//!   reads in which half the bit falls.
+//! * to boolean, a lookup table maps the bit to `0` or `1/4`, which is shifted by `-1/8`, key
+//!   switched from the shortint small key to the boolean encryption key and rounded to 32 bits.
+//!
+//! Both ways, the bit sits `1/8` away from the boundaries, as in a boolean gate, whatever the size
+//! of the shortint message space. With [`DEFAULT_PARAMETERS`] and
+//! [`PARAM_MESSAGE_1_CARRY_1_KS_PBS`], the noise the shortint bootstrap reads has a standard
+//! deviation about 13 times smaller than this margin, for a failure probability around `2^-127`
+//! per conversion to shortint; with 2 bits of message and 2 bits of carry, it is more than 30
+//! times smaller.
+//!
+//! [`DEFAULT_PARAMETERS`]: crate::boolean::parameters::DEFAULT_PARAMETERS
+//! [`PARAM_MESSAGE_1_CARRY_1_KS_PBS`]: crate::shortint::parameters::PARAM_MESSAGE_1_CARRY_1_KS_PBS
+
+use crate::boolean::ciphertext::Ciphertext as BooleanCiphertext;
+use crate::boolean::client_key::ClientKey as BooleanClientKey;
+use crate::boolean::parameters::BooleanKeySwitchingParameters;
+use crate::core_crypto::prelude::{
+    keyswitch_lwe_ciphertext, lwe_ciphertext_plaintext_add_assign,
+    lwe_ciphertext_plaintext_sub_assign, CiphertextModulus, LweCiphertext, LweCiphertextOwned,
+    LweKeyswitchKeyOwned, Plaintext,
+};
+use crate::shortint::ciphertext::Degree;
+use crate::shortint::engine::ShortintEngine;
+use crate::shortint::parameters::ShortintKeySwitchingParameters;
+use crate::shortint::{Ciphertext, ClientKey, PBSOrder, ServerKey};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod test;
+
+/// A key converting boolean ciphertexts to shortint ciphertexts holding one bit, and back.
+///
+/// Like the [`KeySwitchingKey`](super::KeySwitchingKey), it is generated by the client, who owns
+/// both client keys, and is meant to be published to the server.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BooleanKeySwitchingKey {
+    /// From the boolean encryption key, lifted to 64 bits, to the shortint encryption key
+    pub(crate) to_shortint_key: LweKeyswitchKeyOwned<u64>,
+    /// From the shortint small key to the boolean encryption key, lifted to 64 bits
+    pub(crate) to_boolean_key: LweKeyswitchKeyOwned<u64>,
+    pub(crate) server_key: ServerKey,
+}
+
+impl BooleanKeySwitchingKey {
+    /// Generate a key converting the ciphertexts of `boolean_client_key` to the ciphertexts of
+    /// `key_pair` and back.
+    ///
+    /// The shortint parameters must have a native ciphertext modulus and a message space, carry
+    /// included, of at least 2 bits.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::boolean::parameters::{BooleanKeySwitchingParameters, DEFAULT_PARAMETERS};
+    /// use tfhe::shortint::key_switching_key::BooleanKeySwitchingKey;
+    /// use tfhe::shortint::parameters::{
+    ///     ShortintKeySwitchingParameters, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
+    /// };
+    ///
+    /// let boolean_ck = tfhe::boolean::client_key::ClientKey::new(&DEFAULT_PARAMETERS);
+    /// let (ck, sk) = tfhe::shortint::gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    ///
+    /// let ksk = BooleanKeySwitchingKey::new(
+    ///     &boolean_ck,
+    ///     (&ck, &sk),
+    ///     ShortintKeySwitchingParameters::new(
+    ///         ck.parameters.ks_base_log(),
+    ///         ck.parameters.ks_level(),
+    ///     ),
+    ///     BooleanKeySwitchingParameters::new(
+    ///         DEFAULT_PARAMETERS.ks_base_log,
+    ///         DEFAULT_PARAMETERS.ks_level,
+    ///     ),
+    /// );
+    ///
+    /// let ct = ksk.cast_from_boolean(&boolean_ck.encrypt(true));
+    /// assert_eq!(ck.decrypt(&ct), 1);
+    ///
+    /// let bit = ksk.cast_to_boolean(&ct);
+    /// assert!(boolean_ck.decrypt(&bit));
+    /// ```
+    pub fn new(
+        boolean_client_key: &BooleanClientKey,
+        key_pair: (&ClientKey, &ServerKey),
+        to_shortint_params: ShortintKeySwitchingParameters,
+        to_boolean_params: BooleanKeySwitchingParameters,
+    ) -> Self {
+        let (client_key, server_key) = key_pair;
+        assert!(
+            client_key
+                .parameters
+                .ciphertext_modulus()
+                .is_native_modulus(),
+            "Cannot convert boolean ciphertexts to shortint ciphertexts with a non native modulus"
+        );
+        assert!(
+            client_key.parameters.message_modulus().0 * client_key.parameters.carry_modulus().0
+                >= 4,
+            "Cannot convert boolean ciphertexts to shortint ciphertexts of less than 2 bits"
+        );
+
+        let (to_shortint_key, to_boolean_key) = ShortintEngine::with_thread_local_mut(|engine| {
+            (
+                engine
+                    .new_key_switching_key_from_boolean(
+                        boolean_client_key,
+                        client_key,
+                        to_shortint_params,
+                    )
+                    .unwrap(),
+                engine
+                    .new_key_switching_key_to_boolean(
+                        client_key,
+                        boolean_client_key,
+                        to_boolean_params,
+                    )
+                    .unwrap(),
+            )
+        });
+
+        Self {
+            to_shortint_key,
+            to_boolean_key,
+            server_key: server_key.clone(),
+        }
+    }
+
+    /// Convert a boolean ciphertext to a shortint ciphertext encrypting `1` for `true` and `0`
+    /// for `false`, with an empty carry.
+    pub fn cast_from_boolean(&self, ct: &BooleanCiphertext) -> Ciphertext {
+        self.cast_from_boolean_shifted(ct, 0)
+    }
+
+    /// Convert a boolean ciphertext to a shortint ciphertext encrypting `1 << shift` for `true`
+    /// and `0` for `false`.
+    pub(crate) fn cast_from_boolean_shifted(
+        &self,
+        ct: &BooleanCiphertext,
+        shift: usize,
+    ) -> Ciphertext {
+        let boolean_lwe = match ct {
+            BooleanCiphertext::Trivial(bit) => {
+                return self.server_key.create_trivial((*bit as u64) << shift)
+            }
+            BooleanCiphertext::Encrypted(boolean_lwe) => boolean_lwe,
+        };
+
+        let switched = self.keyswitch_from_boolean(boolean_lwe);
+
+        let full_message_modulus =
+            (self.server_key.message_modulus.0 * self.server_key.carry_modulus.0) as u64;
+        let mut ct = Ciphertext {
+            ct: switched,
+            degree: Degree(full_message_modulus as usize - 1),
+            message_modulus: self.server_key.message_modulus,
+            carry_modulus: self.server_key.carry_modulus,
+            pbs_order: self.server_key.pbs_order,
+        };
+        // The upper half of the message space is true
+        let lookup_table = self
+            .server_key
+            .generate_lookup_table(|x| ((2 * x >= full_message_modulus) as u64) << shift);
+        self.server_key
+            .apply_lookup_table_assign(&mut ct, &lookup_table);
+        ct
+    }
+
+    /// Key switch a boolean ciphertext to the shortint encryption key, with `false` and `true` at
+    /// the centres of the lower and upper halves of the message space.
+    pub(crate) fn keyswitch_from_boolean(
+        &self,
+        boolean_lwe: &LweCiphertextOwned<u32>,
+    ) -> LweCiphertextOwned<u64> {
+        let lifted = LweCiphertext::from_container(
+            boolean_lwe
+                .as_ref()
+                .iter()
+                .map(|&coefficient| (coefficient as u64) << u32::BITS)
+                .collect::<Vec<_>>(),
+            self.server_key.ciphertext_modulus,
+        );
+        let mut switched = LweCiphertext::new(
+            0u64,
+            self.to_shortint_key
+                .output_key_lwe_dimension()
+                .to_lwe_size(),
+            self.server_key.ciphertext_modulus,
+        );
+        keyswitch_lwe_ciphertext(&self.to_shortint_key, &lifted, &mut switched);
+
+        // The halves of the message space span [-Δ/2, 1/4 - Δ/2[ and [1/4 - Δ/2, 1/2 - Δ/2[ for the
+        // lookup table: false moves from -1/8 to 1/8 - Δ/2 and true from 1/8 to 3/8 - Δ/2
+        let full_message_modulus =
+            (self.server_key.message_modulus.0 * self.server_key.carry_modulus.0) as u64;
+        let half_delta = (1u64 << 62) / full_message_modulus;
+        lwe_ciphertext_plaintext_add_assign(&mut switched, Plaintext((1 << 62) - half_delta));
+        switched
+    }
+
+    /// Convert the least significant bit of the message of a shortint ciphertext to a boolean
+    /// ciphertext.
+    pub fn cast_to_boolean(&self, ct: &Ciphertext) -> BooleanCiphertext {
+        self.cast_bit_to_boolean(ct, 0)
+    }
+
+    /// Convert the bit `index` of the message of a shortint ciphertext to a boolean ciphertext.
+    pub(crate) fn cast_bit_to_boolean(&self, ct: &Ciphertext, index: usize) -> BooleanCiphertext {
+        let message_modulus = self.server_key.message_modulus.0 as u64;
+        let full_message_modulus = message_modulus * self.server_key.carry_modulus.0 as u64;
+
+        // The bit becomes 0 or 1/4
+        let lookup_table = self.server_key.generate_lookup_table(|x| {
+            (((x % message_modulus) >> index) & 1) * (full_message_modulus / 2)
+        });
+        let bootstrapped = self.server_key.apply_lookup_table(ct, &lookup_table);
+
+        let mut small = match bootstrapped.pbs_order {
+            PBSOrder::KeyswitchBootstrap => {
+                let mut small = LweCiphertext::new(
+                    0u64,
+                    self.server_key
+                        .key_switching_key
+                        .output_key_lwe_dimension()
+                        .to_lwe_size(),
+                    self.server_key.ciphertext_modulus,
+                );
+                keyswitch_lwe_ciphertext(
+                    &self.server_key.key_switching_key,
+                    &bootstrapped.ct,
+                    &mut small,
+                );
+                small
+            }
+            PBSOrder::BootstrapKeyswitch => bootstrapped.ct,
+        };
+        // false is -1/8 and true 1/8
+        lwe_ciphertext_plaintext_sub_assign(&mut small, Plaintext(1 << 61));
+
+        let mut switched = LweCiphertext::new(
+            0u64,
+            self.to_boolean_key.output_key_lwe_dimension().to_lwe_size(),
+            self.server_key.ciphertext_modulus,
+        );
+        keyswitch_lwe_ciphertext(&self.to_boolean_key, &small, &mut switched);
+
+        // Round each coefficient to its 32 most significant bits
+        let rounded: LweCiphertextOwned<u32> = LweCiphertext::from_container(
+            switched
+                .as_ref()
+                .iter()
+                .map(|&coefficient| (coefficient.wrapping_add(1 << 31) >> u32::BITS) as u32)
+                .collect(),
+            CiphertextModulus::new_native(),
+        );
+        BooleanCiphertext::Encrypted(rounded)
+    }
+}
@@ -0,0 +1,246 @@
+use crate::boolean::ciphertext::Ciphertext as BooleanCiphertext;
+use crate::boolean::keycache::KEY_CACHE as BOOLEAN_KEY_CACHE;
+use crate::boolean::parameters::{
+    BooleanKeySwitchingParameters, BooleanParameters, DEFAULT_PARAMETERS,
+    DEFAULT_PARAMETERS_KS_PBS, TFHE_LIB_PARAMETERS,
+};
+use crate::boolean::server_key::BinaryBooleanGates;
+use crate::core_crypto::prelude::{keyswitch_lwe_ciphertext, LweCiphertext};
+use crate::shortint::key_switching_key::BooleanKeySwitchingKey;
+use crate::shortint::keycache::KEY_CACHE;
+use crate::shortint::parameters::{
+    PBSParameters, ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS,
+    PARAM_MESSAGE_2_CARRY_2_KS_PBS, PARAM_MESSAGE_2_CARRY_2_PBS_KS,
+};
+use crate::shortint::PBSOrder;
+use rand::Rng;
+
+const NB_TEST: usize = 10;
+const NB_NOISE_SAMPLES: usize = 200;
+
+fn boolean_to_shortint_round_trip<P>(boolean_params: BooleanParameters, params: P)
+where
+    P: Into<PBSParameters>,
+{
+    let boolean_keys = BOOLEAN_KEY_CACHE.get_from_param(boolean_params);
+    let (boolean_cks, boolean_sks) = (boolean_keys.client_key(), boolean_keys.server_key());
+    let keys = KEY_CACHE.get_from_param(params);
+    let (cks, sks) = (keys.client_key(), keys.server_key());
+
+    let ksk = BooleanKeySwitchingKey::new(
+        boolean_cks,
+        (cks, sks),
+        ShortintKeySwitchingParameters::new(
+            cks.parameters.ks_base_log(),
+            cks.parameters.ks_level(),
+        ),
+        BooleanKeySwitchingParameters::new(boolean_params.ks_base_log, boolean_params.ks_level),
+    );
+
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..NB_TEST {
+        let clear = rng.gen::<bool>();
+
+        let ct = ksk.cast_from_boolean(&boolean_cks.encrypt(clear));
+        assert_eq!(cks.decrypt_message_and_carry(&ct), clear as u64);
+        assert_eq!(ct.degree.0, 1);
+
+        let back = ksk.cast_to_boolean(&ct);
+        assert_eq!(boolean_cks.decrypt(&back), clear);
+
+        // The converted ciphertext is usable in gates
+        let other = rng.gen::<bool>();
+        let xor = boolean_sks.xor(&back, &boolean_cks.encrypt(other));
+        assert_eq!(boolean_cks.decrypt(&xor), clear ^ other);
+    }
+}
+
+#[test]
+fn test_boolean_to_shortint_default_1_1() {
+    boolean_to_shortint_round_trip(DEFAULT_PARAMETERS, PARAM_MESSAGE_1_CARRY_1_KS_PBS);
+}
+
+#[test]
+fn test_boolean_to_shortint_default_2_2() {
+    boolean_to_shortint_round_trip(DEFAULT_PARAMETERS, PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+}
+
+#[test]
+fn test_boolean_to_shortint_default_ks_pbs_2_2_pbs_ks() {
+    boolean_to_shortint_round_trip(DEFAULT_PARAMETERS_KS_PBS, PARAM_MESSAGE_2_CARRY_2_PBS_KS);
+}
+
+#[test]
+fn test_boolean_to_shortint_tfhe_lib_2_2() {
+    boolean_to_shortint_round_trip(TFHE_LIB_PARAMETERS, PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+}
+
+#[test]
+fn test_boolean_to_shortint_trivial() {
+    let boolean_keys = BOOLEAN_KEY_CACHE.get_from_param(DEFAULT_PARAMETERS);
+    let (boolean_cks, boolean_sks) = (boolean_keys.client_key(), boolean_keys.server_key());
+    let keys = KEY_CACHE.get_from_param(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    let (cks, sks) = (keys.client_key(), keys.server_key());
+
+    let ksk = BooleanKeySwitchingKey::new(
+        boolean_cks,
+        (cks, sks),
+        ShortintKeySwitchingParameters::new(
+            cks.parameters.ks_base_log(),
+            cks.parameters.ks_level(),
+        ),
+        BooleanKeySwitchingParameters::new(
+            DEFAULT_PARAMETERS.ks_base_log,
+            DEFAULT_PARAMETERS.ks_level,
+        ),
+    );
+
+    for clear in [false, true] {
+        let ct = ksk.cast_from_boolean(&boolean_sks.trivial_encrypt(clear));
+        assert_eq!(cks.decrypt_message_and_carry(&ct), clear as u64);
+
+        let back = ksk.cast_to_boolean(&sks.create_trivial(clear as u64));
+        assert_eq!(boolean_cks.decrypt(&back), clear);
+    }
+}
+
+#[test]
+fn test_shortint_bits_to_boolean() {
+    let boolean_keys = BOOLEAN_KEY_CACHE.get_from_param(DEFAULT_PARAMETERS);
+    let boolean_cks = boolean_keys.client_key();
+    let keys = KEY_CACHE.get_from_param(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    let (cks, sks) = (keys.client_key(), keys.server_key());
+
+    let ksk = BooleanKeySwitchingKey::new(
+        boolean_cks,
+        (cks, sks),
+        ShortintKeySwitchingParameters::new(
+            cks.parameters.ks_base_log(),
+            cks.parameters.ks_level(),
+        ),
+        BooleanKeySwitchingParameters::new(
+            DEFAULT_PARAMETERS.ks_base_log,
+            DEFAULT_PARAMETERS.ks_level,
+        ),
+    );
+
+    let modulus = cks.parameters.message_modulus().0 as u64;
+    for clear in 0..modulus {
+        // The carry is ignored
+        let ct = cks.unchecked_encrypt(clear + modulus);
+        for index in 0..modulus.ilog2() as usize {
+            let bit = ksk.cast_bit_to_boolean(&ct, index);
+            assert_eq!(boolean_cks.decrypt(&bit), (clear >> index) & 1 == 1);
+        }
+
+        let shifted = ksk.cast_from_boolean_shifted(&boolean_cks.encrypt(clear & 1 == 1), 1);
+        assert_eq!(cks.decrypt_message_and_carry(&shifted), (clear & 1) << 1);
+    }
+}
+
+/// Measure the noise of the conversions to shortint where the shortint bootstrap reads it, after
+/// the key switch to the small key and the modulus switch, and check it stays far from the
+/// boundaries of the lookup table.
+fn cast_from_boolean_noise<P>(boolean_params: BooleanParameters, params: P)
+where
+    P: Into<PBSParameters>,
+{
+    let boolean_keys = BOOLEAN_KEY_CACHE.get_from_param(boolean_params);
+    let (boolean_cks, boolean_sks) = (boolean_keys.client_key(), boolean_keys.server_key());
+    let keys = KEY_CACHE.get_from_param(params);
+    let (cks, sks) = (keys.client_key(), keys.server_key());
+
+    let ksk = BooleanKeySwitchingKey::new(
+        boolean_cks,
+        (cks, sks),
+        ShortintKeySwitchingParameters::new(
+            cks.parameters.ks_base_log(),
+            cks.parameters.ks_level(),
+        ),
+        BooleanKeySwitchingParameters::new(boolean_params.ks_base_log, boolean_params.ks_level),
+    );
+
+    let full_message_modulus = (sks.message_modulus.0 * sks.carry_modulus.0) as i64;
+    let log_double_n = (2 * sks.bootstrapping_key.polynomial_size().0).ilog2();
+    let double_n = 1i64 << log_double_n;
+    let mut rng = rand::thread_rng();
+
+    let noises: Vec<f64> = (0..NB_NOISE_SAMPLES)
+        .map(|_| {
+            let clear = rng.gen::<bool>();
+            // A gate output, as the bits of a boolean circuit are
+            let ct = boolean_sks.xor(&boolean_cks.encrypt(clear), &boolean_cks.encrypt(false));
+            let BooleanCiphertext::Encrypted(boolean_lwe) = ct else {
+                unreachable!()
+            };
+
+            let switched = ksk.keyswitch_from_boolean(&boolean_lwe);
+            let small = match sks.pbs_order {
+                PBSOrder::KeyswitchBootstrap => {
+                    let mut small = LweCiphertext::new(
+                        0u64,
+                        sks.key_switching_key
+                            .output_key_lwe_dimension()
+                            .to_lwe_size(),
+                        sks.ciphertext_modulus,
+                    );
+                    keyswitch_lwe_ciphertext(&sks.key_switching_key, &switched, &mut small);
+                    small
+                }
+                PBSOrder::BootstrapKeyswitch => switched,
+            };
+
+            let modulus_switch = |coefficient: u64| {
+                (coefficient.wrapping_add(1 << (63 - log_double_n)) >> (64 - log_double_n)) as i64
+            };
+            let (mask, body) = small.get_mask_and_body();
+            let phase = mask
+                .as_ref()
+                .iter()
+                .zip(cks.small_lwe_secret_key.as_ref())
+                .fold(modulus_switch(*body.data), |phase, (&a, &s)| {
+                    phase - modulus_switch(a) * s as i64
+                });
+            // false at 1/8 - Δ/2, true at 3/8 - Δ/2
+            let expected =
+                double_n * (1 + 2 * clear as i64) / 8 - double_n / (4 * full_message_modulus);
+            let noise = (phase - expected).rem_euclid(double_n);
+            (if noise >= double_n / 2 {
+                noise - double_n
+            } else {
+                noise
+            }) as f64
+        })
+        .collect();
+
+    let variance = noises.iter().map(|noise| noise * noise).sum::<f64>() / noises.len() as f64;
+    // The bit is 2N / 8 away from the boundaries: at 10 standard deviations, a conversion fails
+    // with a probability below 2^-75
+    let margin = double_n as f64 / 8.;
+    assert!(
+        margin > 10. * variance.sqrt(),
+        "noise standard deviation {}, margin {margin}",
+        variance.sqrt()
+    );
+}
+
+#[test]
+fn test_cast_from_boolean_noise_default_1_1() {
+    cast_from_boolean_noise(DEFAULT_PARAMETERS, PARAM_MESSAGE_1_CARRY_1_KS_PBS);
+}
+
+#[test]
+fn test_cast_from_boolean_noise_default_2_2() {
+    cast_from_boolean_noise(DEFAULT_PARAMETERS, PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+}
+
+#[test]
+fn test_cast_from_boolean_noise_default_ks_pbs_2_2_pbs_ks() {
+    cast_from_boolean_noise(DEFAULT_PARAMETERS_KS_PBS, PARAM_MESSAGE_2_CARRY_2_PBS_KS);
+}
+
+#[test]
+fn test_cast_from_boolean_noise_tfhe_lib_2_2() {
+    cast_from_boolean_noise(TFHE_LIB_PARAMETERS, PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+}
@@ -1,6 +1,7 @@
 //! This module defines KeySwitchingKey
 //!
 //! - [KeySwitchingKey] allows switching the keys of a ciphertext, from a cleitn key to another.
+//! - [BooleanKeySwitchingKey] converts boolean ciphertexts to shortint ciphertexts, and back.
 
 use crate::shortint::engine::ShortintEngine;
 use crate::shortint::parameters::ShortintKeySwitchingParameters;
@@ -10,9 +11,14 @@ use crate::core_crypto::prelude::{keyswitch_lwe_ciphertext, LweKeyswitchKeyOwned
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "boolean")]
+mod boolean;
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "boolean")]
+pub use boolean::BooleanKeySwitchingKey;
+
 /// A structure containing the casting public key.
 ///
 /// The casting key is generated by the client and is meant to be published: the client
@@ -27,6 +27,7 @@ async function setup() {
     const demoNames = [
         'publicKeyTest',
         'compressedPublicKeyTest',
+        'booleanPackedGatesTest',
         'compactPublicKeyTest32BitBig',
         'compactPublicKeyTest32BitSmall',
         'compactPublicKeyTest256BitBig',
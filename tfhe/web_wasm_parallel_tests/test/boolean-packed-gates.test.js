@@ -0,0 +1,5 @@
+import { runTestAttachedToButton } from "./common.mjs";
+
+test('Boolean Packed Gates Test', async () => {
+    await runTestAttachedToButton('booleanPackedGatesTest')
+});
@@ -3,6 +3,8 @@ import * as Comlink from 'comlink';
 import init, {
     initThreadPool,
     init_panic_hook,
+    Boolean,
+    BooleanParameterSet,
     ShortintParametersName,
     ShortintParameters,
     TfheClientKey,
@@ -100,6 +102,45 @@ async function publicKeyTest() {
     assert_eq(decrypted, 255)
 }
 
+async function booleanPackedGatesTest() {
+    // batches of FPGA_BOOTSTRAP_PACKING gates, computed on the threads of the pool
+    const LENGTH = 4 * 23;
+
+    let params = Boolean.get_parameters(BooleanParameterSet.Default);
+    let clientKey = Boolean.new_client_key(params);
+
+    console.time('Boolean ServerKey Gen')
+    let serverKey = Boolean.new_server_key(clientKey);
+    console.timeEnd('Boolean ServerKey Gen')
+
+    let lefts = [];
+    let rights = [];
+    let ctsLeft = Boolean.new_ciphertext_array();
+    let ctsRight = Boolean.new_ciphertext_array();
+    for (let i = 0; i < LENGTH; i++) {
+        lefts.push(i % 2 == 1);
+        rights.push(Math.floor(i / 2) % 2 == 1);
+        Boolean.ciphertext_array_push(ctsLeft, Boolean.encrypt(clientKey, lefts[i]));
+        Boolean.ciphertext_array_push(ctsRight, Boolean.encrypt(clientKey, rights[i]));
+    }
+
+    console.time(`Boolean xor_packed ${LENGTH} gates`)
+    let results = Boolean.xor_packed(serverKey, ctsLeft, ctsRight);
+    console.timeEnd(`Boolean xor_packed ${LENGTH} gates`)
+
+    let decrypted = Boolean.ciphertext_array_to_js(results)
+        .map((ct) => Boolean.decrypt(clientKey, ct));
+    for (let i = 0; i < LENGTH; i++) {
+        assert_eq(decrypted[i], lefts[i] != rights[i]);
+    }
+
+    results = Boolean.nand_packed(serverKey, ctsLeft, ctsRight);
+    for (let i = 0; i < LENGTH; i++) {
+        let ct = Boolean.ciphertext_array_get(results, i);
+        assert_eq(Boolean.decrypt(clientKey, ct), !(lefts[i] && rights[i]));
+    }
+}
+
 const U32_MAX = 4294967295;
 
 async function compactPublicKeyTest32BitOnConfig(config) {
@@ -451,6 +492,7 @@ async function main() {
     return Comlink.proxy({
         publicKeyTest,
         compressedPublicKeyTest,
+        booleanPackedGatesTest,
         compactPublicKeyTest32BitSmall,
         compactPublicKeyTest32BitBig,
         compactPublicKeyTest256BitSmall,
@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=csrc/run_set_arg.c");
+    println!("cargo:rerun-if-changed=exports.map");
+
+    // The fake panics on misuse: let the panic unwind through the C frame up to the test
+    cc::Build::new()
+        .file("csrc/run_set_arg.c")
+        .flag("-fexceptions")
+        .compile("xrt_mock_run_set_arg");
+
+    // A Rust shared library only exports the Rust symbols: export the C one as well
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rustc-cdylib-link-arg=-Wl,--undefined=xrtRunSetArg");
+    println!("cargo:rustc-cdylib-link-arg=-Wl,--version-script={manifest_dir}/exports.map");
+}
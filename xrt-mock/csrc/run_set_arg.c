@@ -0,0 +1,24 @@
+// The variadic entry point of the fake XRT, which cannot be defined in Rust.
+//
+// Like XRT, it reads the value with the type the kernel declares for the argument, then hands it
+// to the fake implemented in Rust.
+#include <stdarg.h>
+
+int xrt_mock_run_arg_is_buffer(void *rhdl, int index);
+int xrt_mock_run_set_buffer_arg(void *rhdl, int index, void *bhdl);
+int xrt_mock_run_set_scalar_arg(void *rhdl, int index, int value);
+
+int xrtRunSetArg(void *rhdl, int index, ...) {
+  va_list args;
+  int status;
+
+  va_start(args, index);
+  if (xrt_mock_run_arg_is_buffer(rhdl, index)) {
+    status = xrt_mock_run_set_buffer_arg(rhdl, index, va_arg(args, void *));
+  } else {
+    status = xrt_mock_run_set_scalar_arg(rhdl, index, va_arg(args, int));
+  }
+  va_end(args);
+
+  return status;
+}
@@ -0,0 +1,322 @@
+//! The definitions of the fake XRT functions, exported with their C names and signatures.
+//!
+//! The variadic `xrtRunSetArg` is defined in C, on top of the `xrt_mock_run_*` functions.
+
+use crate::bindings::*;
+use crate::{with_state, Object, INJECTED_FAILURE_CODE};
+use std::collections::BTreeMap;
+use std::os::raw::{c_char, c_int, c_uchar, c_uint, c_void};
+
+fn status(fail: bool) -> c_int {
+    if fail {
+        INJECTED_FAILURE_CODE
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtDeviceOpen(_index: c_uint) -> xrtDeviceHandle {
+    with_state(|state| state.open("xrtDeviceOpen", Object::Device))
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtDeviceClose(dhdl: xrtDeviceHandle) -> c_int {
+    with_state(|state| state.close("xrtDeviceClose", "device", dhdl))
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtDeviceLoadXclbinHandle(
+    dhdl: xrtDeviceHandle,
+    xhdl: xrtXclbinHandle,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtDeviceLoadXclbinHandle";
+        assert!(matches!(state.object_mut(function, dhdl), Object::Device));
+        assert!(matches!(state.object_mut(function, xhdl), Object::Xclbin));
+        status(state.record(function, dhdl, 0))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtDeviceGetXclbinUUID(
+    dhdl: xrtDeviceHandle,
+    out: *mut c_uchar,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtDeviceGetXclbinUUID";
+        assert!(matches!(state.object_mut(function, dhdl), Object::Device));
+        std::ptr::write_bytes(out, 0xab, 16);
+        status(state.record(function, dhdl, 0))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtXclbinAllocFilename(
+    _filename: *const c_char,
+) -> xrtXclbinHandle {
+    with_state(|state| state.open("xrtXclbinAllocFilename", Object::Xclbin))
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtXclbinFreeHandle(xhdl: xrtXclbinHandle) -> c_int {
+    with_state(|state| state.close("xrtXclbinFreeHandle", "xclbin", xhdl))
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtPLKernelOpenExclusive(
+    dhdl: xrtDeviceHandle,
+    _xclbin_id: *const c_uchar,
+    _name: *const c_char,
+) -> xrtKernelHandle {
+    with_state(|state| {
+        state.open(
+            "xrtPLKernelOpenExclusive",
+            Object::Kernel {
+                device: dhdl as usize,
+            },
+        )
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtKernelClose(khdl: xrtKernelHandle) -> c_int {
+    with_state(|state| state.close("xrtKernelClose", "kernel", khdl))
+}
+
+/// Every argument of the fake kernel is connected to the memory bank of the same index.
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtKernelArgGroupId(khdl: xrtKernelHandle, argno: c_int) -> c_int {
+    with_state(|state| {
+        let function = "xrtKernelArgGroupId";
+        assert!(matches!(
+            state.object_mut(function, khdl),
+            Object::Kernel { .. }
+        ));
+        if state.record(function, khdl, 0) {
+            INJECTED_FAILURE_CODE
+        } else {
+            argno
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtRunOpen(khdl: xrtKernelHandle) -> xrtRunHandle {
+    with_state(|state| {
+        state.open(
+            "xrtRunOpen",
+            Object::Run {
+                kernel: khdl as usize,
+                buffer_args: BTreeMap::new(),
+                started: false,
+            },
+        )
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtRunClose(rhdl: xrtRunHandle) -> c_int {
+    with_state(|state| state.close("xrtRunClose", "run", rhdl))
+}
+
+/// The number of buffer arguments of the fake kernel, which come first: the following arguments
+/// are `int` scalars.
+const KERNEL_BUFFER_ARGS: c_int = 3;
+
+/// Whether the argument `index` of the kernel of a run is a buffer, for the variadic
+/// `xrtRunSetArg` to read its value with the right type.
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrt_mock_run_arg_is_buffer(
+    rhdl: xrtRunHandle,
+    index: c_int,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtRunSetArg";
+        match state.object_mut(function, rhdl) {
+            Object::Run { .. } => {}
+            object => panic!("{function} called on a {} handle", object.kind()),
+        }
+        assert!(
+            index >= 0,
+            "{function} called with the argument index {index}"
+        );
+        c_int::from(index < KERNEL_BUFFER_ARGS)
+    })
+}
+
+/// `xrtRunSetArg` for a buffer argument.
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrt_mock_run_set_buffer_arg(
+    rhdl: xrtRunHandle,
+    index: c_int,
+    bhdl: xrtBufferHandle,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtRunSetArg";
+        assert!(
+            matches!(
+                state.objects.get(&(bhdl as usize)),
+                Some(Object::Buffer { .. })
+            ),
+            "{function} called with a buffer that is not open"
+        );
+        if let Object::Run { buffer_args, .. } = state.object_mut(function, rhdl) {
+            buffer_args.insert(index, bhdl as usize);
+        }
+        status(state.record(function, rhdl, 0))
+    })
+}
+
+/// `xrtRunSetArg` for a scalar argument.
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrt_mock_run_set_scalar_arg(
+    rhdl: xrtRunHandle,
+    _index: c_int,
+    _value: c_int,
+) -> c_int {
+    with_state(|state| status(state.record("xrtRunSetArg", rhdl, 0)))
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtRunStart(rhdl: xrtRunHandle) -> c_int {
+    with_state(|state| {
+        let function = "xrtRunStart";
+        let buffers: Vec<usize> = match state.object_mut(function, rhdl) {
+            Object::Run {
+                buffer_args,
+                started,
+                ..
+            } => {
+                assert!(
+                    !*started,
+                    "{function} called on a run that is already started"
+                );
+                *started = true;
+                buffer_args.values().copied().collect()
+            }
+            object => panic!("{function} called on a {} handle", object.kind()),
+        };
+        for buffer in buffers {
+            match state.objects.get_mut(&buffer) {
+                Some(Object::Buffer {
+                    host_dirty,
+                    device_dirty,
+                    ..
+                }) => {
+                    assert!(
+                        !host_dirty.contains(&true),
+                        "{function} called before a written buffer was synced to the device"
+                    );
+                    device_dirty.fill(true);
+                }
+                _ => panic!("{function} called with a buffer argument that is not open"),
+            }
+        }
+        let fail = state.record(function, rhdl, 0);
+        if fail {
+            if let Object::Run { started, .. } = state.object_mut(function, rhdl) {
+                *started = false;
+            }
+        }
+        status(fail)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtRunWait(rhdl: xrtRunHandle) -> ert_cmd_state {
+    with_state(|state| {
+        let function = "xrtRunWait";
+        match state.object_mut(function, rhdl) {
+            Object::Run { started, .. } => {
+                assert!(*started, "{function} called on a run that is not started");
+                *started = false;
+            }
+            object => panic!("{function} called on a {} handle", object.kind()),
+        }
+        if state.record(function, rhdl, 0) {
+            ert_cmd_state_ERT_CMD_STATE_ERROR
+        } else {
+            ert_cmd_state_ERT_CMD_STATE_COMPLETED
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtBOAlloc(
+    dhdl: xrtDeviceHandle,
+    size: usize,
+    _flags: xrtBufferFlags,
+    _grp: xrtMemoryGroup,
+) -> xrtBufferHandle {
+    with_state(|state| {
+        state.open(
+            "xrtBOAlloc",
+            Object::Buffer {
+                device: dhdl as usize,
+                data: vec![0; size],
+                host_dirty: vec![false; size],
+                device_dirty: vec![false; size],
+            },
+        )
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtBOFree(bhdl: xrtBufferHandle) -> c_int {
+    with_state(|state| state.close("xrtBOFree", "buffer", bhdl))
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtBOWrite(
+    bhdl: xrtBufferHandle,
+    src: *const c_void,
+    size: usize,
+    seek: usize,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtBOWrite";
+        let (data, host_dirty, _) = state.buffer_mut(function, bhdl, size, seek);
+        std::ptr::copy_nonoverlapping(src as *const u8, data.as_mut_ptr(), size);
+        host_dirty.fill(true);
+        status(state.record(function, bhdl, size))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtBORead(
+    bhdl: xrtBufferHandle,
+    dst: *mut c_void,
+    size: usize,
+    skip: usize,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtBORead";
+        let (data, _, device_dirty) = state.buffer_mut(function, bhdl, size, skip);
+        assert!(
+            !device_dirty.contains(&true),
+            "{function} called before the buffer was synced from the device"
+        );
+        std::ptr::copy_nonoverlapping(data.as_ptr(), dst as *mut u8, size);
+        status(state.record(function, bhdl, size))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C-unwind" fn xrtBOSync(
+    bhdl: xrtBufferHandle,
+    dir: xclBOSyncDirection,
+    size: usize,
+    offset: usize,
+) -> c_int {
+    with_state(|state| {
+        let function = "xrtBOSync";
+        let (_, host_dirty, device_dirty) = state.buffer_mut(function, bhdl, size, offset);
+        match dir {
+            xclBOSyncDirection_XCL_BO_SYNC_BO_TO_DEVICE => host_dirty.fill(false),
+            xclBOSyncDirection_XCL_BO_SYNC_BO_FROM_DEVICE => device_dirty.fill(false),
+            _ => panic!("{function} called with an unknown direction {dir}"),
+        }
+        status(state.record(function, bhdl, size))
+    })
+}
@@ -0,0 +1,65 @@
+//! The declarations generated from the XRT headers, restricted to the subset the fake implements.
+//!
+//! Host code compiled against these declarations calls the fake through the C ABI, exactly as it
+//! calls XRT through the bindings generated at build time. The functions are declared
+//! `C-unwind` so that the panics of the fake on misuse reach the test that made the call.
+
+use std::os::raw::{c_char, c_int, c_uchar, c_uint, c_void};
+
+pub type xrtDeviceHandle = *mut c_void;
+pub type xrtXclbinHandle = *mut c_void;
+pub type xrtKernelHandle = *mut c_void;
+pub type xrtRunHandle = *mut c_void;
+pub type xrtBufferHandle = *mut c_void;
+pub type xrtBufferFlags = u64;
+pub type xrtMemoryGroup = u32;
+
+pub type xclBOSyncDirection = c_uint;
+pub const xclBOSyncDirection_XCL_BO_SYNC_BO_TO_DEVICE: xclBOSyncDirection = 0;
+pub const xclBOSyncDirection_XCL_BO_SYNC_BO_FROM_DEVICE: xclBOSyncDirection = 1;
+
+pub type ert_cmd_state = c_uint;
+pub const ert_cmd_state_ERT_CMD_STATE_NEW: ert_cmd_state = 1;
+pub const ert_cmd_state_ERT_CMD_STATE_COMPLETED: ert_cmd_state = 4;
+pub const ert_cmd_state_ERT_CMD_STATE_ERROR: ert_cmd_state = 5;
+
+extern "C-unwind" {
+    pub fn xrtDeviceOpen(index: c_uint) -> xrtDeviceHandle;
+    pub fn xrtDeviceClose(dhdl: xrtDeviceHandle) -> c_int;
+    pub fn xrtDeviceLoadXclbinHandle(dhdl: xrtDeviceHandle, xhdl: xrtXclbinHandle) -> c_int;
+    pub fn xrtDeviceGetXclbinUUID(dhdl: xrtDeviceHandle, out: *mut c_uchar) -> c_int;
+
+    pub fn xrtXclbinAllocFilename(filename: *const c_char) -> xrtXclbinHandle;
+    pub fn xrtXclbinFreeHandle(xhdl: xrtXclbinHandle) -> c_int;
+
+    pub fn xrtPLKernelOpenExclusive(
+        deviceHandle: xrtDeviceHandle,
+        xclbinId: *const c_uchar,
+        name: *const c_char,
+    ) -> xrtKernelHandle;
+    pub fn xrtKernelClose(kernelHandle: xrtKernelHandle) -> c_int;
+    pub fn xrtKernelArgGroupId(kernelHandle: xrtKernelHandle, argno: c_int) -> c_int;
+
+    pub fn xrtRunOpen(kernelHandle: xrtKernelHandle) -> xrtRunHandle;
+    pub fn xrtRunSetArg(rhdl: xrtRunHandle, index: c_int, ...) -> c_int;
+    pub fn xrtRunStart(rhdl: xrtRunHandle) -> c_int;
+    pub fn xrtRunWait(rhdl: xrtRunHandle) -> ert_cmd_state;
+    pub fn xrtRunClose(rhdl: xrtRunHandle) -> c_int;
+
+    pub fn xrtBOAlloc(
+        dhdl: xrtDeviceHandle,
+        size: usize,
+        flags: xrtBufferFlags,
+        grp: xrtMemoryGroup,
+    ) -> xrtBufferHandle;
+    pub fn xrtBOFree(bhdl: xrtBufferHandle) -> c_int;
+    pub fn xrtBOWrite(bhdl: xrtBufferHandle, src: *const c_void, size: usize, seek: usize)
+        -> c_int;
+    pub fn xrtBORead(bhdl: xrtBufferHandle, dst: *mut c_void, size: usize, skip: usize) -> c_int;
+    pub fn xrtBOSync(
+        bhdl: xrtBufferHandle,
+        dir: xclBOSyncDirection,
+        size: usize,
+        offset: usize,
+    ) -> c_int;
+}
@@ -0,0 +1,271 @@
+//! Fake of the subset of the XRT C API used to drive the FPT accelerator.
+//!
+//! The crate builds a shared library exporting the XRT functions with their C signatures, which
+//! stands for `libxrt_coreutil` at link or load time, and an rlib whose [`bindings`] are the
+//! declarations generated from the XRT headers, for tests linking the fake statically. Instead of
+//! talking to a card, every call is recorded and checked:
+//!
+//! * handles must be closed exactly once, with the function matching their kind, and a device can
+//!   only be closed once every object allocated on it has been released;
+//! * reads, writes and syncs must stay within the size of the buffer they target;
+//! * buffer arguments of a run must be open buffers, at the indices the kernel declares for them;
+//! * a run can only start once every buffer written by the host has been synced to the device, and
+//!   the host can only read a buffer the kernel may have written after syncing it back.
+//!
+//! Any of these violations is a bug in the host driver and panics. Runtime failures of the card
+//! are simulated with [`fail_call`] instead, which makes a chosen call return an error.
+//!
+//! The fake card does not compute anything: buffers keep what the host wrote to them, and a run
+//! leaves them untouched.
+//!
+//! Like a real card, the fake is shared by every thread of the process: tests driving it hold the
+//! guard returned by [`lock`] so that they do not see each other's handles and calls.
+
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+
+pub mod bindings;
+
+mod api;
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::os::raw::{c_int, c_void};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Status code returned by the calls made to fail with [`fail_call`].
+pub const INJECTED_FAILURE_CODE: c_int = -5;
+
+/// A call made to the fake XRT.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    pub function: &'static str,
+    /// The handle the call was made on, or the handle it returned for constructors, 0 if none.
+    pub handle: usize,
+    /// The number of bytes written, read or synced, 0 for other calls.
+    pub size: usize,
+}
+
+enum Object {
+    Device,
+    Xclbin,
+    Kernel {
+        device: usize,
+    },
+    Run {
+        kernel: usize,
+        /// The buffers bound to the buffer arguments, by index.
+        buffer_args: BTreeMap<c_int, usize>,
+        started: bool,
+    },
+    Buffer {
+        device: usize,
+        data: Vec<u8>,
+        /// Bytes written by the host since their last sync to the device.
+        host_dirty: Vec<bool>,
+        /// Bytes the kernel may have written since their last sync from the device.
+        device_dirty: Vec<bool>,
+    },
+}
+
+impl Object {
+    fn kind(&self) -> &'static str {
+        match self {
+            Object::Device => "device",
+            Object::Xclbin => "xclbin",
+            Object::Kernel { .. } => "kernel",
+            Object::Run { .. } => "run",
+            Object::Buffer { .. } => "buffer",
+        }
+    }
+
+    fn parent(&self) -> Option<usize> {
+        match self {
+            Object::Kernel { device } | Object::Buffer { device, .. } => Some(*device),
+            Object::Run { kernel, .. } => Some(*kernel),
+            Object::Device | Object::Xclbin => None,
+        }
+    }
+}
+
+struct State {
+    next_handle: usize,
+    objects: BTreeMap<usize, Object>,
+    calls: Vec<Call>,
+    /// For each function, the indices of its upcoming calls that must fail.
+    failures: BTreeMap<&'static str, Vec<usize>>,
+    call_counts: BTreeMap<&'static str, usize>,
+}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            next_handle: 0,
+            objects: BTreeMap::new(),
+            calls: Vec::new(),
+            failures: BTreeMap::new(),
+            call_counts: BTreeMap::new(),
+        }
+    }
+
+    /// Record a call, returning whether it must fail.
+    fn record(&mut self, function: &'static str, handle: *mut c_void, size: usize) -> bool {
+        self.calls.push(Call {
+            function,
+            handle: handle as usize,
+            size,
+        });
+
+        let count = self.call_counts.entry(function).or_insert(0);
+        let index = *count;
+        *count += 1;
+
+        match self.failures.get_mut(function) {
+            Some(failures) => {
+                let fail = failures.contains(&index);
+                failures.retain(|&nth| nth != index);
+                fail
+            }
+            None => false,
+        }
+    }
+
+    fn open(&mut self, function: &'static str, object: Object) -> *mut c_void {
+        if let Some(parent) = object.parent() {
+            assert!(
+                self.objects.contains_key(&parent),
+                "{function} called on a handle that is not open"
+            );
+        }
+        if self.record(function, std::ptr::null_mut(), 0) {
+            return std::ptr::null_mut();
+        }
+
+        self.next_handle += 1;
+        let handle = self.next_handle;
+        self.objects.insert(handle, object);
+        self.calls.last_mut().unwrap().handle = handle;
+        handle as *mut c_void
+    }
+
+    fn close(&mut self, function: &'static str, kind: &'static str, handle: *mut c_void) -> c_int {
+        let id = handle as usize;
+        match self.objects.get(&id) {
+            Some(object) if object.kind() == kind => {}
+            Some(object) => panic!("{function} called on a {} handle", object.kind()),
+            None => panic!("{function} called on a handle that is not open"),
+        }
+        let children = self
+            .objects
+            .values()
+            .filter(|object| object.parent() == Some(id))
+            .count();
+        assert!(
+            children == 0,
+            "{function} called while {children} objects created from it are still open"
+        );
+
+        let fail = self.record(function, handle, 0);
+        self.objects.remove(&id);
+        if fail {
+            INJECTED_FAILURE_CODE
+        } else {
+            0
+        }
+    }
+
+    fn object_mut(&mut self, function: &'static str, handle: *mut c_void) -> &mut Object {
+        self.objects
+            .get_mut(&(handle as usize))
+            .unwrap_or_else(|| panic!("{function} called on a handle that is not open"))
+    }
+
+    /// The data and dirty flags of `size` bytes at `offset` of a buffer, after checking that they
+    /// are within it.
+    fn buffer_mut(
+        &mut self,
+        function: &'static str,
+        handle: *mut c_void,
+        size: usize,
+        offset: usize,
+    ) -> (&mut [u8], &mut [bool], &mut [bool]) {
+        match self.object_mut(function, handle) {
+            Object::Buffer {
+                data,
+                host_dirty,
+                device_dirty,
+                ..
+            } => {
+                assert!(
+                    offset + size <= data.len(),
+                    "{function} accesses {size} bytes at offset {offset} of a {} bytes buffer",
+                    data.len()
+                );
+                let range = offset..offset + size;
+                (
+                    &mut data[range.clone()],
+                    &mut host_dirty[range.clone()],
+                    &mut device_dirty[range],
+                )
+            }
+            object => panic!("{function} called on a {} handle", object.kind()),
+        }
+    }
+}
+
+static STATE: Mutex<State> = Mutex::new(State::new());
+
+static CARD: Mutex<()> = Mutex::new(());
+
+// A panic of the fake on misuse, or of a test holding the card, leaves nothing half-updated that
+// the next user would trip on: the poisoning of the locks is ignored.
+
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    f(&mut STATE.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Take the fake card for the current test, with no handle, recorded call or pending failure,
+/// until the returned guard is dropped.
+///
+/// Objects driving the card must be dropped before the guard.
+pub fn lock() -> MutexGuard<'static, ()> {
+    let guard = CARD.lock().unwrap_or_else(PoisonError::into_inner);
+    reset();
+    guard
+}
+
+/// Forget every handle, recorded call and pending failure.
+pub fn reset() {
+    with_state(|state| *state = State::new());
+}
+
+/// The calls made so far.
+pub fn calls() -> Vec<Call> {
+    with_state(|state| state.calls.clone())
+}
+
+/// The names of the functions called so far, in order.
+pub fn call_names() -> Vec<&'static str> {
+    with_state(|state| state.calls.iter().map(|call| call.function).collect())
+}
+
+/// The kinds (`"device"`, `"xclbin"`, `"kernel"`, `"run"` or `"buffer"`) of the handles still
+/// open.
+pub fn open_handles() -> Vec<&'static str> {
+    with_state(|state| state.objects.values().map(Object::kind).collect())
+}
+
+/// Make the `nth` call to `function` from now on fail, counting from 0.
+///
+/// Constructors then return a null handle, `xrtRunWait` returns
+/// [`ert_cmd_state_ERT_CMD_STATE_ERROR`](bindings::ert_cmd_state_ERT_CMD_STATE_ERROR) and other
+/// functions return [`INJECTED_FAILURE_CODE`]. A failing close still releases the handle.
+pub fn fail_call(function: &'static str, nth: usize) {
+    with_state(|state| {
+        let next = state.call_counts.get(function).copied().unwrap_or(0);
+        state.failures.entry(function).or_default().push(next + nth);
+    });
+}
@@ -0,0 +1,181 @@
+use super::bindings::*;
+use super::*;
+
+unsafe fn open_buffer(size: usize) -> (xrtDeviceHandle, xrtBufferHandle) {
+    let device = xrtDeviceOpen(0);
+    let buffer = xrtBOAlloc(device, size, 0, 0);
+    (device, buffer)
+}
+
+unsafe fn open_run(device: xrtDeviceHandle, buffer: xrtBufferHandle) -> xrtRunHandle {
+    let mut uuid = [0u8; 16];
+    xrtDeviceGetXclbinUUID(device, uuid.as_mut_ptr());
+    let kernel = xrtPLKernelOpenExclusive(device, uuid.as_ptr(), std::ptr::null());
+    let run = xrtRunOpen(kernel);
+    xrtRunSetArg(run, 0, buffer);
+    run
+}
+
+#[test]
+fn test_buffer_round_trip() {
+    let _card = lock();
+    unsafe {
+        let (device, buffer) = open_buffer(8);
+        let run = open_run(device, buffer);
+
+        let input = [1u32, 2];
+        assert_eq!(xrtBOWrite(buffer, input.as_ptr() as *const c_void, 8, 0), 0);
+        xrtBOSync(buffer, xclBOSyncDirection_XCL_BO_SYNC_BO_TO_DEVICE, 8, 0);
+        assert_eq!(xrtRunStart(run), 0);
+        assert_eq!(xrtRunWait(run), ert_cmd_state_ERT_CMD_STATE_COMPLETED);
+        xrtBOSync(buffer, xclBOSyncDirection_XCL_BO_SYNC_BO_FROM_DEVICE, 8, 0);
+
+        let mut output = [0u32; 2];
+        assert_eq!(
+            xrtBORead(buffer, output.as_mut_ptr() as *mut c_void, 8, 0),
+            0
+        );
+        assert_eq!(output, input);
+    }
+
+    assert_eq!(
+        call_names(),
+        [
+            "xrtDeviceOpen",
+            "xrtBOAlloc",
+            "xrtDeviceGetXclbinUUID",
+            "xrtPLKernelOpenExclusive",
+            "xrtRunOpen",
+            "xrtRunSetArg",
+            "xrtBOWrite",
+            "xrtBOSync",
+            "xrtRunStart",
+            "xrtRunWait",
+            "xrtBOSync",
+            "xrtBORead",
+        ]
+    );
+    assert_eq!(open_handles().len(), 4);
+}
+
+#[test]
+#[should_panic(expected = "synced to the device")]
+fn test_start_before_sync_panics() {
+    let _card = lock();
+    unsafe {
+        let (device, buffer) = open_buffer(8);
+        let run = open_run(device, buffer);
+
+        xrtBOWrite(buffer, [0u8; 8].as_ptr() as *const c_void, 8, 0);
+        // Syncing half of the written bytes is not enough
+        xrtBOSync(buffer, xclBOSyncDirection_XCL_BO_SYNC_BO_TO_DEVICE, 4, 0);
+        xrtRunStart(run);
+    }
+}
+
+#[test]
+#[should_panic(expected = "synced from the device")]
+fn test_read_before_sync_panics() {
+    let _card = lock();
+    unsafe {
+        let (device, buffer) = open_buffer(8);
+        let run = open_run(device, buffer);
+
+        xrtRunStart(run);
+        xrtRunWait(run);
+        xrtBORead(buffer, [0u8; 8].as_mut_ptr() as *mut c_void, 8, 0);
+    }
+}
+
+#[test]
+#[should_panic(expected = "of a 8 bytes buffer")]
+fn test_out_of_bounds_write_panics() {
+    let _card = lock();
+    unsafe {
+        let (_, buffer) = open_buffer(8);
+        xrtBOWrite(buffer, [0u8; 16].as_ptr() as *const c_void, 8, 4);
+    }
+}
+
+#[test]
+#[should_panic(expected = "still open")]
+fn test_device_close_with_open_buffer_panics() {
+    let _card = lock();
+    unsafe {
+        let (device, _) = open_buffer(8);
+        xrtDeviceClose(device);
+    }
+}
+
+#[test]
+fn test_failure_injection() {
+    let _card = lock();
+    fail_call("xrtBOAlloc", 1);
+    unsafe {
+        let (device, first) = open_buffer(8);
+        assert!(!first.is_null());
+        assert!(xrtBOAlloc(device, 8, 0, 0).is_null());
+        assert!(!xrtBOAlloc(device, 8, 0, 0).is_null());
+
+        let run = open_run(device, first);
+        fail_call("xrtRunWait", 0);
+        assert_eq!(xrtRunStart(run), 0);
+        assert_eq!(xrtRunWait(run), ert_cmd_state_ERT_CMD_STATE_ERROR);
+    }
+    assert_eq!(
+        open_handles()
+            .iter()
+            .filter(|&&kind| kind == "buffer")
+            .count(),
+        2
+    );
+
+    reset();
+    assert!(open_handles().is_empty());
+    assert!(calls().is_empty());
+}
+
+#[test]
+fn test_scalar_argument() {
+    let _card = lock();
+    unsafe {
+        let (device, buffer) = open_buffer(8);
+        let run = open_run(device, buffer);
+        // The argument after the buffers is read as an int
+        assert_eq!(xrtRunSetArg(run, 3, 1 as c_int), 0);
+        assert_eq!(xrtRunStart(run), 0);
+    }
+    assert_eq!(
+        call_names()
+            .iter()
+            .filter(|&&call| call == "xrtRunSetArg")
+            .count(),
+        2
+    );
+}
+
+#[test]
+#[should_panic(expected = "called with a buffer that is not open")]
+fn test_set_closed_buffer_argument_panics() {
+    let _card = lock();
+    unsafe {
+        let (device, buffer) = open_buffer(8);
+        let run = open_run(device, buffer);
+        xrtBOFree(buffer);
+        xrtRunSetArg(run, 1, buffer);
+    }
+}
+
+#[test]
+fn test_state_is_shared_by_threads() {
+    let _card = lock();
+    let device = std::thread::spawn(|| unsafe { xrtDeviceOpen(0) as usize })
+        .join()
+        .unwrap();
+    assert_eq!(open_handles(), ["device"]);
+
+    unsafe {
+        assert_eq!(xrtDeviceClose(device as xrtDeviceHandle), 0);
+    }
+    assert!(open_handles().is_empty());
+}